@@ -0,0 +1,2 @@
+-- Remove consumed_at column from ContentUpload table
+ALTER TABLE "ContentUpload" DROP COLUMN IF EXISTS consumed_at;
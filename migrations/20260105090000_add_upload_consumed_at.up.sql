@@ -0,0 +1,2 @@
+-- Track when an upload has been turned into a chapter
+ALTER TABLE "ContentUpload" ADD COLUMN consumed_at TIMESTAMP(3);
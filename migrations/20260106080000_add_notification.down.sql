@@ -0,0 +1,6 @@
+-- Drop indexes
+DROP INDEX IF EXISTS idx_notification_user_unread;
+DROP INDEX IF EXISTS idx_notification_user_id;
+
+-- Drop table
+DROP TABLE IF EXISTS "Notification";
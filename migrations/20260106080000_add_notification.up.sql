@@ -0,0 +1,16 @@
+-- Create Notification table for the in-app notification inbox
+CREATE TABLE "Notification" (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    body TEXT NOT NULL,
+    novel_id TEXT,
+    chapter_id TEXT,
+    read_at TIMESTAMP(3),
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (user_id) REFERENCES "User"(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- Unread lookups are always scoped to a single user
+CREATE INDEX idx_notification_user_id ON "Notification"(user_id, created_at DESC);
+CREATE INDEX idx_notification_user_unread ON "Notification"(user_id) WHERE read_at IS NULL;
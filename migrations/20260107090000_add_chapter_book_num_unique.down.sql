@@ -0,0 +1 @@
+DROP INDEX IF EXISTS uq_chapter_book_num;
@@ -0,0 +1,2 @@
+-- A book can't have two chapters with the same number
+CREATE UNIQUE INDEX IF NOT EXISTS uq_chapter_book_num ON "Chapter"(book_id, chapter_num);
@@ -0,0 +1,5 @@
+-- Restore the original RESTRICT behaviour
+ALTER TABLE "BookGenre" DROP CONSTRAINT IF EXISTS "BookGenre_genre_id_fkey";
+ALTER TABLE "BookGenre"
+    ADD CONSTRAINT "BookGenre_genre_id_fkey"
+    FOREIGN KEY (genre_id) REFERENCES "Genre"(id) ON DELETE RESTRICT ON UPDATE CASCADE;
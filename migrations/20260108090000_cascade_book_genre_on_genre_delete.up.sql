@@ -0,0 +1,5 @@
+-- Deleting a genre removes its book associations instead of being blocked by them
+ALTER TABLE "BookGenre" DROP CONSTRAINT IF EXISTS "BookGenre_genre_id_fkey";
+ALTER TABLE "BookGenre"
+    ADD CONSTRAINT "BookGenre_genre_id_fkey"
+    FOREIGN KEY (genre_id) REFERENCES "Genre"(id) ON DELETE CASCADE ON UPDATE CASCADE;
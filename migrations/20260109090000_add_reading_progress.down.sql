@@ -0,0 +1,2 @@
+-- Drop ReadingProgress table
+DROP TABLE IF EXISTS "ReadingProgress";
@@ -0,0 +1,13 @@
+-- Track where each user left off in a book
+CREATE TABLE "ReadingProgress" (
+    user_id TEXT NOT NULL,
+    book_id TEXT NOT NULL,
+    chapter_id TEXT NOT NULL,
+    chapter_num INTEGER NOT NULL,
+    percent DOUBLE PRECISION NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (user_id, book_id),
+    FOREIGN KEY (user_id) REFERENCES "User"(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (chapter_id) REFERENCES "Chapter"(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
@@ -0,0 +1,16 @@
+-- Create Review table for book ratings and reviews
+CREATE TABLE "Review" (
+    id TEXT PRIMARY KEY,
+    user_id TEXT NOT NULL,
+    book_id TEXT NOT NULL,
+    rating SMALLINT NOT NULL CHECK (rating BETWEEN 1 AND 5),
+    body TEXT,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL,
+    FOREIGN KEY (user_id) REFERENCES "User"(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+-- One review per user per book
+CREATE UNIQUE INDEX uq_review_user_book ON "Review"(user_id, book_id);
+CREATE INDEX idx_review_book_id ON "Review"(book_id, created_at DESC);
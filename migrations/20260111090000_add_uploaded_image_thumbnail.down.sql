@@ -0,0 +1,2 @@
+-- Remove thumbnail_url from UploadedImage
+ALTER TABLE "UploadedImage" DROP COLUMN IF EXISTS thumbnail_url;
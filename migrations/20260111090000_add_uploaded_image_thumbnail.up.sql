@@ -0,0 +1,2 @@
+-- WebP preview generated alongside each extracted image
+ALTER TABLE "UploadedImage" ADD COLUMN thumbnail_url TEXT;
@@ -0,0 +1,11 @@
+-- Demote moderators and rebuild Role without the Moderator value
+UPDATE "User" SET role = 'User' WHERE role = 'Moderator';
+ALTER TABLE "User" ALTER COLUMN role DROP DEFAULT;
+ALTER TYPE Role RENAME TO role_old;
+CREATE TYPE Role AS ENUM (
+    'User',
+    'Admin'
+);
+ALTER TABLE "User" ALTER COLUMN role TYPE Role USING role::text::Role;
+ALTER TABLE "User" ALTER COLUMN role SET DEFAULT 'User';
+DROP TYPE role_old;
@@ -0,0 +1,2 @@
+-- Moderators can edit chapter content without full admin rights
+ALTER TYPE Role ADD VALUE IF NOT EXISTS 'Moderator';
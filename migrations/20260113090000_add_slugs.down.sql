@@ -0,0 +1,5 @@
+-- Remove book and genre slugs
+DROP INDEX IF EXISTS uq_genre_slug;
+DROP INDEX IF EXISTS uq_book_slug;
+ALTER TABLE "Genre" DROP COLUMN IF EXISTS slug;
+ALTER TABLE "Book" DROP COLUMN IF EXISTS slug;
@@ -0,0 +1,36 @@
+-- URL-friendly identifiers for books and genres
+ALTER TABLE "Book" ADD COLUMN slug TEXT;
+ALTER TABLE "Genre" ADD COLUMN slug TEXT;
+
+-- Backfill: strip to lowercase ASCII words, numbering duplicates by age
+WITH base AS (
+    SELECT id, created_at,
+           COALESCE(NULLIF(TRIM(BOTH '-' FROM regexp_replace(LOWER(title), '[^a-z0-9]+', '-', 'g')), ''), 'book') AS slug
+    FROM "Book"
+), ranked AS (
+    SELECT id, slug, ROW_NUMBER() OVER (PARTITION BY slug ORDER BY created_at, id) AS rn
+    FROM base
+)
+UPDATE "Book" b
+SET slug = CASE WHEN r.rn = 1 THEN r.slug ELSE r.slug || '-' || r.rn END
+FROM ranked r
+WHERE b.id = r.id;
+
+WITH base AS (
+    SELECT id, created_at,
+           COALESCE(NULLIF(TRIM(BOTH '-' FROM regexp_replace(LOWER(title), '[^a-z0-9]+', '-', 'g')), ''), 'genre') AS slug
+    FROM "Genre"
+), ranked AS (
+    SELECT id, slug, ROW_NUMBER() OVER (PARTITION BY slug ORDER BY created_at, id) AS rn
+    FROM base
+)
+UPDATE "Genre" g
+SET slug = CASE WHEN r.rn = 1 THEN r.slug ELSE r.slug || '-' || r.rn END
+FROM ranked r
+WHERE g.id = r.id;
+
+ALTER TABLE "Book" ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE "Genre" ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX uq_book_slug ON "Book"(slug);
+CREATE UNIQUE INDEX uq_genre_slug ON "Genre"(slug);
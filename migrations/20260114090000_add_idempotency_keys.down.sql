@@ -0,0 +1,3 @@
+-- Remove stored idempotent responses
+DROP INDEX IF EXISTS idx_idempotency_key_expires_at;
+DROP TABLE IF EXISTS "IdempotencyKey";
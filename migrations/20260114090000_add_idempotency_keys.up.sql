@@ -0,0 +1,14 @@
+-- Responses to POST requests retried with the same Idempotency-Key
+CREATE TABLE "IdempotencyKey" (
+    user_id TEXT NOT NULL,
+    key TEXT NOT NULL,
+    request_path TEXT NOT NULL,
+    -- NULL while the first request is still being handled
+    status_code INTEGER,
+    response_body BYTEA,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMP(3) NOT NULL,
+    PRIMARY KEY (user_id, key)
+);
+
+CREATE INDEX idx_idempotency_key_expires_at ON "IdempotencyKey"(expires_at);
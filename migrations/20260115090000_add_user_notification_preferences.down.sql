@@ -0,0 +1,2 @@
+-- Remove per-user notification preferences
+ALTER TABLE "User" DROP COLUMN IF EXISTS notify_new_chapters;
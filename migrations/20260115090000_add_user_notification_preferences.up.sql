@@ -0,0 +1,2 @@
+-- Lets readers opt out of new-chapter pushes for their bookmarks
+ALTER TABLE "User" ADD COLUMN notify_new_chapters BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,17 @@
+-- Enum values can't be dropped, so rebuild the type without them
+UPDATE "Book" SET language = 'Korean' WHERE language::TEXT IN ('Chinese', 'Indonesian');
+
+ALTER TYPE Language RENAME TO language_old;
+
+CREATE TYPE Language AS ENUM (
+    'English',
+    'Japanese',
+    'Korean'
+);
+
+ALTER TABLE "Book"
+    ALTER COLUMN language DROP DEFAULT,
+    ALTER COLUMN language TYPE Language USING language::TEXT::Language,
+    ALTER COLUMN language SET DEFAULT 'Korean';
+
+DROP TYPE language_old;
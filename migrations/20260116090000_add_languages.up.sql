@@ -0,0 +1,3 @@
+-- Books can now be tagged as Chinese or Indonesian
+ALTER TYPE Language ADD VALUE IF NOT EXISTS 'Chinese';
+ALTER TYPE Language ADD VALUE IF NOT EXISTS 'Indonesian';
@@ -0,0 +1,19 @@
+-- Enum values can't be dropped, so rebuild the type without Hiatus
+UPDATE "Book" SET status = 'Ongoing' WHERE status::TEXT = 'Hiatus';
+
+ALTER TYPE Status RENAME TO status_old;
+
+CREATE TYPE Status AS ENUM (
+    'Ongoing',
+    'Completed',
+    'Drop'
+);
+
+ALTER TABLE "Book"
+    ALTER COLUMN status DROP DEFAULT,
+    ALTER COLUMN status TYPE Status USING (
+        CASE status::TEXT WHEN 'Dropped' THEN 'Drop' ELSE status::TEXT END
+    )::Status,
+    ALTER COLUMN status SET DEFAULT 'Ongoing';
+
+DROP TYPE status_old;
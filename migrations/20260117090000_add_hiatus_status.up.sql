@@ -0,0 +1,3 @@
+-- Spell out the dropped status and add a hiatus state
+ALTER TYPE Status RENAME VALUE 'Drop' TO 'Dropped';
+ALTER TYPE Status ADD VALUE IF NOT EXISTS 'Hiatus';
@@ -0,0 +1,2 @@
+-- Collapse release dates back to their year
+ALTER TABLE "Book" ALTER COLUMN release_date TYPE INTEGER USING EXTRACT(YEAR FROM release_date)::INTEGER;
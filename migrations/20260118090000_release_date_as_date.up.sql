@@ -0,0 +1,4 @@
+-- release_date held a bare year; keep each one as January 1st of that year
+ALTER TABLE "Book" ALTER COLUMN release_date TYPE DATE USING (
+    CASE WHEN release_date BETWEEN 1 AND 9999 THEN make_date(release_date, 1, 1) END
+);
@@ -0,0 +1,2 @@
+-- Remove book view counter
+ALTER TABLE "Book" DROP COLUMN IF EXISTS views;
@@ -0,0 +1,2 @@
+-- Running count of (debounced) book page views
+ALTER TABLE "Book" ADD COLUMN views BIGINT NOT NULL DEFAULT 0;
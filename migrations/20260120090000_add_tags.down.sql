@@ -0,0 +1,3 @@
+-- Drop tag tables
+DROP TABLE IF EXISTS "BookTag";
+DROP TABLE IF EXISTS "Tag";
@@ -0,0 +1,19 @@
+-- Free-form tags, narrower than genres and created on first use
+CREATE TABLE "Tag" (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Names are stored lowercased, so a plain unique index is enough
+CREATE UNIQUE INDEX uq_tag_name ON "Tag"(name);
+
+CREATE TABLE "BookTag" (
+    book_id TEXT NOT NULL,
+    tag_id TEXT NOT NULL,
+    PRIMARY KEY (book_id, tag_id),
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    FOREIGN KEY (tag_id) REFERENCES "Tag"(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX idx_book_tag_tag_id ON "BookTag"(tag_id);
@@ -0,0 +1,5 @@
+-- Books keep their copied author name, so only the link needs removing
+ALTER TABLE "Book" DROP CONSTRAINT IF EXISTS "Book_author_id_fkey";
+DROP INDEX IF EXISTS idx_book_author_id;
+ALTER TABLE "Book" DROP COLUMN IF EXISTS author_id;
+DROP TABLE IF EXISTS "Author";
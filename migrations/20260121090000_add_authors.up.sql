@@ -0,0 +1,45 @@
+-- Authors become their own entity; "Book".author is kept as a copy of the resolved name
+CREATE TABLE "Author" (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Names differing only in case or surrounding whitespace are the same author
+CREATE UNIQUE INDEX uq_author_name ON "Author"(LOWER(name));
+
+-- Backfill: one author per distinct name, keeping the earliest spelling
+INSERT INTO "Author" (id, name)
+SELECT gen_random_uuid()::TEXT, name
+FROM (
+    SELECT DISTINCT ON (LOWER(TRIM(author))) TRIM(author) AS name
+    FROM "Book"
+    WHERE TRIM(author) <> ''
+    ORDER BY LOWER(TRIM(author)), created_at, id
+) names;
+
+ALTER TABLE "Book" ADD COLUMN author_id TEXT;
+
+UPDATE "Book" b
+SET author_id = a.id, author = a.name
+FROM "Author" a
+WHERE LOWER(a.name) = LOWER(TRIM(b.author));
+
+-- Books without any author name are attributed to a placeholder
+INSERT INTO "Author" (id, name)
+SELECT gen_random_uuid()::TEXT, 'Unknown'
+WHERE EXISTS (SELECT 1 FROM "Book" WHERE author_id IS NULL)
+ON CONFLICT (LOWER(name)) DO NOTHING;
+
+UPDATE "Book" b
+SET author_id = a.id, author = a.name
+FROM "Author" a
+WHERE b.author_id IS NULL AND LOWER(a.name) = 'unknown';
+
+ALTER TABLE "Book" ALTER COLUMN author_id SET NOT NULL;
+ALTER TABLE "Book"
+    ADD CONSTRAINT "Book_author_id_fkey"
+    FOREIGN KEY (author_id) REFERENCES "Author"(id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+CREATE INDEX idx_book_author_id ON "Book"(author_id, created_at DESC);
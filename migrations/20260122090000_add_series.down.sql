@@ -0,0 +1,7 @@
+-- Drop series grouping
+DROP INDEX IF EXISTS uq_book_series_position;
+ALTER TABLE "Book" DROP CONSTRAINT IF EXISTS chk_book_series_position;
+ALTER TABLE "Book" DROP CONSTRAINT IF EXISTS "Book_series_id_fkey";
+ALTER TABLE "Book" DROP COLUMN IF EXISTS series_position;
+ALTER TABLE "Book" DROP COLUMN IF EXISTS series_id;
+DROP TABLE IF EXISTS "Series";
@@ -0,0 +1,26 @@
+-- Ordered groupings of books, e.g. "Book 2 of 5"
+CREATE TABLE "Series" (
+    id TEXT PRIMARY KEY,
+    title TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMP(3) NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP(3) NOT NULL
+);
+
+CREATE UNIQUE INDEX uq_series_slug ON "Series"(slug);
+
+ALTER TABLE "Book" ADD COLUMN series_id TEXT;
+ALTER TABLE "Book" ADD COLUMN series_position INTEGER;
+
+ALTER TABLE "Book"
+    ADD CONSTRAINT "Book_series_id_fkey"
+    FOREIGN KEY (series_id) REFERENCES "Series"(id) ON DELETE RESTRICT ON UPDATE CASCADE;
+
+-- A position only means something inside a series, and positions start at 1
+ALTER TABLE "Book"
+    ADD CONSTRAINT chk_book_series_position
+    CHECK ((series_id IS NULL) = (series_position IS NULL) AND (series_position IS NULL OR series_position > 0));
+
+CREATE UNIQUE INDEX uq_book_series_position ON "Book"(series_id, series_position)
+    WHERE series_id IS NOT NULL;
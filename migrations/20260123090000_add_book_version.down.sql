@@ -0,0 +1,2 @@
+-- Drop optimistic locking version
+ALTER TABLE "Book" DROP COLUMN IF EXISTS version;
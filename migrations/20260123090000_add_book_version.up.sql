@@ -0,0 +1,2 @@
+-- Optimistic locking: every edit bumps the version, and patches must name the one they read
+ALTER TABLE "Book" ADD COLUMN version INTEGER NOT NULL DEFAULT 1;
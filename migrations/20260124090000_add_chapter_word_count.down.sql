@@ -0,0 +1,2 @@
+-- Drop chapter word counts
+ALTER TABLE "Chapter" DROP COLUMN IF EXISTS word_count;
@@ -0,0 +1,10 @@
+-- Stripped-text word count, recomputed by the API whenever content changes
+ALTER TABLE "Chapter" ADD COLUMN word_count INTEGER NOT NULL DEFAULT 0;
+
+-- Approximate backfill; the API's count also drops entities and script/style bodies
+UPDATE "Chapter" c
+SET word_count = (
+    SELECT COUNT(*)
+    FROM regexp_split_to_table(regexp_replace(c.content, '<[^>]*>', ' ', 'g'), '\s+') AS w
+    WHERE w ~ '[[:alnum:]]'
+);
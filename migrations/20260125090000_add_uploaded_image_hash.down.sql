@@ -0,0 +1,3 @@
+-- Remove content_hash from UploadedImage
+DROP INDEX IF EXISTS idx_uploaded_image_cdn_url;
+ALTER TABLE "UploadedImage" DROP COLUMN IF EXISTS content_hash;
@@ -0,0 +1,5 @@
+-- Extracted images are stored under their SHA-256, so uploads of the same content share objects
+ALTER TABLE "UploadedImage" ADD COLUMN content_hash TEXT;
+
+-- Deleting an upload checks whether other uploads still reference its objects
+CREATE INDEX idx_uploaded_image_cdn_url ON "UploadedImage"(cdn_url);
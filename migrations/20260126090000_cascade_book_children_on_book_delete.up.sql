@@ -0,0 +1,15 @@
+-- Deleting a book removes its chapters, bookmarks and genre links instead of being blocked by them
+ALTER TABLE "Chapter" DROP CONSTRAINT IF EXISTS "Chapter_book_id_fkey";
+ALTER TABLE "Chapter"
+    ADD CONSTRAINT "Chapter_book_id_fkey"
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE "Bookmark" DROP CONSTRAINT IF EXISTS "Bookmark_book_id_fkey";
+ALTER TABLE "Bookmark"
+    ADD CONSTRAINT "Bookmark_book_id_fkey"
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+ALTER TABLE "BookGenre" DROP CONSTRAINT IF EXISTS "BookGenre_book_id_fkey";
+ALTER TABLE "BookGenre"
+    ADD CONSTRAINT "BookGenre_book_id_fkey"
+    FOREIGN KEY (book_id) REFERENCES "Book"(id) ON DELETE CASCADE ON UPDATE CASCADE;
@@ -1,16 +1,93 @@
 use crate::errors::ConfigError;
+use crate::utils::field_limits::FieldLimits;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::env;
+use std::net::IpAddr;
+
+/// HS256 keys shorter than the hash output are trivially brute-forced
+const MIN_JWT_SECRET_LENGTH: usize = 32;
+
+/// Joins the documents of an extracted EPUB unless configured otherwise
+const DEFAULT_CONTENT_CHAPTER_BREAK: &str = "\n\n<!-- Chapter Break -->\n\n";
+
+const DEFAULT_ROBOTS_TXT: &str = "User-agent: *\nDisallow: /api/auth/\nDisallow: /api/upload/\n";
+
+/// Lowercase user-agent fragments treated as crawlers
+const DEFAULT_CRAWLER_USER_AGENTS: &[&str] = &[
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "facebookexternalhit",
+    "bingpreview",
+];
+
+/// File name patterns that mark EPUB front matter such as cover and copyright pages
+const DEFAULT_EPUB_BOILERPLATE_PATTERNS: &[&str] = &["cover", "titlepage", "copyright"];
+
+/// Browser origins allowed to call the API with credentials
+const DEFAULT_CORS_ALLOWED_ORIGINS: &[&str] = &[
+    "http://localhost:5173",
+    "http://localhost:3000",
+    "https://novel.wign.cloud",
+    "https://wign-realm.vercel.app",
+    "https://n-f-theta.vercel.app",
+    "https://n-f-git-main-wigns-projects.vercel.app",
+];
+
+const DEFAULT_CORS_ALLOWED_METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE", "OPTIONS"];
+
+/// How new-chapter pushes reach devices
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FcmDelivery {
+    /// One message per bookmarking user's device token
+    Tokens,
+    /// One message to the novel's `novel_{id}` topic; devices are subscribed on bookmark
+    Topic,
+}
+
+/// How extracted chapter HTML refers to its images
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentImageUrls {
+    /// Full CDN URLs, fixed to the CDN host at extraction time
+    Absolute,
+    /// `/api/images/{key}` paths, redirected to the current `cdn_url` when requested
+    Relative,
+}
+
+/// Heuristics for EPUB documents skipped when an extract asks to drop boilerplate
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EpubBoilerplate {
+    /// Lowercase prefixes matched against each word of a document's file name,
+    /// so `cover` catches `cover01.xhtml` but not `discovery.xhtml`
+    pub patterns: Vec<String>,
+    /// Documents with fewer words than this are skipped too; 0 disables the check
+    pub min_words: i32,
+}
+
+/// A client API key and the name it's logged under
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiKey {
+    pub label: String,
+    pub key: String,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Config {
     pub database_url: String,
-    pub redis_url: String,
+    pub db_max_connections: u32,
+    pub db_min_connections: u32,
+    /// How long a request waits for a pooled connection before failing with 503
+    pub db_acquire_timeout_secs: u64,
+    /// Caching, rate limiting and crawler limits are disabled when unset
+    pub redis_url: Option<String>,
     pub jwt_secret_key: String,
     pub jwt_expire_in: i64,
     pub jwt_refresh_expire_in: i64,
-    pub api_key: String,
+    pub api_keys: Vec<ApiKey>,
     pub email: String,
     pub password: String,
     pub cloudflare_api_token: String,
@@ -18,21 +95,63 @@ pub struct Config {
     pub s3_endpoint: String,
     pub s3_bucket: String,
     pub cdn_url: String,
+    pub content_image_urls: ContentImageUrls,
+    pub epub_boilerplate: EpubBoilerplate,
+    /// Placed between the documents of an extracted EPUB
+    pub content_chapter_break: String,
+    /// Wrap each extracted EPUB document in `<section data-chapter="n">`
+    pub content_chapter_sections: bool,
+    /// How many extracted images are uploaded to storage at once
+    pub image_upload_concurrency: usize,
+    /// Lifetime of presigned upload URLs, in seconds
+    pub presign_expires_in: i64,
     pub port: String,
     // FCM V1 API (optional)
+    /// Overrides the project id from the service account file
     pub fcm_project_id: Option<String>,
+    /// Path to the Firebase service account JSON; push is disabled when unset
     pub fcm_service_account_path: Option<String>,
+    /// Attempts per FCM request, retrying only network errors and 5xx responses
+    pub fcm_max_attempts: u32,
+    pub fcm_delivery: FcmDelivery,
+    /// Re-check elevated role claims against the database instead of trusting the token
+    pub auth_role_from_db: bool,
+    // Internal service-to-service token (optional)
+    pub internal_service_token: Option<String>,
+    pub field_limits: FieldLimits,
+    // Crawler controls
+    pub robots_txt: String,
+    pub crawler_user_agents: Vec<String>,
+    pub crawler_rate_limit_per_minute: i64,
+    // Global per-client token bucket
+    pub rate_limit_per_minute: i64,
+    pub rate_limit_burst: i64,
+    /// Reverse proxies whose `x-forwarded-for` hops are believed; empty means the
+    /// peer address is the client
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Trailing window of reading activity counted towards the popular ranking
+    pub popular_window_days: i64,
+    /// Reading speed behind the per-chapter reading time estimate
+    pub reading_wpm: u32,
+    // CORS
+    pub cors_allowed_origins: Vec<String>,
+    pub cors_allowed_methods: Vec<String>,
+    /// Development only: reflect any request origin instead of the allowlist
+    pub cors_allow_any_origin: bool,
 }
 
 impl Config {
     pub fn from_env() -> Result<Self, ConfigError> {
         Ok(Self {
             database_url: Self::get_env("DATABASE_URL")?,
-            redis_url: Self::get_env("REDIS_URL")?,
+            db_max_connections: Self::get_env_u32_or("DB_MAX_CONNECTIONS", 100)?,
+            db_min_connections: Self::get_env_u32_or("DB_MIN_CONNECTIONS", 10)?,
+            db_acquire_timeout_secs: Self::get_env_u64_or("DB_ACQUIRE_TIMEOUT_SECS", 5)?,
+            redis_url: Self::get_env_optional("REDIS_URL"),
             jwt_secret_key: Self::get_env("JWT_SECRET_KEY")?,
             jwt_expire_in: Self::get_env_i64("JWT_ACCESS_EXPIRES_IN")?,
             jwt_refresh_expire_in: Self::get_env_i64("JWT_REFRESH_EXPIRES_IN")?,
-            api_key: Self::get_env("API_KEY")?,
+            api_keys: Self::load_api_keys()?,
             email: Self::get_env("EMAIL")?,
             password: Self::get_env("PASSWORD")?,
             cloudflare_api_token: Self::get_env("AWS_ACCESS_KEY_ID")?,
@@ -40,10 +159,267 @@ impl Config {
             s3_endpoint: Self::get_env("AWS_ENDPOINT")?,
             s3_bucket: Self::get_env("AWS_BUCKET")?,
             cdn_url: Self::get_env("AWS_URL")?,
+            content_image_urls: Self::load_content_image_urls()?,
+            epub_boilerplate: EpubBoilerplate {
+                patterns: Self::get_env_list_or(
+                    "EPUB_BOILERPLATE_PATTERNS",
+                    DEFAULT_EPUB_BOILERPLATE_PATTERNS,
+                ),
+                min_words: Self::get_env_i32_or("EPUB_BOILERPLATE_MIN_WORDS", 10)?,
+            },
+            content_chapter_break: Self::get_env_optional("CONTENT_CHAPTER_BREAK")
+                .map(|v| v.replace("\\n", "\n"))
+                .unwrap_or_else(|| DEFAULT_CONTENT_CHAPTER_BREAK.to_string()),
+            content_chapter_sections: Self::get_env_bool_or("CONTENT_CHAPTER_SECTIONS", false)?,
+            image_upload_concurrency: Self::get_env_usize_or("IMAGE_UPLOAD_CONCURRENCY", 8)?,
+            presign_expires_in: Self::get_env_i64_or("PRESIGN_EXPIRES_IN", 15 * 60)?,
             port: Self::get_env("PORT")?,
             // FCM V1 API (optional - app still works without these)
             fcm_project_id: Self::get_env_optional("FCM_PROJECT_ID"),
-            fcm_service_account_path: Self::get_env_optional("GOOGLE_APPLICATION_CREDENTIALS"),
+            fcm_service_account_path: Self::get_env_optional("FCM_SERVICE_ACCOUNT_PATH")
+                .or_else(|| Self::get_env_optional("GOOGLE_APPLICATION_CREDENTIALS")),
+            fcm_max_attempts: Self::get_env_u32_or("FCM_MAX_ATTEMPTS", 3)?,
+            fcm_delivery: Self::load_fcm_delivery()?,
+            auth_role_from_db: Self::get_env_bool_or("AUTH_ROLE_FROM_DB", true)?,
+            // Lets background workers call protected routes without a user JWT
+            internal_service_token: Self::get_env_optional("INTERNAL_SERVICE_TOKEN"),
+            field_limits: Self::load_field_limits()?,
+            robots_txt: Self::get_env_optional("ROBOTS_TXT")
+                .map(|v| v.replace("\\n", "\n"))
+                .unwrap_or_else(|| DEFAULT_ROBOTS_TXT.to_string()),
+            crawler_user_agents: Self::get_env_list_or(
+                "CRAWLER_USER_AGENTS",
+                DEFAULT_CRAWLER_USER_AGENTS,
+            ),
+            crawler_rate_limit_per_minute: Self::get_env_i64_or(
+                "CRAWLER_RATE_LIMIT_PER_MINUTE",
+                30,
+            )?,
+            rate_limit_per_minute: Self::get_env_i64_or("RATE_LIMIT_PER_MINUTE", 300)?,
+            rate_limit_burst: Self::get_env_i64_or("RATE_LIMIT_BURST", 60)?,
+            trusted_proxies: Self::load_trusted_proxies()?,
+            popular_window_days: Self::get_env_i64_or("POPULAR_WINDOW_DAYS", 7)?,
+            reading_wpm: Self::get_env_u32_or("READING_WPM", 200)?,
+            cors_allowed_origins: Self::get_env_list_or(
+                "CORS_ALLOWED_ORIGINS",
+                DEFAULT_CORS_ALLOWED_ORIGINS,
+            ),
+            cors_allowed_methods: Self::get_env_list_or(
+                "CORS_ALLOWED_METHODS",
+                DEFAULT_CORS_ALLOWED_METHODS,
+            )
+            .into_iter()
+            .map(|method| method.to_uppercase())
+            .collect(),
+            cors_allow_any_origin: Self::get_env_bool_or("CORS_ALLOW_ANY_ORIGIN", false)?,
+        })
+    }
+
+    /// Reject values that are present but unusable, naming the offending variable
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.jwt_secret_key.len() < MIN_JWT_SECRET_LENGTH {
+            return Err(ConfigError::Invalid(
+                "JWT_SECRET_KEY".to_string(),
+                format!("must be at least {} bytes", MIN_JWT_SECRET_LENGTH),
+            ));
+        }
+
+        if self.db_max_connections == 0 {
+            return Err(ConfigError::Invalid(
+                "DB_MAX_CONNECTIONS".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+        if self.db_min_connections > self.db_max_connections {
+            return Err(ConfigError::Invalid(
+                "DB_MIN_CONNECTIONS".to_string(),
+                format!(
+                    "must not exceed DB_MAX_CONNECTIONS ({})",
+                    self.db_max_connections
+                ),
+            ));
+        }
+
+        if self.fcm_max_attempts == 0 {
+            return Err(ConfigError::Invalid(
+                "FCM_MAX_ATTEMPTS".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.rate_limit_per_minute <= 0 {
+            return Err(ConfigError::Invalid(
+                "RATE_LIMIT_PER_MINUTE".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+        if self.rate_limit_burst <= 0 {
+            return Err(ConfigError::Invalid(
+                "RATE_LIMIT_BURST".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.popular_window_days <= 0 {
+            return Err(ConfigError::Invalid(
+                "POPULAR_WINDOW_DAYS".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+        if self.epub_boilerplate.min_words < 0 {
+            return Err(ConfigError::Invalid(
+                "EPUB_BOILERPLATE_MIN_WORDS".to_string(),
+                "must not be negative".to_string(),
+            ));
+        }
+
+        if self.image_upload_concurrency == 0 {
+            return Err(ConfigError::Invalid(
+                "IMAGE_UPLOAD_CONCURRENCY".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+
+        if self.reading_wpm == 0 {
+            return Err(ConfigError::Invalid(
+                "READING_WPM".to_string(),
+                "must be greater than 0".to_string(),
+            ));
+        }
+
+        for origin in &self.cors_allowed_origins {
+            Self::validate_url("CORS_ALLOWED_ORIGINS", origin)?;
+            if origin.parse::<axum::http::HeaderValue>().is_err() {
+                return Err(ConfigError::Invalid(
+                    "CORS_ALLOWED_ORIGINS".to_string(),
+                    format!("{} is not a valid origin", origin),
+                ));
+            }
+        }
+        for method in &self.cors_allowed_methods {
+            if axum::http::Method::from_bytes(method.as_bytes()).is_err() {
+                return Err(ConfigError::Invalid(
+                    "CORS_ALLOWED_METHODS".to_string(),
+                    format!("{} is not a valid HTTP method", method),
+                ));
+            }
+        }
+
+        Self::validate_url("AWS_ENDPOINT", &self.s3_endpoint)?;
+        Self::validate_url("AWS_URL", &self.cdn_url)?;
+
+        self.port
+            .parse::<u16>()
+            .map_err(|e| ConfigError::Invalid("PORT".to_string(), e.to_string()))?;
+
+        Ok(())
+    }
+
+    fn validate_url(key: &str, value: &str) -> Result<(), ConfigError> {
+        let url = reqwest::Url::parse(value)
+            .map_err(|e| ConfigError::Invalid(key.to_string(), format!("{} ({})", e, value)))?;
+
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(ConfigError::Invalid(
+                key.to_string(),
+                format!("expected an http(s) URL, got {}", value),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// `API_KEY` is a comma-separated list of `label:key` or bare `key` entries;
+    /// bare keys are labelled by position (`key1`, `key2`, ...)
+    fn load_api_keys() -> Result<Vec<ApiKey>, ConfigError> {
+        let raw = Self::get_env("API_KEY")?;
+        let mut keys: Vec<ApiKey> = Vec::new();
+
+        for (index, entry) in raw.split(',').map(str::trim).enumerate() {
+            if entry.is_empty() {
+                continue;
+            }
+
+            let (label, key) = match entry.split_once(':') {
+                Some((label, key)) => (label.trim().to_string(), key.trim().to_string()),
+                None => (format!("key{}", index + 1), entry.to_string()),
+            };
+
+            if label.is_empty() || key.is_empty() {
+                return Err(ConfigError::Invalid(
+                    "API_KEY".to_string(),
+                    format!("entry {} must be `label:key` or `key`", index + 1),
+                ));
+            }
+            if keys.iter().any(|existing| existing.label == label) {
+                return Err(ConfigError::Invalid(
+                    "API_KEY".to_string(),
+                    format!("duplicate label {}", label),
+                ));
+            }
+
+            keys.push(ApiKey { label, key });
+        }
+
+        if keys.is_empty() {
+            return Err(ConfigError::MissingVar("API_KEY".to_string()));
+        }
+
+        Ok(keys)
+    }
+
+    fn load_fcm_delivery() -> Result<FcmDelivery, ConfigError> {
+        match Self::get_env_optional("FCM_DELIVERY")
+            .map(|v| v.trim().to_lowercase())
+            .as_deref()
+        {
+            None | Some("tokens") => Ok(FcmDelivery::Tokens),
+            Some("topic") => Ok(FcmDelivery::Topic),
+            Some(other) => Err(ConfigError::Invalid(
+                "FCM_DELIVERY".to_string(),
+                format!("{} is not one of tokens, topic", other),
+            )),
+        }
+    }
+
+    fn load_content_image_urls() -> Result<ContentImageUrls, ConfigError> {
+        match Self::get_env_optional("CONTENT_IMAGE_URLS")
+            .map(|v| v.trim().to_lowercase())
+            .as_deref()
+        {
+            None | Some("absolute") => Ok(ContentImageUrls::Absolute),
+            Some("relative") => Ok(ContentImageUrls::Relative),
+            Some(other) => Err(ConfigError::Invalid(
+                "CONTENT_IMAGE_URLS".to_string(),
+                format!("{} is not one of absolute, relative", other),
+            )),
+        }
+    }
+
+    fn load_trusted_proxies() -> Result<Vec<IpAddr>, ConfigError> {
+        Self::get_env_list_or("TRUSTED_PROXIES", &[])
+            .into_iter()
+            .map(|entry| {
+                entry.parse::<IpAddr>().map_err(|_| {
+                    ConfigError::Invalid(
+                        "TRUSTED_PROXIES".to_string(),
+                        format!("{} is not an IP address", entry),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    fn load_field_limits() -> Result<FieldLimits, ConfigError> {
+        let defaults = FieldLimits::default();
+        Ok(FieldLimits {
+            title: Self::get_env_usize_or("MAX_TITLE_LENGTH", defaults.title)?,
+            author: Self::get_env_usize_or("MAX_AUTHOR_LENGTH", defaults.author)?,
+            description: Self::get_env_usize_or("MAX_DESCRIPTION_LENGTH", defaults.description)?,
+            genre_name: Self::get_env_usize_or("MAX_GENRE_NAME_LENGTH", defaults.genre_name)?,
+            tag_name: Self::get_env_usize_or("MAX_TAG_NAME_LENGTH", defaults.tag_name)?,
+            username: Self::get_env_usize_or("MAX_USERNAME_LENGTH", defaults.username)?,
+            bio: Self::get_env_usize_or("MAX_BIO_LENGTH", defaults.bio)?,
         })
     }
 
@@ -57,6 +433,76 @@ impl Config {
             .map_err(|e| ConfigError::ParseError(key.to_string(), e))
     }
 
+    fn get_env_i64_or(key: &str, default: i64) -> Result<i64, ConfigError> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .parse::<i64>()
+                .map_err(|e| ConfigError::ParseError(key.to_string(), e)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_env_list_or(key: &str, default: &[&str]) -> Vec<String> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            None => default.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn get_env_usize_or(key: &str, default: usize) -> Result<usize, ConfigError> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .parse::<usize>()
+                .map_err(|e| ConfigError::ParseError(key.to_string(), e)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_env_i32_or(key: &str, default: i32) -> Result<i32, ConfigError> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .parse::<i32>()
+                .map_err(|e| ConfigError::ParseError(key.to_string(), e)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_env_u32_or(key: &str, default: u32) -> Result<u32, ConfigError> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .parse::<u32>()
+                .map_err(|e| ConfigError::ParseError(key.to_string(), e)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_env_u64_or(key: &str, default: u64) -> Result<u64, ConfigError> {
+        match Self::get_env_optional(key) {
+            Some(val) => val
+                .parse::<u64>()
+                .map_err(|e| ConfigError::ParseError(key.to_string(), e)),
+            None => Ok(default),
+        }
+    }
+
+    fn get_env_bool_or(key: &str, default: bool) -> Result<bool, ConfigError> {
+        match Self::get_env_optional(key).map(|v| v.to_lowercase()) {
+            Some(val) => match val.as_str() {
+                "true" | "1" | "yes" => Ok(true),
+                "false" | "0" | "no" => Ok(false),
+                _ => Err(ConfigError::Invalid(
+                    key.to_string(),
+                    format!("expected true or false, got {}", val),
+                )),
+            },
+            None => Ok(default),
+        }
+    }
+
     fn get_env_optional(key: &str) -> Option<String> {
         env::var(key).ok().filter(|v| !v.is_empty())
     }
@@ -1,7 +1,11 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
+use sqlx::{migrate::Migrator, postgres::PgPoolOptions, PgPool};
 use anyhow::Result;
+use std::time::Duration;
+use crate::config::Config;
 use crate::redis::RedisClient;
 
+/// Embedded so readiness can tell whether the schema is current
+static MIGRATOR: Migrator = sqlx::migrate!("./migrations");
 
 #[derive(Debug, Clone)]
 pub struct Database {
@@ -10,14 +14,35 @@ pub struct Database {
 }
 
 impl Database {
-    pub async fn new(database_url: &str, redis_url: &str) -> Result<Self> {
+    pub async fn new(config: &Config) -> Result<Self> {
+        tracing::info!(
+            max_connections = config.db_max_connections,
+            min_connections = config.db_min_connections,
+            acquire_timeout_secs = config.db_acquire_timeout_secs,
+            "Configuring database pool"
+        );
+
         let pool = PgPoolOptions::new()
-            .max_connections(100)
-            .min_connections(10)
-            .connect(database_url)
+            .max_connections(config.db_max_connections)
+            .min_connections(config.db_min_connections)
+            .acquire_timeout(Duration::from_secs(config.db_acquire_timeout_secs))
+            .connect(&config.database_url)
             .await?;
 
-        let redis = RedisClient::new(redis_url).await?;
+        // The API keeps serving from Postgres alone if the cache can't be reached
+        let redis = match config.redis_url.as_deref() {
+            Some(url) => match RedisClient::new(url).await {
+                Ok(redis) => redis,
+                Err(e) => {
+                    tracing::warn!(error = %e, "Redis unavailable, continuing without cache");
+                    RedisClient::disabled()
+                }
+            },
+            None => {
+                tracing::info!("REDIS_URL not set, caching and rate limiting are disabled");
+                RedisClient::disabled()
+            }
+        };
 
         Ok(Self { pool, redis })
     }
@@ -30,11 +55,32 @@ impl Database {
             .await?;
         
         tracing::info!("Database connection test successful");
-        
-        self.redis.test_connection().await?;
-        
+
+        // A cache outage degrades performance, not correctness
+        if self.redis.is_enabled() {
+            if let Err(e) = self.redis.test_connection().await {
+                tracing::warn!(error = %e, "Redis connection test failed");
+            }
+        }
+
         Ok(())
     }
+
+    /// Whether every bundled migration has been applied successfully
+    pub async fn migrations_applied(&self) -> Result<bool> {
+        let latest = match MIGRATOR.iter().map(|m| m.version).max() {
+            Some(version) => version,
+            None => return Ok(true),
+        };
+
+        let applied: Option<i64> = sqlx::query_scalar(
+            "SELECT MAX(version) FROM _sqlx_migrations WHERE success",
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(applied.is_some_and(|version| version >= latest))
+    }
 }
 
 pub async fn get_db_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
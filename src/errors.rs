@@ -2,6 +2,7 @@
 pub enum ConfigError {
     MissingVar(String),
     ParseError(String, std::num::ParseIntError),
+    Invalid(String, String),
 }
 
 impl std::fmt::Display for ConfigError {
@@ -11,6 +12,7 @@ impl std::fmt::Display for ConfigError {
                 write!(f, "Environment variable {} not configured", var)
             }
             ConfigError::ParseError(var, err) => write!(f, "Failed to parse {}: {}", var, err),
+            ConfigError::Invalid(var, reason) => write!(f, "Invalid {}: {}", var, reason),
         }
     }
 }
@@ -19,7 +21,7 @@ impl std::error::Error for ConfigError {}
 
 use axum::extract::rejection::JsonRejection;
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -30,7 +32,7 @@ use validator::ValidationErrors;
 #[derive(Error, Debug)]
 pub enum AppError {
     #[error("Database error: {0}")]
-    Database(#[from] sqlx::Error),
+    Database(sqlx::Error),
 
     #[error("JWT error: {0}")]
     Jwt(#[from] jsonwebtoken::errors::Error),
@@ -47,6 +49,12 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Unprocessable entity: {0}")]
+    Unprocessable(String),
+
+    #[error("Field {field} exceeds maximum length of {max}")]
+    FieldTooLong { field: String, max: usize },
+
     #[error("Unauthorized")]
     Unauthorized,
 
@@ -59,6 +67,18 @@ pub enum AppError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Email already exists")]
+    EmailExists,
+
+    #[error("Username already exists")]
+    UsernameExists,
+
+    #[error("Too many requests")]
+    TooManyRequests,
+
+    #[error("Too many requests")]
+    RateLimited { retry_after_secs: u64 },
+
     #[error("Bad request: {0}")]
     BadRequest(String),
 
@@ -70,11 +90,102 @@ pub enum AppError {
 
     #[error("HTTP request error: {0}")]
     Reqwest(#[from] reqwest::Error),
+
+    #[error("Invalid item at index {index}: {source}")]
+    InvalidBatchItem { index: usize, source: Box<AppError> },
+
+    #[error("Genre is still assigned to {book_count} books")]
+    GenreInUse { book_count: i64 },
 }
 
-impl IntoResponse for AppError {
-    fn into_response(self) -> Response {
-        let (status, error_message, details) = match self {
+/// Constraint violations are the client's fault, so they surface as 409/400
+/// rather than being reported as database failures
+impl From<sqlx::Error> for AppError {
+    fn from(e: sqlx::Error) -> Self {
+        if let sqlx::Error::Database(ref db_err) = e {
+            let constraint = db_err.constraint().unwrap_or("unknown");
+            if db_err.is_unique_violation() {
+                tracing::debug!(constraint, "Unique constraint violated");
+                return AppError::Conflict("Resource already exists".to_string());
+            }
+            if db_err.is_foreign_key_violation() {
+                tracing::debug!(constraint, "Foreign key constraint violated");
+                // Deletes that can trip a RESTRICT key map their own error through
+                // `map_still_referenced`; anything else points at a missing row
+                return AppError::BadRequest("Referenced resource does not exist".to_string());
+            }
+        }
+        AppError::Database(e)
+    }
+}
+
+/// A delete refused because other rows still point at the one being removed.
+/// Postgres reports both directions of a foreign key violation with the same code,
+/// so only the delete site knows which one it hit
+pub fn map_still_referenced(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_err) if db_err.is_foreign_key_violation() => {
+            let pg_err = db_err.try_downcast_ref::<sqlx::postgres::PgDatabaseError>();
+            let table = pg_err.and_then(|pg| pg.table());
+            tracing::debug!(
+                table,
+                constraint = pg_err.and_then(|pg| pg.constraint()),
+                "Delete blocked by a foreign key"
+            );
+            match table {
+                Some(table) => {
+                    AppError::Conflict(format!("Resource is still referenced by {} records", table))
+                }
+                None => {
+                    AppError::Conflict("Resource is still referenced by other records".to_string())
+                }
+            }
+        }
+        e => e.into(),
+    }
+}
+
+impl AppError {
+    /// Stable, machine-readable code sent to clients alongside the message
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::Database(sqlx::Error::PoolTimedOut) => "SERVICE_UNAVAILABLE",
+            AppError::Database(_)
+            | AppError::PasswordHash(_)
+            | AppError::InternalServer
+            | AppError::Internal(_) => "INTERNAL_ERROR",
+            AppError::Jwt(_) => "INVALID_TOKEN",
+            AppError::ValidationError(_) | AppError::Validation(_) => "VALIDATION_FAILED",
+            AppError::JsonRejection(_) => "INVALID_JSON",
+            AppError::Unprocessable(_) => "UNPROCESSABLE_ENTITY",
+            AppError::FieldTooLong { .. } => "FIELD_TOO_LONG",
+            AppError::Unauthorized => "UNAUTHORIZED",
+            AppError::Forbidden => "FORBIDDEN",
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::EmailExists => "EMAIL_EXISTS",
+            AppError::UsernameExists => "USERNAME_EXISTS",
+            AppError::TooManyRequests | AppError::RateLimited { .. } => "RATE_LIMITED",
+            AppError::BadRequest(_) => "BAD_REQUEST",
+            AppError::Reqwest(_) => "UPSTREAM_ERROR",
+            AppError::InvalidBatchItem { .. } => "INVALID_BATCH_ITEM",
+            AppError::GenreInUse { .. } => "GENRE_IN_USE",
+        }
+    }
+}
+
+impl AppError {
+    /// Status, client-facing message and extra fields merged into the error body
+    fn parts(&self) -> (StatusCode, String, Option<serde_json::Value>) {
+        match *self {
+            AppError::Database(sqlx::Error::PoolTimedOut) => {
+                tracing::warn!("Timed out waiting for a database connection");
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    "Service temporarily unavailable".to_string(),
+                    None,
+                )
+            }
             AppError::Database(ref e) => {
                 tracing::error!("Database error: {:?}", e);
                 (
@@ -84,25 +195,29 @@ impl IntoResponse for AppError {
                 )
             }
             AppError::ValidationError(ref errors) => {
-                let error_messages: Vec<String> = errors
-                    .field_errors()
-                    .into_iter()
-                    .flat_map(|(field, errors)| {
-                        errors.iter().map(move |error| {
-                            let message = error
+                let mut fields = serde_json::Map::new();
+                let mut error_messages: Vec<String> = Vec::new();
+
+                for (field, errors) in errors.field_errors() {
+                    let messages: Vec<String> = errors
+                        .iter()
+                        .map(|error| {
+                            error
                                 .message
                                 .as_ref()
                                 .map(|m| m.to_string())
-                                .unwrap_or_else(|| "Invalid value".to_string());
-                            format!("{}: {}", field, message)
+                                .unwrap_or_else(|| format!("Invalid value ({})", error.code))
                         })
-                    })
-                    .collect();
+                        .collect();
+
+                    error_messages.extend(messages.iter().map(|m| format!("{}: {}", field, m)));
+                    fields.insert(field.to_string(), json!(messages));
+                }
 
                 (
-                    StatusCode::BAD_REQUEST,
+                    StatusCode::UNPROCESSABLE_ENTITY,
                     "Validation failed".to_string(),
-                    Some(json!({"messages": error_messages})),
+                    Some(json!({"messages": error_messages, "fields": fields})),
                 )
             }
             AppError::JsonRejection(ref rejection) => (
@@ -117,10 +232,26 @@ impl IntoResponse for AppError {
                 None,
             ),
             AppError::Validation(ref msg) => (StatusCode::BAD_REQUEST, msg.clone(), None),
+            AppError::Unprocessable(ref msg) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, msg.clone(), None)
+            }
+            AppError::FieldTooLong { ref field, max } => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                format!("{} must be at most {} characters", field, max),
+                Some(json!({"field": field, "max_length": max})),
+            ),
             AppError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string(), None),
             AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden".to_string(), None),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.clone(), None),
             AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.clone(), None),
+            AppError::EmailExists | AppError::UsernameExists => {
+                (StatusCode::CONFLICT, self.to_string(), None)
+            }
+            AppError::TooManyRequests | AppError::RateLimited { .. } => (
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many requests".to_string(),
+                None,
+            ),
             AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.clone(), None),
             AppError::InternalServer => (
                 StatusCode::INTERNAL_SERVER_ERROR,
@@ -143,15 +274,44 @@ impl IntoResponse for AppError {
                     None,
                 )
             }
-        };
+            AppError::InvalidBatchItem { index, ref source } => {
+                let (_, message, details) = source.parts();
+                let mut details = match details {
+                    Some(serde_json::Value::Object(map)) => map,
+                    _ => serde_json::Map::new(),
+                };
+                details.insert("index".to_string(), json!(index));
+                details.insert("item_code".to_string(), json!(source.code()));
 
-        let mut body = json!({
-            "error": error_message,
-            "status": status.as_u16()
+                (
+                    StatusCode::UNPROCESSABLE_ENTITY,
+                    format!("Item {} is invalid: {}", index, message),
+                    Some(serde_json::Value::Object(details)),
+                )
+            }
+            AppError::GenreInUse { book_count } => (
+                StatusCode::CONFLICT,
+                format!(
+                    "Genre is still assigned to {} books; pass force=true to remove it anyway",
+                    book_count
+                ),
+                Some(json!({ "book_count": book_count })),
+            ),
+        }
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let (status, error_message, details) = self.parts();
+
+        let mut error = json!({
+            "code": self.code(),
+            "message": error_message,
         });
 
         if let Some(details) = details {
-            if let serde_json::Value::Object(ref mut map) = body {
+            if let serde_json::Value::Object(ref mut map) = error {
                 if let serde_json::Value::Object(details_map) = details {
                     for (key, value) in details_map {
                         map.insert(key, value);
@@ -160,8 +320,208 @@ impl IntoResponse for AppError {
             }
         }
 
-        (status, Json(body)).into_response()
+        let body = json!({
+            "error": error,
+            "status": status.as_u16()
+        });
+
+        let mut response = (status, Json(body)).into_response();
+        if let AppError::RateLimited { retry_after_secs } = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+        }
+        response
     }
 }
 
 pub type AppResult<T> = Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::error::{DatabaseError, ErrorKind};
+    use std::borrow::Cow;
+
+    /// Stand-in for a Postgres error carrying just what the mapping looks at
+    #[derive(Debug)]
+    struct FakeDbError {
+        kind: fn() -> ErrorKind,
+        message: &'static str,
+    }
+
+    impl std::fmt::Display for FakeDbError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str(self.message)
+        }
+    }
+
+    impl std::error::Error for FakeDbError {}
+
+    impl DatabaseError for FakeDbError {
+        fn message(&self) -> &str {
+            self.message
+        }
+
+        fn code(&self) -> Option<Cow<'_, str>> {
+            None
+        }
+
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+
+        fn kind(&self) -> ErrorKind {
+            (self.kind)()
+        }
+    }
+
+    fn db_error(kind: fn() -> ErrorKind, message: &'static str) -> AppError {
+        sqlx::Error::Database(Box::new(FakeDbError { kind, message })).into()
+    }
+
+    #[test]
+    fn unique_violation_is_conflict() {
+        let err = db_error(
+            || ErrorKind::UniqueViolation,
+            r#"duplicate key value violates unique constraint "User_email_key""#,
+        );
+        assert!(matches!(err, AppError::Conflict(_)));
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+    }
+
+    #[test]
+    fn missing_reference_is_bad_request() {
+        let err = db_error(
+            || ErrorKind::ForeignKeyViolation,
+            r#"insert or update on table "Chapter" violates foreign key constraint "Chapter_book_id_fkey""#,
+        );
+        assert!(matches!(err, AppError::BadRequest(_)));
+        assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn message_wording_does_not_pick_the_status() {
+        // A localized server words this differently, so the text must not matter
+        let err = db_error(
+            || ErrorKind::ForeignKeyViolation,
+            r#"update or delete on table "Book" violates foreign key constraint "Chapter_book_id_fkey" on table "Chapter""#,
+        );
+        assert!(matches!(err, AppError::BadRequest(_)));
+    }
+
+    #[test]
+    fn deleting_referenced_row_is_conflict() {
+        let err = map_still_referenced(sqlx::Error::Database(Box::new(FakeDbError {
+            kind: || ErrorKind::ForeignKeyViolation,
+            message: "la mise à jour ou la suppression viole la contrainte de clé étrangère",
+        })));
+        assert!(matches!(err, AppError::Conflict(_)));
+        assert_eq!(err.into_response().status(), StatusCode::CONFLICT);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn postgres_foreign_keys_map_by_call_site(pool: sqlx::PgPool) {
+        sqlx::query("CREATE TABLE parent (id INT PRIMARY KEY)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("CREATE TABLE child (parent_id INT REFERENCES parent(id) ON DELETE RESTRICT)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let dangling = sqlx::query("INSERT INTO child VALUES (1)")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        assert!(matches!(AppError::from(dangling), AppError::BadRequest(_)));
+
+        sqlx::query("INSERT INTO parent VALUES (1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO child VALUES (1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let blocked = sqlx::query("DELETE FROM parent WHERE id = 1")
+            .execute(&pool)
+            .await
+            .unwrap_err();
+        match map_still_referenced(blocked) {
+            AppError::Conflict(message) => assert!(message.contains("child"), "{}", message),
+            other => panic!("expected a conflict, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn delete_mapping_leaves_other_errors_alone() {
+        let err = map_still_referenced(sqlx::Error::RowNotFound);
+        assert!(matches!(err, AppError::Database(_)));
+    }
+
+    #[test]
+    fn other_database_errors_stay_internal() {
+        let err: AppError = sqlx::Error::RowNotFound.into();
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn validation_failure_lists_each_field() {
+        use crate::models::auth_model::RegisterDto;
+        use validator::Validate;
+
+        let dto = RegisterDto {
+            username: "ok_name".to_string(),
+            email: "not-an-email".to_string(),
+            password: "short".to_string(),
+        };
+        let err: AppError = dto.validate().unwrap_err().into();
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 422);
+        assert_eq!(body["error"]["code"], "VALIDATION_FAILED");
+        assert_eq!(body["error"]["message"], "Validation failed");
+        assert_eq!(
+            body["error"]["fields"],
+            json!({
+                "email": ["must be a valid email address"],
+                "password": ["must be at least 8 characters"],
+            })
+        );
+
+        let mut messages: Vec<&str> = body["error"]["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m.as_str().unwrap())
+            .collect();
+        messages.sort_unstable();
+        assert_eq!(
+            messages,
+            [
+                "email: must be a valid email address",
+                "password: must be at least 8 characters",
+            ]
+        );
+    }
+}
@@ -1,15 +1,23 @@
 use crate::middleware::auth::AuthUser;
-use crate::models::auth_model::{LoginDto, RegisterDto};
+use crate::models::auth_model::{Auth, LoginDto, RegisterDto};
 use crate::models::response_model::ApiResponse;
+use crate::models::user_model::SafeUser;
 use crate::services::auth_service::AuthService;
+use crate::utils::field_limits::LengthLimited;
 use crate::utils::jwt::JwtService;
 use crate::{errors::AppError, AppState};
 use axum::Extension;
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{
+    extract::{Multipart, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use serde::Deserialize;
 use time::Duration;
 use tower_cookies::{Cookie, Cookies};
 use tracing::{error, info, instrument, warn};
+use validator::Validate;
 
 pub struct AuthHandler;
 
@@ -43,6 +51,8 @@ impl AuthHandler {
         Json(request): Json<RegisterDto>,
     ) -> Result<impl IntoResponse, AppError> {
         info!("Attempting user registration");
+        request.validate()?;
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -60,15 +70,14 @@ impl AuthHandler {
                 access_cookie.set_secure(true);
                 access_cookie.set_path("/");
                 access_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in as i64));
+                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in));
 
                 let mut refresh_cookie = Cookie::new("refresh_token", auth.refresh_token.clone());
                 refresh_cookie.set_http_only(true);
                 refresh_cookie.set_secure(true);
                 refresh_cookie.set_path("/");
                 refresh_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                refresh_cookie
-                    .set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in as i64));
+                refresh_cookie.set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in));
 
                 cookies.add(access_cookie);
                 cookies.add(refresh_cookie);
@@ -76,10 +85,7 @@ impl AuthHandler {
                 info!("Cookies set successfully for new user");
 
                 // Return full auth response with tokens (same as login)
-                Ok((
-                    StatusCode::CREATED,
-                    Json(crate::models::auth_model::AuthResponse::success(auth)),
-                ))
+                Ok((StatusCode::CREATED, Json(ApiResponse::success(auth))))
             }
             Err(e) => {
                 error!(error = ?e, "Failed to register user");
@@ -95,7 +101,7 @@ impl AuthHandler {
         State(state): State<AppState>,
         cookies: Cookies,
         Json(request): Json<LoginDto>,
-    ) -> Result<Json<crate::models::auth_model::AuthResponse>, AppError> {
+    ) -> Result<Json<ApiResponse<Auth>>, AppError> {
         info!("Attempting user login");
 
         let service = Self::create_service(&state);
@@ -114,22 +120,21 @@ impl AuthHandler {
                 access_cookie.set_secure(true);
                 access_cookie.set_path("/");
                 access_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in as i64));
+                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in));
 
                 let mut refresh_cookie = Cookie::new("refresh_token", auth.refresh_token.clone());
                 refresh_cookie.set_http_only(true);
                 refresh_cookie.set_secure(true);
                 refresh_cookie.set_path("/");
                 refresh_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                refresh_cookie
-                    .set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in as i64));
+                refresh_cookie.set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in));
 
                 cookies.add(access_cookie);
                 cookies.add(refresh_cookie);
 
                 info!("Cookies set successfully for login");
 
-                Ok(Json(crate::models::auth_model::AuthResponse::success(auth)))
+                Ok(Json(ApiResponse::success(auth)))
             }
             Err(e) => {
                 warn!(error = ?e, "Login attempt failed");
@@ -143,7 +148,7 @@ impl AuthHandler {
         State(state): State<AppState>,
         cookies: Cookies,
         body: Option<Json<RefreshTokenRequest>>,
-    ) -> Result<Json<crate::models::auth_model::AuthResponse>, AppError> {
+    ) -> Result<Json<ApiResponse<Auth>>, AppError> {
         info!("Attempting to refresh token");
 
         // Try to get refresh token from body first (for mobile), then from cookie (for web)
@@ -176,15 +181,14 @@ impl AuthHandler {
                 access_cookie.set_secure(true);
                 access_cookie.set_path("/");
                 access_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in as i64));
+                access_cookie.set_max_age(Duration::minutes(state.config.jwt_expire_in));
 
                 let mut refresh_cookie = Cookie::new("refresh_token", auth.refresh_token.clone());
                 refresh_cookie.set_http_only(true);
                 refresh_cookie.set_secure(true);
                 refresh_cookie.set_path("/");
                 refresh_cookie.set_same_site(tower_cookies::cookie::SameSite::Strict);
-                refresh_cookie
-                    .set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in as i64));
+                refresh_cookie.set_max_age(Duration::minutes(state.config.jwt_refresh_expire_in));
 
                 cookies.add(access_cookie);
                 cookies.add(refresh_cookie);
@@ -192,7 +196,7 @@ impl AuthHandler {
                 info!("New cookies set successfully");
 
                 // Return full auth response with tokens (for mobile clients)
-                Ok(Json(crate::models::auth_model::AuthResponse::success(auth)))
+                Ok(Json(ApiResponse::success(auth)))
             }
             Err(e) => {
                 error!(error = ?e, "Failed to refresh token");
@@ -207,7 +211,7 @@ impl AuthHandler {
     pub async fn me(
         State(state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
-    ) -> Result<Json<ApiResponse<crate::models::user_model::SafeUser>>, AppError> {
+    ) -> Result<Json<ApiResponse<SafeUser>>, AppError> {
         info!("Fetching current user profile");
 
         let service = Self::create_service(&state);
@@ -256,6 +260,7 @@ impl AuthHandler {
         Json(request): Json<crate::models::auth_model::UpdateProfileDto>,
     ) -> Result<Json<ApiResponse<crate::models::user_model::SafeUser>>, AppError> {
         info!("Updating user profile");
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -302,64 +307,59 @@ impl AuthHandler {
         }
     }
 
-    #[instrument(skip(state, multipart), fields(user_id = %auth_user.id))]
-    pub async fn upload_avatar(
-        State(state): State<AppState>,
-        Extension(auth_user): Extension<AuthUser>,
-        mut multipart: axum::extract::Multipart,
-    ) -> Result<Json<ApiResponse<String>>, AppError> {
-        info!("Uploading avatar");
-
-        let service = Self::create_service(&state);
-
-        // Extract file from multipart
-        let mut file_bytes: Option<Vec<u8>> = None;
-        let mut content_type: Option<String> = None;
-
+    /// Pull the avatar bytes out of an `avatar` or `file` multipart field
+    async fn read_avatar(mut multipart: Multipart) -> Result<Vec<u8>, AppError> {
         while let Some(field) = multipart
             .next_field()
             .await
             .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
         {
             let name = field.name().unwrap_or("").to_string();
-            if name == "avatar" || name == "file" {
-                content_type = field.content_type().map(|s| s.to_string());
-                file_bytes = Some(
-                    field
-                        .bytes()
-                        .await
-                        .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?
-                        .to_vec(),
-                );
-                break;
+            if name != "avatar" && name != "file" {
+                continue;
             }
-        }
 
-        let bytes =
-            file_bytes.ok_or_else(|| AppError::BadRequest("No file uploaded".to_string()))?;
-        let ct = content_type.unwrap_or_else(|| "image/jpeg".to_string());
+            if let Some(ct) = field.content_type() {
+                if !ct.starts_with("image/") {
+                    return Err(AppError::BadRequest(
+                        "Only image files are allowed".to_string(),
+                    ));
+                }
+            }
 
-        // Validate content type
-        if !ct.starts_with("image/") {
-            return Err(AppError::BadRequest(
-                "Only image files are allowed".to_string(),
-            ));
+            let bytes = field
+                .bytes()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+            return Ok(bytes.to_vec());
         }
 
-        // Limit file size (5MB)
-        if bytes.len() > 5 * 1024 * 1024 {
-            return Err(AppError::BadRequest(
-                "File size must be less than 5MB".to_string(),
-            ));
-        }
+        Err(AppError::BadRequest("No file uploaded".to_string()))
+    }
+
+    /// Replace the current user's profile picture
+    /// POST /api/users/me/avatar (also served at the older POST /api/auth/avatar)
+    #[instrument(skip(state, multipart), fields(user_id = %auth_user.id))]
+    pub async fn update_avatar(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        multipart: Multipart,
+    ) -> Result<Json<ApiResponse<SafeUser>>, AppError> {
+        info!("Updating avatar");
 
-        match service.upload_avatar(&auth_user.id, bytes, &ct).await {
-            Ok(url) => {
-                info!("Avatar uploaded successfully");
-                Ok(Json(ApiResponse::success(url)))
+        let bytes = Self::read_avatar(multipart).await?;
+        let service = Self::create_service(&state);
+
+        match service.upload_avatar(&auth_user.id, bytes).await {
+            Ok(user) => {
+                info!("Avatar updated successfully");
+                Ok(Json(ApiResponse::with_message(
+                    "Avatar updated successfully",
+                    user,
+                )))
             }
             Err(e) => {
-                error!(error = ?e, "Failed to upload avatar");
+                error!(error = ?e, "Failed to update avatar");
                 Err(e)
             }
         }
@@ -375,11 +375,18 @@ impl AuthHandler {
 
         let service = Self::create_service(&state);
 
+        // Topic subscriptions follow the device, so drop the old device's before replacing it
+        state
+            .notification
+            .release_user_topics(&auth_user.id, Some(request.fcm_token.trim()))
+            .await;
+
         match service
             .save_fcm_token(&auth_user.id, &request.fcm_token)
             .await
         {
             Ok(_) => {
+                state.notification.spawn_user_topic_sync(&auth_user.id);
                 info!("FCM token saved successfully");
                 Ok(Json(ApiResponse::success("FCM token saved".to_string())))
             }
@@ -389,4 +396,72 @@ impl AuthHandler {
             }
         }
     }
+
+    #[instrument(skip(state), fields(user_id = %auth_user.id))]
+    pub async fn delete_fcm_token(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+    ) -> Result<Json<ApiResponse<String>>, AppError> {
+        info!("Clearing FCM token for user");
+
+        let service = Self::create_service(&state);
+
+        state
+            .notification
+            .release_user_topics(&auth_user.id, None)
+            .await;
+
+        match service.clear_fcm_token(&auth_user.id).await {
+            Ok(_) => {
+                info!("FCM token cleared successfully");
+                Ok(Json(ApiResponse::success("FCM token cleared".to_string())))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to clear FCM token");
+                Err(e)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user_model::Role;
+    use crate::test_support;
+    use sqlx::PgPool;
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn me_returns_the_profile_without_the_password(pool: PgPool) {
+        let user_id = test_support::seed_user(&pool, "profile_owner").await;
+        sqlx::query(r#"UPDATE "User" SET bio = 'Reads at night' WHERE id = $1"#)
+            .bind(&user_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let state = test_support::app_state_with(
+            test_support::config(),
+            test_support::database(pool.clone()),
+        );
+        let auth_user = AuthUser {
+            id: user_id.clone(),
+            email: "profile_owner@example.com".to_string(),
+            role: Role::User,
+        };
+
+        let Json(response) = AuthHandler::me(State(state), Extension(auth_user))
+            .await
+            .unwrap();
+        let body = serde_json::to_value(response).unwrap();
+
+        let user = &body["data"];
+        assert_eq!(user["id"], user_id);
+        assert_eq!(user["username"], "profile_owner");
+        assert_eq!(user["bio"], "Reads at night");
+        assert_eq!(user["role"], "User");
+        assert!(user.get("profile_pic").is_some());
+        assert!(user.get("password").is_none());
+        assert!(!body.to_string().contains("not-a-real-hash"));
+    }
 }
@@ -0,0 +1,119 @@
+use crate::{
+    errors::AppError,
+    middleware::auth::AuthUser,
+    models::author_model::{AuthorDto, AuthorQuery, CreateAuthorDto},
+    models::book_model::BookDto,
+    models::paging_model::{PaginatedApiResponse, PaginationParams},
+    models::response_model::ApiResponse,
+    models::user_model::Role,
+    require_role,
+    services::author_service::AuthorService,
+    utils::field_limits::LengthLimited,
+    AppState,
+};
+use axum::http::StatusCode;
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+use validator::Validate;
+
+pub struct AuthorHandler;
+
+impl AuthorHandler {
+    fn create_service(state: &AppState) -> AuthorService {
+        AuthorService::new(state.db.clone())
+    }
+
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        author_name = %request.name
+    ))]
+    pub async fn create_author(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<CreateAuthorDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<AuthorDto>>), AppError> {
+        info!("Attempting to create author");
+        require_role!(auth_user, Role::Admin);
+        request.validate()?;
+        request.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.create_author(request).await {
+            Ok(author) => {
+                info!(author_id = %author.id, "Author created successfully");
+                Ok((
+                    StatusCode::CREATED,
+                    Json(ApiResponse::with_message(
+                        "Author created successfully",
+                        author,
+                    )),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to create author");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(state), fields(author_id = %id))]
+    pub async fn get_author(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<AuthorDto>>), AppError> {
+        info!("Fetching single author");
+        let service = Self::create_service(&state);
+        let author = service.get_author(id).await?;
+        info!(author_name = %author.name, "Author fetched successfully");
+
+        Ok((StatusCode::OK, Json(ApiResponse::success(author))))
+    }
+
+    #[instrument(skip(state), fields(
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_authors(
+        State(state): State<AppState>,
+        Query(params): Query<AuthorQuery>,
+    ) -> Result<Json<PaginatedApiResponse<AuthorDto>>, AppError> {
+        info!("Fetching authors");
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.get_authors(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Authors fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    #[instrument(skip(state), fields(
+        author_id = %author_id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_books_by_author(
+        State(state): State<AppState>,
+        Path(author_id): Path<String>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<BookDto>>, AppError> {
+        info!("Fetching books for author");
+        params.validate()?;
+        let service = Self::create_service(&state);
+        let paginated = service.get_books_by_author(author_id, params).await?;
+        info!(
+            total_items = paginated.meta.total_items,
+            "Author books fetched successfully"
+        );
+        Ok(Json(paginated.into_api_response()))
+    }
+}
@@ -1,24 +1,39 @@
 use crate::middleware::auth::AuthUser;
+use crate::middleware::rate_limit::client_ip;
 use crate::models::user_model::Role;
-use crate::models::book_model::{BookDto, CreateBookDto, UpdateBookDto};
-use crate::models::paging_model::{PaginatedResponse, PaginationParams};
+use crate::models::book_model::{
+    AdminBookQuery, BookDto, BookImportSummary, BookQuery, BookSearchParams, BookmarkCountDto,
+    CreateBookDto, ReplaceBookDto, UpdateBookDto,
+};
+use crate::models::paging_model::{PaginatedApiResponse, PaginationParams};
 use crate::models::response_model::ApiResponse;
 use crate::require_role;
-use crate::services::book_service::BookService;
+use crate::services::book_service::{BookService, MAX_BULK_BOOKS};
+use crate::utils::etag::conditional_json;
+use crate::utils::field_limits::LengthLimited;
 use crate::{errors::AppError, AppState};
 use axum::Extension;
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{ConnectInfo, Multipart, Path, Query, State},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use std::net::SocketAddr;
+use tokio::sync::mpsc;
 use tracing::{info, error, instrument};
+use validator::Validate;
+
+type BookList = Vec<BookDto>;
+
+/// Book details change with ratings and bookmarks, so clients revalidate often
+const BOOK_CACHE_CONTROL: &str = "public, max-age=60, must-revalidate";
 
 pub struct BookHandler;
 
 impl BookHandler {
     fn create_service(state: &AppState) -> BookService {
-        BookService::new(state.db.clone())
+        BookService::new(state.db.clone(), state.storage.clone())
     }
 
     #[instrument(skip(state), fields(
@@ -27,27 +42,158 @@ impl BookHandler {
     ))]
     pub async fn get_books(
         State(state): State<AppState>,
-        Query(params): Query<PaginationParams>,
-    ) -> Result<Json<PaginatedResponse<BookDto>>, AppError> {
+        auth_user: Option<Extension<AuthUser>>,
+        Query(params): Query<BookQuery>,
+    ) -> Result<Response, AppError> {
         info!("Fetching books with pagination");
+        params.validate()?;
 
         let service = Self::create_service(&state);
-        let paginated = service.get_books(params).await?;
+
+        if params.is_cursor_mode() {
+            let mut page = service.get_books_by_cursor(params).await?;
+            if let Some(Extension(user)) = &auth_user {
+                service.mark_bookmarked(&user.id, &mut page.data).await?;
+            }
+
+            info!(
+                returned = page.data.len(),
+                has_more = page.meta.next_cursor.is_some(),
+                "Books fetched successfully"
+            );
+
+            return Ok(Json(page.into_api_response()).into_response());
+        }
+
+        let mut paginated = service.get_books(params).await?;
+        if let Some(Extension(user)) = &auth_user {
+            service.mark_bookmarked(&user.id, &mut paginated.data).await?;
+        }
 
         info!(
-            total_items = paginated.total_items,
-            total_pages = paginated.total_pages,
+            total_items = paginated.meta.total_items,
+            total_pages = paginated.meta.total_pages,
             "Books fetched successfully"
         );
 
-        Ok(Json(paginated))
+        Ok(Json(paginated.into_api_response()).into_response())
     }
 
-    #[instrument(skip(state), fields(book_id = %id))]
+    /// List books for admin reports, optionally bounded by creation time
+    /// GET /api/admin/books?created_after=&created_before=
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn list_books_admin(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Query(params): Query<AdminBookQuery>,
+    ) -> Result<Json<PaginatedApiResponse<BookDto>>, AppError> {
+        info!("Fetching books for admin");
+        require_role!(auth_user, Role::Admin);
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.list_books_for_admin(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Admin books fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    #[instrument(skip(state), fields(
+        query = ?params.q,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn search_books(
+        State(state): State<AppState>,
+        Query(params): Query<BookSearchParams>,
+    ) -> Result<Json<PaginatedApiResponse<BookDto>>, AppError> {
+        info!("Searching books");
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.search_books(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            total_pages = paginated.meta.total_pages,
+            "Book search completed"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    /// Count a view of a book, debounced per signed-in user or client IP
+    /// POST /api/books/{id}/view
+    #[instrument(skip(state, headers, connect_info), fields(book_id = %id))]
+    pub async fn record_view(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+        auth_user: Option<Extension<AuthUser>>,
+        connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+        headers: HeaderMap,
+    ) -> Result<StatusCode, AppError> {
+        let viewer = match &auth_user {
+            Some(Extension(user)) => format!("user:{}", user.id),
+            None => format!(
+                "ip:{}",
+                client_ip(
+                    &headers,
+                    connect_info.as_ref().map(|Extension(info)| info),
+                    &state.config.trusted_proxies,
+                )
+            ),
+        };
+
+        let service = Self::create_service(&state);
+        service.record_view(&id, &viewer).await?;
+
+        Ok(StatusCode::NO_CONTENT)
+    }
+
+    /// Books ranked by bookmarks and recent reading activity
+    /// GET /api/books/popular
+    #[instrument(skip(state), fields(
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_popular_books(
+        State(state): State<AppState>,
+        auth_user: Option<Extension<AuthUser>>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<BookDto>>, AppError> {
+        info!("Fetching popular books");
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let mut paginated = service
+            .get_popular_books(params, state.config.popular_window_days)
+            .await?;
+        if let Some(Extension(user)) = &auth_user {
+            service.mark_bookmarked(&user.id, &mut paginated.data).await?;
+        }
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Popular books fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    #[instrument(skip(state, headers), fields(book_id = %id))]
     pub async fn get_book(
         State(state): State<AppState>,
         Path(id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<BookDto>>), AppError> {
+        headers: HeaderMap,
+    ) -> Result<Response, AppError> {
         info!("Fetching single book");
 
         let service = Self::create_service(&state);
@@ -55,7 +201,47 @@ impl BookHandler {
 
         info!(book_title = %book.title, "Book fetched successfully");
 
-        Ok((StatusCode::OK, Json(ApiResponse::success(book))))
+        Ok(conditional_json(
+            &headers,
+            &ApiResponse::success(book),
+            BOOK_CACHE_CONTROL,
+        ))
+    }
+
+    #[instrument(skip(state, headers), fields(book_slug = %slug))]
+    pub async fn get_book_by_slug(
+        State(state): State<AppState>,
+        Path(slug): Path<String>,
+        headers: HeaderMap,
+    ) -> Result<Response, AppError> {
+        info!("Fetching book by slug");
+
+        let service = Self::create_service(&state);
+        let book = service.get_book_by_slug(&slug).await?;
+
+        info!(book_id = %book.id, "Book fetched successfully");
+
+        Ok(conditional_json(
+            &headers,
+            &ApiResponse::success(book),
+            BOOK_CACHE_CONTROL,
+        ))
+    }
+
+    /// How many readers bookmarked a book
+    /// GET /api/books/{id}/bookmark-count
+    #[instrument(skip(state), fields(book_id = %id))]
+    pub async fn get_bookmark_count(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+    ) -> Result<Json<ApiResponse<BookmarkCountDto>>, AppError> {
+        let service = Self::create_service(&state);
+        let count = service.get_bookmark_count(&id).await?;
+
+        Ok(Json(ApiResponse::success(BookmarkCountDto {
+            book_id: id,
+            count,
+        })))
     }
 
     #[instrument(skip(state, request), fields(
@@ -71,6 +257,8 @@ impl BookHandler {
         info!("Attempting to create book");
 
         require_role!(auth_user, Role::Admin);
+        request.validate()?;
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -93,6 +281,126 @@ impl BookHandler {
         }
     }
 
+    /// Create many books at once; either all are inserted or none are
+    /// POST /api/books/bulk
+    #[instrument(skip(state, requests), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        count = requests.len()
+    ))]
+    pub async fn create_books(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(requests): Json<Vec<CreateBookDto>>,
+    ) -> Result<(StatusCode, Json<ApiResponse<BookList>>), AppError> {
+        info!("Attempting to bulk create books");
+
+        require_role!(auth_user, Role::Admin);
+
+        if requests.is_empty() {
+            return Err(AppError::BadRequest("No books provided".to_string()));
+        }
+        if requests.len() > MAX_BULK_BOOKS {
+            return Err(AppError::Unprocessable(format!(
+                "At most {} books can be created per request",
+                MAX_BULK_BOOKS
+            )));
+        }
+
+        for (index, request) in requests.iter().enumerate() {
+            request
+                .validate()
+                .map_err(AppError::from)
+                .and_then(|_| request.check_lengths(&state.config.field_limits))
+                .map_err(|e| AppError::InvalidBatchItem {
+                    index,
+                    source: Box::new(e),
+                })?;
+        }
+
+        let service = Self::create_service(&state);
+
+        match service.create_books(requests).await {
+            Ok(books) => {
+                info!(created = books.len(), "Books created successfully");
+                Ok((
+                    StatusCode::CREATED,
+                    Json(ApiResponse::with_message("Books created successfully", books)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to bulk create books");
+                Err(e)
+            }
+        }
+    }
+
+    /// Import books from a CSV upload in a `file` or `csv` multipart field
+    /// POST /api/books/import
+    #[instrument(skip(state, multipart), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role
+    ))]
+    pub async fn import_books(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        mut multipart: Multipart,
+    ) -> Result<Json<ApiResponse<BookImportSummary>>, AppError> {
+        info!("Attempting to import books from CSV");
+
+        require_role!(auth_user, Role::Admin);
+
+        let mut field = loop {
+            let field = multipart
+                .next_field()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
+                .ok_or_else(|| AppError::BadRequest("No file uploaded".to_string()))?;
+
+            if matches!(field.name(), Some("file") | Some("csv")) {
+                break field;
+            }
+        };
+
+        let service = Self::create_service(&state);
+        let (chunk_tx, chunk_rx) = mpsc::channel(8);
+
+        // Feed the upload to the parser chunk by chunk while rows are being inserted
+        let feed = async move {
+            while let Some(chunk) = field
+                .chunk()
+                .await
+                .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?
+            {
+                if chunk_tx.send(chunk).await.is_err() {
+                    break;
+                }
+            }
+            Ok(())
+        };
+        let import = service.import_books(chunk_rx, &state.config.field_limits);
+
+        match tokio::try_join!(feed, import) {
+            Ok(((), summary)) => {
+                info!(
+                    inserted = summary.inserted,
+                    skipped = summary.skipped,
+                    "Books imported successfully"
+                );
+                Ok(Json(ApiResponse::with_message(
+                    "Books imported successfully",
+                    summary,
+                )))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to import books");
+                Err(e)
+            }
+        }
+    }
+
+    /// Change only the fields present in the body
+    /// PATCH /api/books/{id}
     #[instrument(skip(state, request), fields(
         user_id = %auth_user.id,
         user_role = ?auth_user.role,
@@ -107,6 +415,7 @@ impl BookHandler {
         info!("Attempting to update book");
 
         require_role!(auth_user, Role::Admin);
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -129,6 +438,166 @@ impl BookHandler {
         }
     }
 
+    /// Replace every field of a book; omitted optional fields take their defaults
+    /// PUT /api/book/{id}
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %id
+    ))]
+    pub async fn replace_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+        Json(request): Json<ReplaceBookDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<BookDto>>), AppError> {
+        info!("Attempting to replace book");
+
+        require_role!(auth_user, Role::Admin);
+        request.book.validate()?;
+        request.book.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.replace_book(id, request).await {
+            Ok(book) => {
+                info!(
+                    book_id = %book.id,
+                    book_title = %book.title,
+                    "Book replaced successfully"
+                );
+                Ok((
+                    StatusCode::OK,
+                    Json(ApiResponse::with_message("Book updated successfully", book)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to replace book");
+                Err(e)
+            }
+        }
+    }
+
+    /// Download a book with its genres and all chapters as one JSON document
+    /// GET /api/books/{id}/export
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %id
+    ))]
+    pub async fn export_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+    ) -> Result<Response, AppError> {
+        info!("Attempting to export book");
+
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.export_book(id, state.config.reading_wpm).await {
+            Ok((slug, body)) => {
+                info!("Book export started");
+                Ok((
+                    [
+                        (header::CONTENT_TYPE, "application/json".to_string()),
+                        (
+                            header::CONTENT_DISPOSITION,
+                            format!("attachment; filename=\"{}.json\"", slug),
+                        ),
+                    ],
+                    body,
+                )
+                    .into_response())
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to export book");
+                Err(e)
+            }
+        }
+    }
+
+    /// Pull the cover bytes out of a `cover` or `file` multipart field, plus the
+    /// `version` field the client last read
+    async fn read_cover(mut multipart: Multipart) -> Result<(Vec<u8>, i32), AppError> {
+        let mut cover = None;
+        let mut version = None;
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to read multipart: {}", e)))?
+        {
+            let name = field.name().unwrap_or("").to_string();
+            match name.as_str() {
+                "cover" | "file" if cover.is_none() => {
+                    if let Some(ct) = field.content_type() {
+                        if !ct.starts_with("image/") {
+                            return Err(AppError::BadRequest(
+                                "Only image files are allowed".to_string(),
+                            ));
+                        }
+                    }
+
+                    let bytes = field.bytes().await.map_err(|e| {
+                        AppError::BadRequest(format!("Failed to read file: {}", e))
+                    })?;
+                    cover = Some(bytes.to_vec());
+                }
+                "version" => {
+                    let text = field.text().await.map_err(|e| {
+                        AppError::BadRequest(format!("Failed to read version: {}", e))
+                    })?;
+                    version = Some(text.trim().parse::<i32>().map_err(|_| {
+                        AppError::BadRequest("version must be an integer".to_string())
+                    })?);
+                }
+                _ => {}
+            }
+        }
+
+        let cover = cover.ok_or_else(|| AppError::BadRequest("No file uploaded".to_string()))?;
+        let version =
+            version.ok_or_else(|| AppError::BadRequest("version is required".to_string()))?;
+        Ok((cover, version))
+    }
+
+    /// Replace a book's cover image
+    /// POST /api/books/{id}/cover
+    #[instrument(skip(state, multipart), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %id
+    ))]
+    pub async fn upload_cover(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+        multipart: Multipart,
+    ) -> Result<Json<ApiResponse<BookDto>>, AppError> {
+        info!("Attempting to upload book cover");
+
+        require_role!(auth_user, Role::Admin);
+
+        let (bytes, version) = Self::read_cover(multipart).await?;
+        let service = Self::create_service(&state);
+
+        match service.update_cover(&id, bytes, version).await {
+            Ok(book) => {
+                info!(book_id = %book.id, "Book cover updated successfully");
+                Ok(Json(ApiResponse::with_message(
+                    "Book cover updated successfully",
+                    book,
+                )))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to upload book cover");
+                Err(e)
+            }
+        }
+    }
+
     #[instrument(skip(state), fields(
         user_id = %auth_user.id,
         user_role = ?auth_user.role,
@@ -138,20 +607,29 @@ impl BookHandler {
         State(state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
         Path(id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<()>>), AppError> {
+    ) -> Result<StatusCode, AppError> {
         info!("Attempting to delete book");
 
         require_role!(auth_user, Role::Admin);
 
         let service = Self::create_service(&state);
 
-        match service.delete_book(id).await {
+        match service.delete_book(id.clone()).await {
             Ok(_) => {
                 info!("Book deleted successfully");
-                Ok((
-                    StatusCode::NO_CONTENT,
-                    Json(ApiResponse::with_message("Book deleted successfully", ())),
-                ))
+
+                // Extracted images, presigned assets and covers are all grouped by book id
+                for folder in ["content-images", "assets", "covers"] {
+                    let prefix = format!("{}/{}/", folder, id);
+                    match state.storage.delete_prefix(&prefix).await {
+                        Ok(deleted) => info!(deleted, prefix = %prefix, "Deleted book files"),
+                        Err(e) => {
+                            error!(error = ?e, prefix = %prefix, "Failed to delete book files")
+                        }
+                    }
+                }
+
+                Ok(StatusCode::NO_CONTENT)
             }
             Err(e) => {
                 error!(error = ?e, "Failed to delete book");
@@ -1,29 +1,40 @@
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Extension, Json,
 };
 use chrono::Utc;
+use validator::Validate;
 
 use crate::{
     errors::AppError,
     middleware::auth::AuthUser,
     models::bookmark_model::{
-        Bookmark, BookmarkResponse, BookmarkStatusResponse, BookmarkWithBook,
+        Bookmark, BookmarkQuery, BookmarkResponse, BookmarkStatusResponse, BookmarkWithBook,
         BookmarkWithBookResponse, CreateBookmarkDto,
     },
+    models::paging_model::{page_offset, PaginatedApiResponse, PaginatedResponse},
+    models::response_model::ApiResponse,
+    services::book_service::BookService,
     AppState,
 };
 
 pub struct BookmarkHandler;
 
 impl BookmarkHandler {
+    async fn invalidate_count(state: &AppState, book_id: &str) {
+        BookService::new(state.db.clone(), state.storage.clone())
+            .invalidate_bookmark_count(book_id)
+            .await;
+    }
+
     pub async fn create_bookmark(
         State(state): State<AppState>,
         Extension(user): Extension<AuthUser>,
         Json(dto): Json<CreateBookmarkDto>,
-    ) -> Result<(StatusCode, Json<BookmarkResponse>), AppError> {
+    ) -> Result<(StatusCode, Json<ApiResponse<BookmarkResponse>>), AppError> {
         tracing::debug!(user_id = %user.id, book_id = %dto.book_id, "Creating bookmark");
+        dto.validate()?;
 
         let existing = sqlx::query_as::<_, Bookmark>(
             r#"SELECT id, user_id, book_id, created_at, updated_at 
@@ -32,15 +43,14 @@ impl BookmarkHandler {
         .bind(&user.id)
         .bind(&dto.book_id)
         .fetch_optional(&state.db.pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Error checking existing bookmark: {}", e);
-            AppError::Internal(format!("Database error: {}", e))
-        })?;
+        .await?;
 
         if let Some(bookmark) = existing {
             tracing::debug!("Bookmark already exists");
-            return Ok((StatusCode::OK, Json(BookmarkResponse::from(bookmark))));
+            return Ok((
+                StatusCode::OK,
+                Json(ApiResponse::success(BookmarkResponse::from(bookmark))),
+            ));
         }
 
         // Check if book exists
@@ -48,11 +58,7 @@ impl BookmarkHandler {
             sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Book" WHERE id = $1"#)
                 .bind(&dto.book_id)
                 .fetch_one(&state.db.pool)
-                .await
-                .map_err(|e| {
-                    tracing::error!("Error checking book exists: {}", e);
-                    AppError::Internal(format!("Database error: {}", e))
-                })?;
+                .await?;
 
         if book_exists == 0 {
             return Err(AppError::NotFound("Book not found".to_string()));
@@ -74,11 +80,12 @@ impl BookmarkHandler {
         .bind(now)
         .bind(now)
         .fetch_one(&state.db.pool)
-        .await
-        .map_err(|e| {
-            tracing::error!("Error inserting bookmark: {}", e);
-            AppError::Internal(format!("Database error: {}", e))
-        })?;
+        .await?;
+
+        Self::invalidate_count(&state, &dto.book_id).await;
+        state
+            .notification
+            .spawn_novel_topic_sync(&user.id, &dto.book_id, true);
 
         tracing::info!(
             user_id = %user.id,
@@ -86,7 +93,89 @@ impl BookmarkHandler {
             "Bookmark created"
         );
 
-        Ok((StatusCode::CREATED, Json(BookmarkResponse::from(bookmark))))
+        Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(BookmarkResponse::from(bookmark))),
+        ))
+    }
+
+    /// Bookmark the book if it isn't yet, otherwise remove the bookmark
+    /// POST /api/bookmark/toggle
+    pub async fn toggle_bookmark(
+        State(state): State<AppState>,
+        Extension(user): Extension<AuthUser>,
+        Json(dto): Json<CreateBookmarkDto>,
+    ) -> Result<Json<ApiResponse<BookmarkStatusResponse>>, AppError> {
+        dto.validate()?;
+        let mut tx = state.db.pool.begin().await?;
+
+        // Serialize toggles for the same (user, book) so double-taps can't both insert
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1 || ':' || $2))")
+            .bind(&user.id)
+            .bind(&dto.book_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let removed = sqlx::query_scalar::<_, String>(
+            r#"DELETE FROM "Bookmark" WHERE user_id = $1 AND book_id = $2 RETURNING id"#,
+        )
+        .bind(&user.id)
+        .bind(&dto.book_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        if !removed.is_empty() {
+            tx.commit().await?;
+            Self::invalidate_count(&state, &dto.book_id).await;
+            state
+                .notification
+                .spawn_novel_topic_sync(&user.id, &dto.book_id, false);
+            tracing::info!(user_id = %user.id, book_id = %dto.book_id, "Bookmark toggled off");
+
+            return Ok(Json(ApiResponse::success(BookmarkStatusResponse {
+                is_bookmarked: false,
+                bookmark_id: None,
+            })));
+        }
+
+        let book_exists =
+            sqlx::query_scalar::<_, bool>(r#"SELECT EXISTS(SELECT 1 FROM "Book" WHERE id = $1)"#)
+                .bind(&dto.book_id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        if !book_exists {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let id = cuid2::create_id();
+        let now = Utc::now().naive_utc();
+
+        sqlx::query(
+            r#"
+            INSERT INTO "Bookmark" (id, user_id, book_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+        .bind(&id)
+        .bind(&user.id)
+        .bind(&dto.book_id)
+        .bind(now)
+        .bind(now)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Self::invalidate_count(&state, &dto.book_id).await;
+        state
+            .notification
+            .spawn_novel_topic_sync(&user.id, &dto.book_id, true);
+        tracing::info!(user_id = %user.id, book_id = %dto.book_id, "Bookmark toggled on");
+
+        Ok(Json(ApiResponse::success(BookmarkStatusResponse {
+            is_bookmarked: true,
+            bookmark_id: Some(id),
+        })))
     }
 
     /// Delete a bookmark
@@ -96,15 +185,19 @@ impl BookmarkHandler {
         Extension(user): Extension<AuthUser>,
         Path(id): Path<String>,
     ) -> Result<StatusCode, AppError> {
-        let result = sqlx::query(r#"DELETE FROM "Bookmark" WHERE id = $1 AND user_id = $2"#)
-            .bind(&id)
-            .bind(&user.id)
-            .execute(&state.db.pool)
-            .await?;
+        let book_id = sqlx::query_scalar::<_, String>(
+            r#"DELETE FROM "Bookmark" WHERE id = $1 AND user_id = $2 RETURNING book_id"#,
+        )
+        .bind(&id)
+        .bind(&user.id)
+        .fetch_optional(&state.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Bookmark not found".to_string()))?;
 
-        if result.rows_affected() == 0 {
-            return Err(AppError::NotFound("Bookmark not found".to_string()));
-        }
+        Self::invalidate_count(&state, &book_id).await;
+        state
+            .notification
+            .spawn_novel_topic_sync(&user.id, &book_id, false);
 
         tracing::info!(bookmark_id = %id, user_id = %user.id, "Bookmark deleted");
 
@@ -128,18 +221,34 @@ impl BookmarkHandler {
             return Err(AppError::NotFound("Bookmark not found".to_string()));
         }
 
+        Self::invalidate_count(&state, &book_id).await;
+        state
+            .notification
+            .spawn_novel_topic_sync(&user.id, &book_id, false);
+
         tracing::info!(book_id = %book_id, user_id = %user.id, "Bookmark deleted");
 
         Ok(StatusCode::NO_CONTENT)
     }
 
-    /// Get all bookmarks for current user
-    /// GET /api/bookmarks
+    /// Get the current user's bookmarks, a page at a time
+    /// GET /api/bookmarks?page=&page_size=&sort=recent|title
     pub async fn get_user_bookmarks(
         State(state): State<AppState>,
         Extension(user): Extension<AuthUser>,
-    ) -> Result<Json<Vec<BookmarkWithBookResponse>>, AppError> {
-        let bookmarks = sqlx::query_as::<_, BookmarkWithBook>(
+        Query(query): Query<BookmarkQuery>,
+    ) -> Result<Json<PaginatedApiResponse<BookmarkWithBookResponse>>, AppError> {
+        query.validate()?;
+        let sort = query.sort()?;
+        let offset = page_offset(query.page, query.page_size)?;
+
+        let total_items =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Bookmark" WHERE user_id = $1"#)
+                .bind(&user.id)
+                .fetch_one(&state.db.pool)
+                .await?;
+
+        let sql = format!(
             r#"
             SELECT 
                 b.id,
@@ -149,23 +258,37 @@ impl BookmarkHandler {
                 bk.title as book_title,
                 bk.cover as book_cover,
                 bk.author as book_author,
-                bk.description as book_description
+                bk.description as book_description,
+                rp.chapter_id as progress_chapter_id,
+                rp.chapter_num as progress_chapter_num,
+                rp.percent as progress_percent,
+                rp.updated_at as progress_updated_at
             FROM "Bookmark" b
             JOIN "Book" bk ON b.book_id = bk.id
+            LEFT JOIN "ReadingProgress" rp ON rp.user_id = b.user_id AND rp.book_id = b.book_id
             WHERE b.user_id = $1
-            ORDER BY b.created_at DESC
+            {}
+            LIMIT $2 OFFSET $3
             "#,
-        )
-        .bind(&user.id)
-        .fetch_all(&state.db.pool)
-        .await?;
+            sort.order_by()
+        );
 
-        let response: Vec<BookmarkWithBookResponse> = bookmarks
+        let bookmarks = sqlx::query_as::<_, BookmarkWithBook>(&sql)
+            .bind(&user.id)
+            .bind(query.page_size)
+            .bind(offset)
+            .fetch_all(&state.db.pool)
+            .await?;
+
+        let data: Vec<BookmarkWithBookResponse> = bookmarks
             .into_iter()
             .map(BookmarkWithBookResponse::from)
             .collect();
 
-        Ok(Json(response))
+        Ok(Json(
+            PaginatedResponse::new(data, query.page, query.page_size, total_items)
+                .into_api_response(),
+        ))
     }
 
     /// Check if a book is bookmarked by current user
@@ -174,7 +297,7 @@ impl BookmarkHandler {
         State(state): State<AppState>,
         Extension(user): Extension<AuthUser>,
         Path(book_id): Path<String>,
-    ) -> Result<Json<BookmarkStatusResponse>, AppError> {
+    ) -> Result<Json<ApiResponse<BookmarkStatusResponse>>, AppError> {
         let bookmark = sqlx::query_as::<_, Bookmark>(
             r#"SELECT id, user_id, book_id, created_at, updated_at 
                FROM "Bookmark" WHERE user_id = $1 AND book_id = $2"#,
@@ -184,9 +307,85 @@ impl BookmarkHandler {
         .fetch_optional(&state.db.pool)
         .await?;
 
-        Ok(Json(BookmarkStatusResponse {
+        Ok(Json(ApiResponse::success(BookmarkStatusResponse {
             is_bookmarked: bookmark.is_some(),
             bookmark_id: bookmark.map(|b| b.id),
-        }))
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::user_model::Role;
+    use crate::test_support;
+    use axum::{body::to_bytes, response::IntoResponse};
+    use serde_json::Value;
+    use sqlx::PgPool;
+
+    fn to_json<T: serde::Serialize>(value: T) -> Value {
+        serde_json::to_value(value).unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn bookmark_endpoints_share_the_standard_envelope(pool: PgPool) {
+        let user_id = test_support::seed_user(&pool, "enveloped").await;
+        let book_id = test_support::seed_book(&pool, "Enveloped", 0).await;
+        let state = test_support::app_state_with(
+            test_support::config(),
+            test_support::database(pool.clone()),
+        );
+        let user = AuthUser {
+            id: user_id,
+            email: "enveloped@example.com".to_string(),
+            role: Role::User,
+        };
+
+        let (status, Json(created)) = BookmarkHandler::create_bookmark(
+            State(state.clone()),
+            Extension(user.clone()),
+            Json(CreateBookmarkDto {
+                book_id: book_id.clone(),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        let created = to_json(created);
+        assert_eq!(created["status"], "success");
+        assert_eq!(created["data"]["book_id"], book_id);
+
+        let Json(listed) = BookmarkHandler::get_user_bookmarks(
+            State(state.clone()),
+            Extension(user.clone()),
+            Query(BookmarkQuery {
+                page: 1,
+                page_size: 10,
+                sort: None,
+            }),
+        )
+        .await
+        .unwrap();
+        let listed = to_json(listed);
+        assert_eq!(listed["status"], "success");
+        assert_eq!(listed["data"].as_array().unwrap().len(), 1);
+        assert_eq!(listed["meta"]["total_items"], 1);
+        assert_eq!(listed["meta"]["current_page"], 1);
+
+        let missing = BookmarkHandler::delete_bookmark(
+            State(state),
+            Extension(user),
+            Path("no-such-bookmark".to_string()),
+        )
+        .await
+        .unwrap_err()
+        .into_response();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+        let body = to_bytes(missing.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["error"]["code"], "NOT_FOUND");
+        assert_eq!(body["error"]["message"], "Bookmark not found");
     }
 }
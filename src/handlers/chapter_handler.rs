@@ -1,24 +1,37 @@
 use crate::middleware::auth::AuthUser;
-use crate::models::chapter_model::{ChapterDto, CreateChapterDto, UpdateChapterDto};
-use crate::models::paging_model::{PaginatedResponse, PaginationParams};
+use crate::models::chapter_model::{
+    ChapterDto, CreateChapterDto, ReorderChaptersDto, UpdateChapterDto,
+};
+use crate::models::paging_model::{PaginatedApiResponse, PaginationParams};
 use crate::models::response_model::ApiResponse;
+use crate::models::upload_model::CreateChapterFromUploadDto;
 use crate::models::user_model::Role;
 use crate::require_role;
 use crate::services::chapter_service::ChapterService;
+use crate::utils::etag::conditional_json;
+use crate::utils::field_limits::LengthLimited;
 use crate::{errors::AppError, AppState};
 use axum::Extension;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
+    response::Response,
     Json,
 };
 use tracing::{error, info, instrument};
 
+/// Chapter text rarely changes once published; neighbours do when chapters are added
+const CHAPTER_CACHE_CONTROL: &str = "public, max-age=300, must-revalidate";
+
 pub struct ChapterHandler;
 
 impl ChapterHandler {
     fn create_service(state: &AppState) -> ChapterService {
-        ChapterService::new(state.db.clone(), &state.notification)
+        ChapterService::new(
+            state.db.clone(),
+            state.notification.clone(),
+            state.config.reading_wpm,
+        )
     }
 
     #[instrument(skip(state), fields(
@@ -28,17 +41,18 @@ impl ChapterHandler {
     pub async fn get_chapters(
         State(state): State<AppState>,
         Query(params): Query<PaginationParams>,
-    ) -> Result<Json<PaginatedResponse<ChapterDto>>, AppError> {
+    ) -> Result<Json<PaginatedApiResponse<ChapterDto>>, AppError> {
         info!("Fetching chapters with pagination");
+        params.validate()?;
         let service = Self::create_service(&state);
         let paginated = service.get_chapters(params).await?;
         info!(
-            total_items = paginated.total_items,
-            total_pages = paginated.total_pages,
+            total_items = paginated.meta.total_items,
+            total_pages = paginated.meta.total_pages,
             "Chapters fetched successfully"
         );
 
-        Ok(Json(paginated))
+        Ok(Json(paginated.into_api_response()))
     }
 
     #[tracing::instrument(
@@ -54,31 +68,37 @@ impl ChapterHandler {
         State(state): State<AppState>,
         Path(book_id): Path<String>,
         Query(params): Query<PaginationParams>,
-    ) -> Result<Json<PaginatedResponse<ChapterDto>>, AppError> {
+    ) -> Result<Json<PaginatedApiResponse<ChapterDto>>, AppError> {
         info!("Fetching chapters for book");
+        params.validate()?;
 
         let service = Self::create_service(&state);
         let paginated = service
             .get_chapters_by_book(book_id.clone(), params)
             .await?;
         info!(
-            total_chapters = paginated.total_items,
+            total_chapters = paginated.meta.total_items,
             "Chapters fetched successfully"
         );
 
-        Ok(Json(paginated))
+        Ok(Json(paginated.into_api_response()))
     }
 
-    #[instrument(skip(state), fields(chapter_id = %id))]
+    #[instrument(skip(state, headers), fields(chapter_id = %id))]
     pub async fn get_chapter(
         State(state): State<AppState>,
         Path(id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<ChapterDto>>), AppError> {
+        headers: HeaderMap,
+    ) -> Result<Response, AppError> {
         info!("Fetching single chapter");
         let service = Self::create_service(&state);
         let chapter = service.get_chapter(id).await?;
-        info!(chapter_title = %chapter.title, "chapter fetched successfully");
-        Ok((StatusCode::OK, Json(ApiResponse::success(chapter))))
+        info!(chapter_title = %chapter.chapter.title, "chapter fetched successfully");
+        Ok(conditional_json(
+            &headers,
+            &ApiResponse::success(chapter),
+            CHAPTER_CACHE_CONTROL,
+        ))
     }
 
     #[instrument(skip(state, request), fields(
@@ -93,7 +113,8 @@ impl ChapterHandler {
     ) -> Result<(StatusCode, Json<ApiResponse<ChapterDto>>), AppError> {
         info!(user_role = ?auth_user.role, "Creating chapter by user");
 
-        require_role!(auth_user, Role::Admin);
+        require_role!(auth_user, Role::Moderator);
+        request.check_lengths(&state.config.field_limits)?;
         let service = Self::create_service(&state);
 
         match service.create_chapter(request).await {
@@ -118,6 +139,45 @@ impl ChapterHandler {
         }
     }
 
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        upload_id = %request.upload_id,
+        chapter_title = %request.title
+    ))]
+    pub async fn create_chapter_from_upload(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<CreateChapterFromUploadDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<ChapterDto>>), AppError> {
+        info!(user_role = ?auth_user.role, "Creating chapter from upload by user");
+
+        require_role!(auth_user, Role::Moderator);
+        request.check_lengths(&state.config.field_limits)?;
+        let service = Self::create_service(&state);
+
+        match service.create_chapter_from_upload(request).await {
+            Ok(chapter) => {
+                info!(
+                    chapter_id = %chapter.id,
+                    chapter_title = %chapter.title,
+                    "Chapter created from upload successfully"
+                );
+                Ok((
+                    StatusCode::CREATED,
+                    Json(ApiResponse::with_message(
+                        "Chapter created successfully",
+                        chapter,
+                    )),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to create chapter from upload");
+                Err(e)
+            }
+        }
+    }
+
     #[instrument(skip(state, request), fields(
         chapter_id = %id,
         user_id = %auth_user.id,
@@ -130,7 +190,8 @@ impl ChapterHandler {
         Json(request): Json<UpdateChapterDto>,
     ) -> Result<(StatusCode, Json<ApiResponse<ChapterDto>>), AppError> {
         info!(user_role = ?auth_user.role, "Updating chapter by user");
-        require_role!(auth_user, Role::Admin);
+        require_role!(auth_user, Role::Moderator);
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -156,6 +217,40 @@ impl ChapterHandler {
         }
     }
 
+    /// Renumber all chapters of a book in the given order
+    /// PATCH /api/books/{id}/chapters/reorder
+    #[instrument(skip(state, request), fields(
+        book_id = %book_id,
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        count = request.chapter_ids.len()
+    ))]
+    pub async fn reorder_chapters(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+        Json(request): Json<ReorderChaptersDto>,
+    ) -> Result<Json<ApiResponse<Vec<ChapterDto>>>, AppError> {
+        info!(user_role = ?auth_user.role, "Reordering chapters by user");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.reorder_chapters(book_id, request).await {
+            Ok(chapters) => {
+                info!(count = chapters.len(), "Chapters reordered successfully");
+                Ok(Json(ApiResponse::with_message(
+                    "Chapters reordered successfully",
+                    chapters,
+                )))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to reorder chapters");
+                Err(e)
+            }
+        }
+    }
+
     #[instrument(skip(state), fields(
         chapter_id = %id,
         user_id = %auth_user.id,
@@ -165,7 +260,7 @@ impl ChapterHandler {
         State(state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
         Path(id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<()>>), AppError> {
+    ) -> Result<StatusCode, AppError> {
         info!(user_role = ?auth_user.role, "Deleting chapter by user");
         require_role!(auth_user, Role::Admin);
 
@@ -174,13 +269,7 @@ impl ChapterHandler {
         match service.delete_chapter(id.clone()).await {
             Ok(_) => {
                 info!(chapter_id = %id, "Chapter deleted successfully");
-                Ok((
-                    StatusCode::NO_CONTENT,
-                    Json(ApiResponse::with_message(
-                        "Chapter deleted successfully",
-                        (),
-                    )),
-                ))
+                Ok(StatusCode::NO_CONTENT)
             }
             Err(e) => {
                 error!(error = ?e, "Failed to delete Chapter");
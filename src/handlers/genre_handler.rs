@@ -1,20 +1,28 @@
 use crate::{
     errors::AppError,
     middleware::auth::AuthUser,
-    models::genre_model::{CreateGenreDto, GenreDto, UpdateGenreDto},
+    models::book_model::BookDto,
+    models::genre_model::{
+        AddBookGenresDto, CreateGenreDto, DeleteGenreQuery, GenreDto, GenreQuery, GenreTrendParams,
+        GenreTrendsResponse, UpdateGenreDto,
+    },
+    models::paging_model::{PaginatedApiResponse, PaginationParams},
     models::response_model::ApiResponse,
     models::user_model::Role,
     require_role,
     services::genre_service::GenreService,
+    utils::field_limits::LengthLimited,
     AppState,
 };
 use axum::http::StatusCode;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     Extension, Json,
 };
 use tracing::{error, info, instrument};
 
+type GenreList = Vec<GenreDto>;
+
 pub struct GenreHandler;
 
 impl GenreHandler {
@@ -34,6 +42,7 @@ impl GenreHandler {
     ) -> Result<(StatusCode, Json<ApiResponse<GenreDto>>), AppError> {
         info!("Attempting to create genre");
         require_role!(auth_user, Role::Admin);
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -67,6 +76,7 @@ impl GenreHandler {
     ) -> Result<(StatusCode, Json<ApiResponse<GenreDto>>), AppError> {
         info!("Attempting to update genre");
         require_role!(auth_user, Role::Admin);
+        request.check_lengths(&state.config.field_limits)?;
 
         let service = Self::create_service(&state);
 
@@ -101,18 +111,16 @@ impl GenreHandler {
         State(state): State<AppState>,
         Extension(auth_user): Extension<AuthUser>,
         Path(id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<()>>), AppError> {
+        Query(query): Query<DeleteGenreQuery>,
+    ) -> Result<StatusCode, AppError> {
         require_role!(auth_user, Role::Admin);
 
         let service = Self::create_service(&state);
 
-        match service.delete_genre(id).await {
+        match service.delete_genre(id, query.force).await {
             Ok(_) => {
                 info!("genre deleted successfully");
-                Ok((
-                    StatusCode::NO_CONTENT,
-                    Json(ApiResponse::with_message("Genre deleted successfully", ())),
-                ))
+                Ok(StatusCode::NO_CONTENT)
             }
             Err(e) => {
                 error!(error = ?e, "Failed to delete genre");
@@ -133,26 +141,153 @@ impl GenreHandler {
         Ok((StatusCode::OK, Json(ApiResponse::success(genre))))
     }
 
-    #[tracing::instrument(name = "get_genres", skip(state))]
+    #[instrument(skip(state), fields(genre_slug = %slug))]
+    pub async fn get_genre_by_slug(
+        State(state): State<AppState>,
+        Path(slug): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<GenreDto>>), AppError> {
+        info!("Fetching genre by slug");
+        let service = Self::create_service(&state);
+        let genre = service.get_genre_by_slug(&slug).await?;
+        info!(genre_id = %genre.id, "Genre fetched successfully");
+
+        Ok((StatusCode::OK, Json(ApiResponse::success(genre))))
+    }
+
+    #[instrument(skip(state), fields(
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
     pub async fn get_genres(
         State(state): State<AppState>,
-    ) -> Result<(StatusCode, Json<ApiResponse<Vec<GenreDto>>>), AppError> {
+        Query(params): Query<GenreQuery>,
+    ) -> Result<Json<PaginatedApiResponse<GenreDto>>, AppError> {
         info!("Fetching genres");
+        params.validate()?;
+
         let service = Self::create_service(&state);
-        let genres = service.get_genres().await?;
-        info!(count = genres.len(), "Genres fetched successfully");
-        Ok((StatusCode::OK, Json(ApiResponse::success(genres))))
+        let paginated = service.get_genres(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Genres fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
     }
 
     #[instrument(skip(state), fields(book_id = %book_id))]
     pub async fn get_genres_by_book(
         State(state): State<AppState>,
         Path(book_id): Path<String>,
-    ) -> Result<(StatusCode, Json<ApiResponse<Vec<GenreDto>>>), AppError> {
+    ) -> Result<(StatusCode, Json<ApiResponse<GenreList>>), AppError> {
         info!("Fetching genres for book");
         let service = Self::create_service(&state);
         let genres = service.get_genres_by_book(book_id).await?;
         info!(count = genres.len(), "Book genres fetched successfully");
         Ok((StatusCode::OK, Json(ApiResponse::success(genres))))
     }
+
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        days = params.days,
+        limit = params.limit
+    ))]
+    pub async fn get_genre_trends(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Query(params): Query<GenreTrendParams>,
+    ) -> Result<(StatusCode, Json<ApiResponse<GenreTrendsResponse>>), AppError> {
+        info!("Fetching genre trends");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+        let trends = service.get_genre_trends(params).await?;
+        info!(
+            count = trends.genres.len(),
+            "Genre trends fetched successfully"
+        );
+        Ok((StatusCode::OK, Json(ApiResponse::success(trends))))
+    }
+
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %book_id
+    ))]
+    pub async fn add_genres_to_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+        Json(request): Json<AddBookGenresDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<GenreList>>), AppError> {
+        info!("Attempting to add genres to book");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.add_genres_to_book(book_id, request).await {
+            Ok(genres) => {
+                info!(count = genres.len(), "Genres added to book successfully");
+                Ok((
+                    StatusCode::OK,
+                    Json(ApiResponse::with_message("Genres added to book", genres)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to add genres to book");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %book_id,
+        genre_id = %genre_id
+    ))]
+    pub async fn remove_genre_from_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path((book_id, genre_id)): Path<(String, String)>,
+    ) -> Result<StatusCode, AppError> {
+        info!("Attempting to remove genre from book");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.remove_genre_from_book(book_id, genre_id).await {
+            Ok(_) => {
+                info!("Genre removed from book successfully");
+                Ok(StatusCode::NO_CONTENT)
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to remove genre from book");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(state), fields(
+        genre_id = %genre_id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_books_by_genre(
+        State(state): State<AppState>,
+        Path(genre_id): Path<String>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<BookDto>>, AppError> {
+        info!("Fetching books for genre");
+        params.validate()?;
+        let service = Self::create_service(&state);
+        let paginated = service.get_books_by_genre(genre_id, params).await?;
+        info!(
+            total_items = paginated.meta.total_items,
+            "Genre books fetched successfully"
+        );
+        Ok(Json(paginated.into_api_response()))
+    }
 }
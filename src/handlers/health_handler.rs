@@ -4,6 +4,7 @@ use axum::response::IntoResponse;
 use http::StatusCode;
 use crate::AppState;
 
+/// Liveness probe: answers as long as the process is serving, without touching the database
 pub async fn health_checker_handler() -> impl IntoResponse {
     const MESSAGE: &str = "Simple CRUD API with Rust, SQLX, Postgres, and Axum";
 
@@ -14,22 +15,80 @@ pub async fn health_checker_handler() -> impl IntoResponse {
 }
 
 pub async fn db_health_check(State(state): State<AppState>) -> impl IntoResponse {
-    match state.db.test_connection().await {
+    let (status, body_status, message, database) = match state.db.test_connection().await {
         Ok(_) => (
             StatusCode::OK,
-            Json(serde_json::json!({
-                "status": "success",
-                "message": "Database connection is healthy",
-                "database": "connected"
-            })),
+            "success",
+            "Database connection is healthy".to_string(),
+            "connected",
         ),
         Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "status": "error",
-                "message": format!("Database connection failed: {}", e),
-                "database": "disconnected"
-            })),
+            StatusCode::SERVICE_UNAVAILABLE,
+            "error",
+            format!("Database connection failed: {}", e),
+            "disconnected",
         ),
-    }
-}
\ No newline at end of file
+    };
+
+    let redis = &state.db.redis;
+    let cache_status = if !redis.is_enabled() {
+        "disabled"
+    } else if redis.test_connection().await.is_ok() {
+        "connected"
+    } else {
+        "disconnected"
+    };
+    let cache_stats = redis.cache_stats();
+
+    // Same keys on success and failure so dashboards can scrape either
+    (
+        status,
+        Json(serde_json::json!({
+            "status": body_status,
+            "message": message,
+            "database": database,
+            "pool": {
+                "size": state.db.pool.size(),
+                "idle": state.db.pool.num_idle(),
+            },
+            "cache": {
+                "status": cache_status,
+                "hits": cache_stats.hits,
+                "misses": cache_stats.misses,
+            },
+            "version": env!("CARGO_PKG_VERSION"),
+            "uptime_secs": state.started_at.elapsed().as_secs(),
+        })),
+    )
+}
+
+/// Readiness probe: the database answers and the schema is up to date
+pub async fn readiness_check(State(state): State<AppState>) -> impl IntoResponse {
+    let database = state.db.test_connection().await;
+    let migrations = match &database {
+        Ok(_) => state.db.migrations_applied().await.ok(),
+        Err(_) => None,
+    };
+
+    let ready = database.is_ok() && migrations == Some(true);
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let migrations = match migrations {
+        Some(true) => "applied",
+        Some(false) => "pending",
+        None => "unknown",
+    };
+
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if ready { "success" } else { "error" },
+            "database": if database.is_ok() { "connected" } else { "disconnected" },
+            "migrations": migrations,
+        })),
+    )
+}
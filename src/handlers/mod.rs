@@ -1,7 +1,16 @@
 pub mod auth_handler;
+pub mod author_handler;
 pub mod book_handler;
 pub mod bookmark_handler;
 pub mod chapter_handler;
 pub mod genre_handler;
 pub mod health_handler;
+pub mod notification_handler;
+pub mod reading_progress_handler;
+pub mod review_handler;
+pub mod robots_handler;
+pub mod series_handler;
+pub mod stats_handler;
+pub mod tag_handler;
 pub mod upload_handler;
+pub mod user_handler;
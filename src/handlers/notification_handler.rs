@@ -0,0 +1,116 @@
+use crate::middleware::auth::AuthUser;
+use crate::models::notification_model::{
+    MarkAllReadResponse, NotificationDto, NotificationPreferences,
+};
+use crate::models::paging_model::{PaginatedApiResponse, PaginationParams};
+use crate::models::response_model::ApiResponse;
+use crate::{errors::AppError, AppState};
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+
+pub struct NotificationHandler;
+
+impl NotificationHandler {
+    /// List the current user's inbox
+    /// GET /api/notifications
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_notifications(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<NotificationDto>>, AppError> {
+        info!("Fetching notifications");
+        params.validate()?;
+
+        let paginated = state
+            .notification
+            .get_notifications(&auth_user.id, params)
+            .await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Notifications fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    /// Mark one of the current user's notifications as read
+    /// PATCH /api/notifications/{id}/read
+    #[instrument(skip(state), fields(user_id = %auth_user.id, notification_id = %id))]
+    pub async fn mark_read(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+    ) -> Result<Json<ApiResponse<NotificationDto>>, AppError> {
+        info!("Marking notification as read");
+
+        match state.notification.mark_read(&auth_user.id, &id).await {
+            Ok(notification) => Ok(Json(ApiResponse::success(notification))),
+            Err(e) => {
+                error!(error = ?e, "Failed to mark notification as read");
+                Err(e)
+            }
+        }
+    }
+
+    /// Mark all of the current user's notifications as read
+    /// POST /api/notifications/read-all
+    #[instrument(skip(state), fields(user_id = %auth_user.id))]
+    pub async fn mark_all_read(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+    ) -> Result<Json<ApiResponse<MarkAllReadResponse>>, AppError> {
+        info!("Marking all notifications as read");
+
+        match state.notification.mark_all_read(&auth_user.id).await {
+            Ok(marked) => {
+                info!(marked, "Notifications marked as read");
+                Ok(Json(ApiResponse::success(MarkAllReadResponse { marked })))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to mark notifications as read");
+                Err(e)
+            }
+        }
+    }
+
+    /// Update the current user's push preferences
+    /// PUT /api/users/me/notifications
+    #[instrument(skip(state), fields(user_id = %auth_user.id))]
+    pub async fn update_preferences(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<NotificationPreferences>,
+    ) -> Result<Json<ApiResponse<NotificationPreferences>>, AppError> {
+        info!(
+            notify_new_chapters = request.notify_new_chapters,
+            "Updating notification preferences"
+        );
+
+        match state
+            .notification
+            .update_preferences(&auth_user.id, &request)
+            .await
+        {
+            Ok(preferences) => {
+                state.notification.spawn_user_topic_sync(&auth_user.id);
+                Ok(Json(ApiResponse::with_message(
+                    "Notification preferences updated",
+                    preferences,
+                )))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to update notification preferences");
+                Err(e)
+            }
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use crate::middleware::auth::AuthUser;
+use crate::models::reading_progress_model::{ReadingProgressDto, UpdateReadingProgressDto};
+use crate::models::response_model::ApiResponse;
+use crate::services::reading_progress_service::ReadingProgressService;
+use crate::{errors::AppError, AppState};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+
+pub struct ReadingProgressHandler;
+
+impl ReadingProgressHandler {
+    fn create_service(state: &AppState) -> ReadingProgressService {
+        ReadingProgressService::new(state.db.clone())
+    }
+
+    /// Save the current user's position in a book
+    /// PUT /api/books/{id}/progress
+    #[instrument(skip(state, request), fields(user_id = %auth_user.id, book_id = %book_id))]
+    pub async fn upsert_progress(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+        Json(request): Json<UpdateReadingProgressDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<ReadingProgressDto>>), AppError> {
+        info!("Saving reading progress");
+
+        let service = Self::create_service(&state);
+
+        match service
+            .upsert_progress(&auth_user.id, book_id, request)
+            .await
+        {
+            Ok(progress) => {
+                info!(
+                    chapter_num = progress.chapter_num,
+                    percent = progress.percent,
+                    "Reading progress saved"
+                );
+                Ok((StatusCode::OK, Json(ApiResponse::success(progress))))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to save reading progress");
+                Err(e)
+            }
+        }
+    }
+
+    /// Get the current user's position in a book
+    /// GET /api/books/{id}/progress
+    #[instrument(skip(state), fields(user_id = %auth_user.id, book_id = %book_id))]
+    pub async fn get_progress(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<ReadingProgressDto>>), AppError> {
+        let service = Self::create_service(&state);
+        let progress = service.get_progress(&auth_user.id, book_id).await?;
+
+        Ok((StatusCode::OK, Json(ApiResponse::success(progress))))
+    }
+}
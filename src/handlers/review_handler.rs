@@ -0,0 +1,132 @@
+use crate::middleware::auth::AuthUser;
+use crate::models::paging_model::{PaginatedApiResponse, PaginationParams};
+use crate::models::response_model::ApiResponse;
+use crate::models::review_model::{CreateReviewDto, ReviewDto, UpdateReviewDto};
+use crate::models::user_model::Role;
+use crate::services::review_service::ReviewService;
+use crate::utils::field_limits::LengthLimited;
+use crate::{errors::AppError, AppState};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+
+pub struct ReviewHandler;
+
+impl ReviewHandler {
+    fn create_service(state: &AppState) -> ReviewService {
+        ReviewService::new(state.db.clone())
+    }
+
+    /// Review a book as the current user
+    /// POST /api/books/{id}/reviews
+    #[instrument(skip(state, request), fields(user_id = %auth_user.id, book_id = %book_id))]
+    pub async fn create_review(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+        Json(request): Json<CreateReviewDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<ReviewDto>>), AppError> {
+        info!("Attempting to create review");
+        request.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.create_review(&auth_user.id, book_id, request).await {
+            Ok(review) => {
+                info!(review_id = %review.id, rating = review.rating, "Review created successfully");
+                Ok((
+                    StatusCode::CREATED,
+                    Json(ApiResponse::with_message("Review created", review)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to create review");
+                Err(e)
+            }
+        }
+    }
+
+    /// Update one of the current user's reviews
+    /// PUT /api/reviews/{id}
+    #[instrument(skip(state, request), fields(user_id = %auth_user.id, review_id = %id))]
+    pub async fn update_review(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+        Json(request): Json<UpdateReviewDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<ReviewDto>>), AppError> {
+        info!("Attempting to update review");
+        request.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.update_review(&auth_user.id, id, request).await {
+            Ok(review) => {
+                info!("Review updated successfully");
+                Ok((
+                    StatusCode::OK,
+                    Json(ApiResponse::with_message("Review updated", review)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to update review");
+                Err(e)
+            }
+        }
+    }
+
+    /// Delete one of the current user's reviews; moderators may delete any review
+    /// DELETE /api/reviews/{id}
+    #[instrument(skip(state), fields(user_id = %auth_user.id, review_id = %id))]
+    pub async fn delete_review(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+    ) -> Result<StatusCode, AppError> {
+        info!("Attempting to delete review");
+
+        let service = Self::create_service(&state);
+
+        let moderate = auth_user.role.satisfies(&Role::Moderator);
+
+        match service.delete_review(&auth_user.id, id, moderate).await {
+            Ok(_) => {
+                info!("Review deleted successfully");
+                Ok(StatusCode::NO_CONTENT)
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to delete review");
+                Err(e)
+            }
+        }
+    }
+
+    /// List reviews for a book
+    /// GET /api/books/{id}/reviews
+    #[instrument(skip(state), fields(
+        book_id = %book_id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn get_reviews_by_book(
+        State(state): State<AppState>,
+        Path(book_id): Path<String>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<ReviewDto>>, AppError> {
+        info!("Fetching reviews for book");
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.get_reviews_by_book(book_id, params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Reviews fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+}
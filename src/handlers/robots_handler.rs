@@ -0,0 +1,36 @@
+use crate::AppState;
+use axum::extract::State;
+use axum::http::header;
+use axum::response::IntoResponse;
+
+/// Serve the configured crawler policy
+/// GET /robots.txt
+pub async fn robots_txt_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
+        state.config.robots_txt.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use axum::body::to_bytes;
+
+    #[tokio::test]
+    async fn serves_the_configured_policy() {
+        let mut config = test_support::config();
+        config.robots_txt = "User-agent: *\nDisallow: /\n".to_string();
+        let state = test_support::app_state(config);
+
+        let response = robots_txt_handler(State(state)).await.into_response();
+
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"User-agent: *\nDisallow: /\n");
+    }
+}
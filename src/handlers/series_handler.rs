@@ -0,0 +1,90 @@
+use crate::{
+    errors::AppError,
+    middleware::auth::AuthUser,
+    models::book_model::BookDto,
+    models::response_model::ApiResponse,
+    models::series_model::{CreateSeriesDto, SeriesDto},
+    models::user_model::Role,
+    require_role,
+    services::series_service::SeriesService,
+    utils::field_limits::LengthLimited,
+    AppState,
+};
+use axum::http::StatusCode;
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+use validator::Validate;
+
+type BookList = Vec<BookDto>;
+
+pub struct SeriesHandler;
+
+impl SeriesHandler {
+    fn create_service(state: &AppState) -> SeriesService {
+        SeriesService::new(state.db.clone())
+    }
+
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        series_title = %request.title
+    ))]
+    pub async fn create_series(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<CreateSeriesDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<SeriesDto>>), AppError> {
+        info!("Attempting to create series");
+        require_role!(auth_user, Role::Admin);
+        request.validate()?;
+        request.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.create_series(request).await {
+            Ok(series) => {
+                info!(series_id = %series.id, "Series created successfully");
+                Ok((
+                    StatusCode::CREATED,
+                    Json(ApiResponse::with_message(
+                        "Series created successfully",
+                        series,
+                    )),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to create series");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(state), fields(series_id = %id))]
+    pub async fn get_series(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<SeriesDto>>), AppError> {
+        info!("Fetching single series");
+        let service = Self::create_service(&state);
+        let series = service.get_series(id).await?;
+        info!(series_title = %series.title, "Series fetched successfully");
+
+        Ok((StatusCode::OK, Json(ApiResponse::success(series))))
+    }
+
+    #[instrument(skip(state), fields(series_id = %id))]
+    pub async fn get_books_in_series(
+        State(state): State<AppState>,
+        Path(id): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<BookList>>), AppError> {
+        info!("Fetching books in series");
+        let service = Self::create_service(&state);
+        let books = service.get_books_in_series(id).await?;
+        info!(count = books.len(), "Series books fetched successfully");
+
+        Ok((StatusCode::OK, Json(ApiResponse::success(books))))
+    }
+}
@@ -0,0 +1,41 @@
+use crate::{
+    errors::AppError,
+    middleware::auth::AuthUser,
+    models::response_model::ApiResponse,
+    models::stats_model::SiteStatsDto,
+    models::user_model::Role,
+    require_role,
+    services::stats_service::StatsService,
+    AppState,
+};
+use axum::{extract::State, Extension, Json};
+use tracing::{error, info, instrument};
+
+pub struct StatsHandler;
+
+impl StatsHandler {
+    fn create_service(state: &AppState) -> StatsService {
+        StatsService::new(state.db.clone())
+    }
+
+    /// Totals for the admin dashboard, cached for a minute
+    /// GET /api/admin/stats
+    #[instrument(skip(state), fields(user_id = %auth_user.id))]
+    pub async fn get_stats(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+    ) -> Result<Json<ApiResponse<SiteStatsDto>>, AppError> {
+        info!("Fetching site stats");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.get_site_stats().await {
+            Ok(stats) => Ok(Json(ApiResponse::success(stats))),
+            Err(e) => {
+                error!(error = ?e, "Failed to fetch site stats");
+                Err(e)
+            }
+        }
+    }
+}
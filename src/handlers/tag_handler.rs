@@ -0,0 +1,99 @@
+use crate::{
+    errors::AppError,
+    middleware::auth::AuthUser,
+    models::response_model::ApiResponse,
+    models::tag_model::{AddBookTagsDto, TagDto},
+    models::user_model::Role,
+    require_role,
+    services::tag_service::TagService,
+    utils::field_limits::LengthLimited,
+    AppState,
+};
+use axum::http::StatusCode;
+use axum::{
+    extract::{Path, State},
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+
+type TagList = Vec<TagDto>;
+
+pub struct TagHandler;
+
+impl TagHandler {
+    fn create_service(state: &AppState) -> TagService {
+        TagService::new(state.db.clone())
+    }
+
+    #[instrument(skip(state), fields(book_id = %book_id))]
+    pub async fn get_tags_by_book(
+        State(state): State<AppState>,
+        Path(book_id): Path<String>,
+    ) -> Result<(StatusCode, Json<ApiResponse<TagList>>), AppError> {
+        info!("Fetching tags for book");
+        let service = Self::create_service(&state);
+        let tags = service.get_tags_by_book(book_id).await?;
+        info!(count = tags.len(), "Book tags fetched successfully");
+        Ok((StatusCode::OK, Json(ApiResponse::success(tags))))
+    }
+
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %book_id
+    ))]
+    pub async fn add_tags_to_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(book_id): Path<String>,
+        Json(request): Json<AddBookTagsDto>,
+    ) -> Result<(StatusCode, Json<ApiResponse<TagList>>), AppError> {
+        info!("Attempting to add tags to book");
+        require_role!(auth_user, Role::Admin);
+        request.check_lengths(&state.config.field_limits)?;
+
+        let service = Self::create_service(&state);
+
+        match service.add_tags_to_book(book_id, request).await {
+            Ok(tags) => {
+                info!(count = tags.len(), "Tags added to book successfully");
+                Ok((
+                    StatusCode::OK,
+                    Json(ApiResponse::with_message("Tags added to book", tags)),
+                ))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to add tags to book");
+                Err(e)
+            }
+        }
+    }
+
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        user_role = ?auth_user.role,
+        book_id = %book_id,
+        tag = %tag
+    ))]
+    pub async fn remove_tag_from_book(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path((book_id, tag)): Path<(String, String)>,
+    ) -> Result<StatusCode, AppError> {
+        info!("Attempting to remove tag from book");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.remove_tag_from_book(book_id, tag).await {
+            Ok(_) => {
+                info!("Tag removed from book successfully");
+                Ok(StatusCode::NO_CONTENT)
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to remove tag from book");
+                Err(e)
+            }
+        }
+    }
+}
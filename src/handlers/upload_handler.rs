@@ -1,19 +1,30 @@
 use axum::{
     extract::{Multipart, State},
-    http::StatusCode,
+    http::{header, StatusCode},
+    response::{IntoResponse, Redirect, Response},
     Extension, Json,
 };
 use chrono::Utc;
 use cuid2;
+use std::collections::HashSet;
 
 use crate::{
-    errors::AppError,
+    errors::{map_still_referenced, AppError},
     middleware::auth::AuthUser,
-    models::upload_model::{ContentUpload, ContentUploadResponse, ImageInfoDto, UploadedImage},
-    services::content_extractor::{ContentExtractor, ContentFormat},
+    models::response_model::ApiResponse,
+    models::upload_model::{
+        ContentUpload, ContentUploadResponse, ImageInfoDto, PresignUploadDto,
+        PresignUploadResponse, UploadedImage,
+    },
+    models::user_model::Role,
+    require_role,
+    services::content_extractor::{ContentExtractor, ContentFormat, ExtractedContent},
     AppState,
 };
 
+/// Short enough that a moved CDN is picked up within the hour
+const IMAGE_REDIRECT_CACHE_CONTROL: &str = "public, max-age=3600";
+
 pub struct UploadHandler;
 
 impl UploadHandler {
@@ -21,11 +32,12 @@ impl UploadHandler {
     pub async fn upload_content(
         State(state): State<AppState>,
         multipart: Multipart,
-    ) -> Result<(StatusCode, Json<ContentUploadResponse>), AppError> {
+    ) -> Result<(StatusCode, Json<ApiResponse<ContentUploadResponse>>), AppError> {
         let mut multipart = multipart;
         let mut file_bytes: Option<Vec<u8>> = None;
         let mut original_filename: Option<String> = None;
         let mut book_id: Option<String> = None;
+        let mut skip_boilerplate = false;
 
         // Parse multipart form
         while let Some(field) = multipart
@@ -53,6 +65,20 @@ impl UploadHandler {
                         AppError::BadRequest(format!("Failed to read book_id: {}", e))
                     })?);
                 }
+                "skip_boilerplate" => {
+                    let value = field.text().await.map_err(|e| {
+                        AppError::BadRequest(format!("Failed to read skip_boilerplate: {}", e))
+                    })?;
+                    skip_boilerplate = match value.trim().to_lowercase().as_str() {
+                        "true" | "1" | "yes" => true,
+                        "false" | "0" | "no" | "" => false,
+                        _ => {
+                            return Err(AppError::BadRequest(
+                                "skip_boilerplate must be true or false".to_string(),
+                            ))
+                        }
+                    };
+                }
                 _ => {}
             }
         }
@@ -65,13 +91,14 @@ impl UploadHandler {
         let format = ContentExtractor::detect_format(&bytes);
         if format == ContentFormat::Unknown {
             return Err(AppError::BadRequest(
-                "Unsupported file format. Only EPUB and DOCX are supported.".to_string(),
+                "Unsupported file format. Only EPUB, DOCX and PDF are supported.".to_string(),
             ));
         }
 
         let format_str = match format {
             ContentFormat::Epub => "epub",
             ContentFormat::Docx => "docx",
+            ContentFormat::Pdf => "pdf",
             ContentFormat::Unknown => "unknown",
         };
 
@@ -80,11 +107,63 @@ impl UploadHandler {
         let storage_id = book_id.clone().unwrap_or_else(|| upload_id.clone());
 
         // Extract content
-        let extractor = ContentExtractor::new(state.storage.clone());
-        let extracted = extractor.extract(&bytes, &storage_id).await?;
+        let extractor = ContentExtractor::new(state.storage.clone(), &state.config);
+        let extracted = extractor
+            .extract(&bytes, &storage_id, skip_boilerplate)
+            .await?;
+
+        // Rows and objects succeed or fail together: a failed insert removes the images again
+        let saved = Self::save_upload(
+            &state,
+            &upload_id,
+            book_id.as_deref(),
+            &filename,
+            format_str,
+            &extracted,
+        )
+        .await;
+        let (upload, image_dtos) = match saved {
+            Ok(saved) => saved,
+            Err(e) => {
+                tracing::error!(error = ?e, upload_id = %upload_id, "Failed to save upload");
+                extractor.delete_images(&extracted.images).await;
+                return Err(e);
+            }
+        };
 
-        // Save upload metadata to database
+        tracing::info!(
+            upload_id = %upload_id,
+            format = %format_str,
+            images_count = extracted.images.len(),
+            warnings_count = extracted.warnings.len(),
+            "Content uploaded successfully"
+        );
+
+        Ok((
+            StatusCode::CREATED,
+            Json(ApiResponse::success(ContentUploadResponse {
+                id: upload.id,
+                html_content: upload.html_content,
+                images: image_dtos,
+                format: upload.format,
+                created_at: upload.created_at,
+                warnings: extracted.warnings,
+            })),
+        ))
+    }
+
+    /// Insert the upload and its image rows in one transaction
+    async fn save_upload(
+        state: &AppState,
+        upload_id: &str,
+        book_id: Option<&str>,
+        filename: &str,
+        format: &str,
+        extracted: &ExtractedContent,
+    ) -> Result<(ContentUpload, Vec<ImageInfoDto>), AppError> {
         let now = Utc::now();
+        let mut tx = state.db.pool.begin().await?;
+
         let upload = sqlx::query_as::<_, ContentUpload>(
             r#"
             INSERT INTO "ContentUpload" (
@@ -94,36 +173,38 @@ impl UploadHandler {
             RETURNING id, book_id, original_filename, format, html_content, created_at, updated_at
             "#,
         )
-        .bind(&upload_id)
-        .bind(&book_id)
-        .bind(&filename)
-        .bind(format_str)
+        .bind(upload_id)
+        .bind(book_id)
+        .bind(filename)
+        .bind(format)
         .bind(&extracted.html_content)
         .bind(now)
         .bind(now)
-        .fetch_one(&state.db.pool)
+        .fetch_one(&mut *tx)
         .await?;
 
-        // Save image metadata
         let mut image_dtos: Vec<ImageInfoDto> = Vec::new();
         for img in &extracted.images {
             let image_id = cuid2::create_id();
             sqlx::query(
                 r#"
                 INSERT INTO "UploadedImage" (
-                    id, upload_id, original_path, cdn_url, content_type, size, created_at
+                    id, upload_id, original_path, cdn_url, thumbnail_url, content_type, size,
+                    content_hash, created_at
                 )
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
                 "#,
             )
             .bind(&image_id)
-            .bind(&upload_id)
+            .bind(upload_id)
             .bind(&img.original_path)
             .bind(&img.cdn_url)
+            .bind(&img.thumbnail_url)
             .bind(&img.content_type)
             .bind(img.size as i64)
+            .bind(&img.content_hash)
             .bind(now)
-            .execute(&state.db.pool)
+            .execute(&mut *tx)
             .await?;
 
             image_dtos.push(ImageInfoDto {
@@ -134,28 +215,93 @@ impl UploadHandler {
                     .unwrap_or(&img.original_path)
                     .to_string(),
                 url: img.cdn_url.clone(),
+                thumbnail_url: img.thumbnail_url.clone(),
                 content_type: img.content_type.clone(),
                 size: img.size,
             });
         }
 
-        tracing::info!(
-            upload_id = %upload_id,
-            format = %format_str,
-            images_count = extracted.images.len(),
-            "Content uploaded successfully"
+        tx.commit().await?;
+
+        Ok((upload, image_dtos))
+    }
+
+    /// Presigned URL for uploading a large asset directly to storage
+    /// POST /api/upload/presign
+    pub async fn presign_upload(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<PresignUploadDto>,
+    ) -> Result<Json<ApiResponse<PresignUploadResponse>>, AppError> {
+        require_role!(auth_user, Role::Admin);
+
+        let filename = request.filename.trim();
+        if filename.is_empty() || filename.contains('/') || filename.contains("..") {
+            return Err(AppError::Validation("Invalid filename".to_string()));
+        }
+
+        let content_type = request.content_type.trim();
+        if content_type.is_empty() || !content_type.contains('/') {
+            return Err(AppError::Validation("Invalid content_type".to_string()));
+        }
+
+        let expires_in = state.config.presign_expires_in;
+        if expires_in <= 0 {
+            return Err(AppError::Internal(
+                "PRESIGN_EXPIRES_IN must be positive".to_string(),
+            ));
+        }
+
+        let folder = request.book_id.as_deref().unwrap_or("misc");
+        if folder.is_empty()
+            || !folder
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(AppError::Validation("Invalid book_id".to_string()));
+        }
+        let key = format!(
+            "assets/{}/{}_{}",
+            folder,
+            cuid2::create_id(),
+            filename.replace(' ', "_")
         );
 
+        let upload_url = state
+            .storage
+            .presign_put(
+                &key,
+                content_type,
+                std::time::Duration::from_secs(expires_in as u64),
+            )
+            .await?;
+
+        tracing::info!(user_id = %auth_user.id, key = %key, "Presigned upload URL issued");
+
+        Ok(Json(ApiResponse::success(PresignUploadResponse {
+            upload_url,
+            public_url: state.storage.get_public_url(&key),
+            key,
+            expires_in,
+        })))
+    }
+
+    /// Redirect an extracted content image to its current CDN location, so HTML stored
+    /// with `CONTENT_IMAGE_URLS=relative` survives a change of `cdn_url`
+    /// GET /api/images/{*key}
+    pub async fn redirect_image(
+        State(state): State<AppState>,
+        axum::extract::Path(key): axum::extract::Path<String>,
+    ) -> Result<Response, AppError> {
+        if !key.starts_with("content-images/") || key.split('/').any(|part| part == "..") {
+            return Err(AppError::NotFound("Image not found".to_string()));
+        }
+
         Ok((
-            StatusCode::CREATED,
-            Json(ContentUploadResponse {
-                id: upload.id,
-                html_content: upload.html_content,
-                images: image_dtos,
-                format: upload.format,
-                created_at: upload.created_at,
-            }),
-        ))
+            [(header::CACHE_CONTROL, IMAGE_REDIRECT_CACHE_CONTROL)],
+            Redirect::temporary(&state.storage.get_public_url(&key)),
+        )
+            .into_response())
     }
 
     /// Get upload by ID
@@ -163,7 +309,7 @@ impl UploadHandler {
     pub async fn get_upload(
         State(state): State<AppState>,
         axum::extract::Path(id): axum::extract::Path<String>,
-    ) -> Result<Json<ContentUploadResponse>, AppError> {
+    ) -> Result<Json<ApiResponse<ContentUploadResponse>>, AppError> {
         let upload = sqlx::query_as::<_, ContentUpload>(
             r#"
             SELECT id, book_id, original_filename, format, html_content, created_at, updated_at
@@ -178,7 +324,8 @@ impl UploadHandler {
 
         let images = sqlx::query_as::<_, UploadedImage>(
             r#"
-            SELECT id, upload_id, original_path, cdn_url, content_type, size, created_at
+            SELECT id, upload_id, original_path, cdn_url, thumbnail_url, content_type, size,
+                   content_hash, created_at
             FROM "UploadedImage"
             WHERE upload_id = $1
             "#,
@@ -197,18 +344,20 @@ impl UploadHandler {
                     .unwrap_or(&img.original_path)
                     .to_string(),
                 url: img.cdn_url,
+                thumbnail_url: img.thumbnail_url,
                 content_type: img.content_type,
                 size: img.size as u64,
             })
             .collect();
 
-        Ok(Json(ContentUploadResponse {
+        Ok(Json(ApiResponse::success(ContentUploadResponse {
             id: upload.id,
             html_content: upload.html_content,
             images: image_dtos,
             format: upload.format,
             created_at: upload.created_at,
-        }))
+            warnings: Vec::new(),
+        })))
     }
 
     /// Delete upload and its images
@@ -218,26 +367,62 @@ impl UploadHandler {
         Extension(_user): Extension<AuthUser>,
         axum::extract::Path(id): axum::extract::Path<String>,
     ) -> Result<StatusCode, AppError> {
-        // Get images to delete from R2
-        let images = sqlx::query_as::<_, UploadedImage>(
-            r#"
-            SELECT id, upload_id, original_path, cdn_url, content_type, size, created_at
-            FROM "UploadedImage"
-            WHERE upload_id = $1
-            "#,
-        )
-        .bind(&id)
-        .fetch_all(&state.db.pool)
-        .await?;
+        let upload_book_id: Option<Option<String>> =
+            sqlx::query_scalar(r#"SELECT book_id FROM "ContentUpload" WHERE id = $1"#)
+                .bind(&id)
+                .fetch_optional(&state.db.pool)
+                .await?;
 
-        // Delete images from R2
-        for img in &images {
-            // Extract key from CDN URL
-            if let Some(key) = img.cdn_url.strip_prefix(&state.config.cdn_url) {
-                let key = key.trim_start_matches('/');
-                if let Err(e) = state.storage.delete_file(key).await {
-                    tracing::warn!(error = %e, key = %key, "Failed to delete image from R2");
-                }
+        let Some(upload_book_id) = upload_book_id else {
+            return Err(AppError::NotFound("Upload not found".to_string()));
+        };
+
+        if upload_book_id.is_none() {
+            // Images of uploads without a book live in their own folder
+            match state
+                .storage
+                .delete_prefix(&format!("content-images/{}/", id))
+                .await
+            {
+                Ok(deleted) => tracing::info!(upload_id = %id, deleted, "Deleted upload images"),
+                Err(e) => tracing::warn!(error = %e, "Failed to delete upload images from R2"),
+            }
+        } else {
+            // The book folder is shared with other uploads, so delete only this upload's keys
+            let images = sqlx::query_as::<_, UploadedImage>(
+                r#"
+                SELECT id, upload_id, original_path, cdn_url, thumbnail_url, content_type, size,
+                       content_hash, created_at
+                FROM "UploadedImage"
+                WHERE upload_id = $1
+                "#,
+            )
+            .bind(&id)
+            .fetch_all(&state.db.pool)
+            .await?;
+
+            // Images are stored once per content hash, so keep those another upload still lists
+            let urls: Vec<&str> = images.iter().map(|img| img.cdn_url.as_str()).collect();
+            let shared: HashSet<String> = sqlx::query_scalar(
+                r#"SELECT cdn_url FROM "UploadedImage" WHERE upload_id <> $1 AND cdn_url = ANY($2)"#,
+            )
+            .bind(&id)
+            .bind(&urls)
+            .fetch_all(&state.db.pool)
+            .await?
+            .into_iter()
+            .collect();
+
+            // Extract keys from CDN URLs
+            let keys: Vec<String> = images
+                .into_iter()
+                .filter(|img| !shared.contains(&img.cdn_url))
+                .flat_map(|img| std::iter::once(img.cdn_url).chain(img.thumbnail_url))
+                .filter_map(|url| state.storage.key_from_public_url(&url))
+                .collect();
+
+            if let Err(e) = state.storage.delete_files(&keys).await {
+                tracing::warn!(error = %e, "Failed to delete upload images from R2");
             }
         }
 
@@ -250,7 +435,8 @@ impl UploadHandler {
         sqlx::query(r#"DELETE FROM "ContentUpload" WHERE id = $1"#)
             .bind(&id)
             .execute(&state.db.pool)
-            .await?;
+            .await
+            .map_err(map_still_referenced)?;
 
         Ok(StatusCode::NO_CONTENT)
     }
@@ -0,0 +1,172 @@
+use crate::{
+    errors::AppError,
+    middleware::auth::AuthUser,
+    models::paging_model::{PaginatedApiResponse, PaginationParams},
+    models::response_model::ApiResponse,
+    models::user_model::{DeleteAccountDto, Role, SafeUser, UpdateUserRoleDto, UserSearchQuery},
+    require_role,
+    services::user_service::UserService,
+    AppState,
+};
+use axum::http::StatusCode;
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use tracing::{error, info, instrument};
+
+pub struct UserHandler;
+
+impl UserHandler {
+    fn create_service(state: &AppState) -> UserService {
+        UserService::new(state.db.clone(), state.storage.clone())
+    }
+
+    /// List users for administration
+    /// GET /api/admin/users
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        page = %params.page,
+        page_size = %params.page_size
+    ))]
+    pub async fn list_users(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Query(params): Query<PaginationParams>,
+    ) -> Result<Json<PaginatedApiResponse<SafeUser>>, AppError> {
+        info!("Fetching users");
+        require_role!(auth_user, Role::Admin);
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.list_users(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "Users fetched successfully"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    /// Search users by username or email for administration
+    /// GET /api/admin/users/search?q=
+    #[instrument(skip(state), fields(
+        user_id = %auth_user.id,
+        q = ?query.q,
+        page = %query.page,
+        page_size = %query.page_size
+    ))]
+    pub async fn search_users(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Query(query): Query<UserSearchQuery>,
+    ) -> Result<Json<PaginatedApiResponse<SafeUser>>, AppError> {
+        info!("Searching users");
+        require_role!(auth_user, Role::Admin);
+
+        let params = PaginationParams::from(query);
+        params.validate()?;
+
+        let service = Self::create_service(&state);
+        let paginated = service.list_users(params).await?;
+
+        info!(
+            total_items = paginated.meta.total_items,
+            "User search completed"
+        );
+
+        Ok(Json(paginated.into_api_response()))
+    }
+
+    /// Change another user's role
+    /// PATCH /api/admin/users/{id}/role
+    #[instrument(skip(state, request), fields(
+        user_id = %auth_user.id,
+        target_user_id = %id,
+        role = ?request.role
+    ))]
+    pub async fn update_user_role(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+        Json(request): Json<UpdateUserRoleDto>,
+    ) -> Result<Json<ApiResponse<SafeUser>>, AppError> {
+        info!("Attempting to update user role");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.update_role(&auth_user.id, &id, request.role).await {
+            Ok(user) => {
+                info!("User role updated successfully");
+                Ok(Json(ApiResponse::with_message(
+                    "User role updated successfully",
+                    user,
+                )))
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to update user role");
+                Err(e)
+            }
+        }
+    }
+
+    /// Remove another user's account
+    /// DELETE /api/admin/users/{id}
+    #[instrument(skip(state), fields(user_id = %auth_user.id, target_user_id = %id))]
+    pub async fn delete_user(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Path(id): Path<String>,
+    ) -> Result<StatusCode, AppError> {
+        info!("Attempting to delete user");
+        require_role!(auth_user, Role::Admin);
+
+        let service = Self::create_service(&state);
+
+        match service.delete_user(&auth_user.id, &id).await {
+            Ok(_) => {
+                info!("User deleted successfully");
+                Ok(StatusCode::NO_CONTENT)
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to delete user");
+                Err(e)
+            }
+        }
+    }
+
+    /// Delete the current user's account and personal data
+    /// DELETE /api/users/me
+    #[instrument(skip(state, request), fields(user_id = %auth_user.id))]
+    pub async fn delete_account(
+        State(state): State<AppState>,
+        Extension(auth_user): Extension<AuthUser>,
+        Json(request): Json<DeleteAccountDto>,
+    ) -> Result<StatusCode, AppError> {
+        info!("Attempting to delete own account");
+
+        let service = Self::create_service(&state);
+
+        // Read the device token before the row disappears so its topics can be dropped
+        state
+            .notification
+            .release_user_topics(&auth_user.id, None)
+            .await;
+
+        match service
+            .delete_own_account(&auth_user.id, &request.password)
+            .await
+        {
+            Ok(_) => {
+                info!("Account deleted successfully");
+                Ok(StatusCode::NO_CONTENT)
+            }
+            Err(e) => {
+                error!(error = ?e, "Failed to delete account");
+                Err(e)
+            }
+        }
+    }
+}
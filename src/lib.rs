@@ -9,11 +9,15 @@ pub mod routes;
 pub mod services;
 pub mod utils;
 
+#[cfg(test)]
+mod test_support;
+
 use config::Config;
 use database::Database;
 use services::notification_service::NotificationService;
 use services::storage_service::StorageService;
 use std::sync::Arc;
+use std::time::Instant;
 
 pub type AppState = Arc<AppStateInner>;
 
@@ -21,5 +25,7 @@ pub struct AppStateInner {
     pub db: Database,
     pub config: Config,
     pub storage: StorageService,
-    pub notification: NotificationService,
+    pub notification: Arc<NotificationService>,
+    /// Process start, used for uptime reporting
+    pub started_at: Instant,
 }
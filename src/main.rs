@@ -1,12 +1,13 @@
-use axum::http::{header, HeaderValue, Method};
 use dotenvy::dotenv;
 use novel_api::config::Config;
 use novel_api::database::Database;
+use novel_api::middleware::cors::cors_layer;
 use novel_api::services::notification_service::NotificationService;
 use novel_api::services::storage_service::StorageService;
 use novel_api::{routes, AppStateInner};
+use std::net::SocketAddr;
 use std::sync::Arc;
-use tower_http::cors::CorsLayer;
+use std::time::Instant;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[tokio::main]
@@ -25,11 +26,15 @@ async fn main() {
     tracing::info!("Starting application...");
 
     let config = Config::from_env().expect("Failed to load env");
+    if let Err(e) = config.validate() {
+        tracing::error!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    }
 
     let port = config.port.clone();
 
     tracing::info!("Connecting to database...");
-    let db = Database::new(&config.database_url, &config.redis_url)
+    let db = Database::new(&config)
         .await
         .expect("Failed to connect to database");
 
@@ -44,45 +49,16 @@ async fn main() {
     let storage = StorageService::new(&config);
 
     tracing::info!("Initializing notification service...");
-    let notification = NotificationService::new(db.clone(), &config);
-
-    let allowed_origins = [
-        "http://localhost:5173",
-        "http://localhost:3000",
-        "https://novel.wign.cloud",
-        "https://wign-realm.vercel.app",
-        "https://n-f-theta.vercel.app",
-        "https://n-f-git-main-wigns-projects.vercel.app",
-    ];
+    let notification = Arc::new(NotificationService::new(db.clone(), &config));
 
-    let cors = CorsLayer::new()
-        .allow_origin(
-            allowed_origins
-                .iter()
-                .map(|origin| origin.parse::<HeaderValue>().unwrap())
-                .collect::<Vec<_>>(),
-        )
-        .allow_methods([
-            Method::GET,
-            Method::PATCH,
-            Method::PUT,
-            Method::DELETE,
-            Method::POST,
-            Method::OPTIONS,
-        ])
-        .allow_headers([
-            header::CONTENT_TYPE,
-            header::AUTHORIZATION,
-            header::ACCEPT,
-            header::HeaderName::from_static("x-api-key"),
-        ])
-        .allow_credentials(true);
+    let cors = cors_layer(&config);
 
     let state = Arc::new(AppStateInner {
         db,
         config,
         storage,
         notification,
+        started_at: Instant::now(),
     });
 
     let app = routes::create_routes(state, cors);
@@ -94,7 +70,10 @@ async fn main() {
 
     tracing::info!("Server running on port {}", port);
 
-    axum::serve(listener, app)
-        .await
-        .expect("Failed to start server");
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .await
+    .expect("Failed to start server");
 }
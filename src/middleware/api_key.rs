@@ -1,3 +1,4 @@
+use crate::config::ApiKey;
 use crate::errors::AppError;
 use crate::AppState;
 use axum::{
@@ -7,24 +8,52 @@ use axum::{
     response::Response,
 };
 
-const API_KEY_HEADER: &str = "x-api-key";
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Label of the API key that authenticated the request, for logging and metrics
+#[derive(Clone, Debug)]
+pub struct ApiKeyLabel(pub String);
 
 pub async fn api_key_middleware(
     State(state): State<AppState>,
     headers: HeaderMap,
-    request: Request,
+    mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
     let api_key = extract_api_key_from_header(&headers)?;
-    
-    // Verify API key
-    if api_key != state.config.api_key {
-        return Err(AppError::Unauthorized);
-    }
+
+    let matched = find_api_key(&state.config.api_keys, &api_key).ok_or(AppError::Unauthorized)?;
+
+    tracing::debug!(api_key = %matched.label, "API key accepted");
+    request
+        .extensions_mut()
+        .insert(ApiKeyLabel(matched.label.clone()));
 
     Ok(next.run(request).await)
 }
 
+/// Looks up `candidate` among the configured keys. Every key is compared in
+/// full so the response time doesn't reveal how much of a key matched.
+pub fn find_api_key<'a>(keys: &'a [ApiKey], candidate: &str) -> Option<&'a ApiKey> {
+    keys.iter().fold(None, |found, key| {
+        if constant_time_eq(key.key.as_bytes(), candidate.as_bytes()) {
+            Some(key)
+        } else {
+            found
+        }
+    })
+}
+
+/// Byte comparison whose running time depends only on the lengths, not on where
+/// the inputs first differ
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 fn extract_api_key_from_header(headers: &HeaderMap) -> Result<String, AppError> {
     let api_key = headers
         .get(API_KEY_HEADER)
@@ -34,3 +63,67 @@ fn extract_api_key_from_header(headers: &HeaderMap) -> Result<String, AppError>
 
     Ok(api_key.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys() -> Vec<ApiKey> {
+        vec![
+            ApiKey {
+                label: "web".to_string(),
+                key: "web-secret-key".to_string(),
+            },
+            ApiKey {
+                label: "mobile".to_string(),
+                key: "mobile-secret-key".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn accepts_each_configured_key_with_its_label() {
+        let keys = keys();
+        assert_eq!(
+            find_api_key(&keys, "web-secret-key").map(|k| k.label.as_str()),
+            Some("web")
+        );
+        assert_eq!(
+            find_api_key(&keys, "mobile-secret-key").map(|k| k.label.as_str()),
+            Some("mobile")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_truncated_and_empty_keys() {
+        let keys = keys();
+        assert!(find_api_key(&keys, "web-secret-kex").is_none());
+        assert!(find_api_key(&keys, "web-secret").is_none());
+        assert!(find_api_key(&keys, "web-secret-key ").is_none());
+        assert!(find_api_key(&keys, "").is_none());
+        assert!(find_api_key(&[], "web-secret-key").is_none());
+    }
+
+    #[test]
+    fn constant_time_eq_compares_whole_inputs() {
+        assert!(constant_time_eq(b"abc", b"abc"));
+        assert!(!constant_time_eq(b"abc", b"abd"));
+        assert!(!constant_time_eq(b"abc", b"ab"));
+        assert!(constant_time_eq(b"", b""));
+    }
+
+    #[test]
+    fn missing_header_is_unauthorized() {
+        let mut headers = HeaderMap::new();
+        assert!(matches!(
+            extract_api_key_from_header(&headers),
+            Err(AppError::Unauthorized)
+        ));
+
+        headers.insert(API_KEY_HEADER, "web-secret-key".parse().unwrap());
+        assert_eq!(
+            extract_api_key_from_header(&headers).unwrap(),
+            "web-secret-key"
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::errors::AppError;
+use crate::middleware::api_key::constant_time_eq;
 use crate::models::user_model::Role;
 use crate::utils::jwt::{Claims, JwtService};
 use crate::AppState;
@@ -10,23 +11,36 @@ use axum::{
 };
 use tower_cookies::Cookies;
 
+const INTERNAL_TOKEN_HEADER: &str = "x-internal-token";
+const INTERNAL_SERVICE_ID: &str = "internal-service";
 
+/// How long a looked-up role may be reused before the database is asked again
+const ROLE_CACHE_TTL_SECS: i64 = 30;
 
 #[derive(Debug, Clone)]
 pub struct AuthUser {
+    /// User id (a cuid, not a UUID) taken verbatim from the JWT subject
     pub id: String,
     pub email: String,
     pub role: Role,
 }
 
 impl AuthUser {
-    pub fn from_claims(claims: Claims) -> Result<Self, AppError> {
-        let id = claims.sub;
-        Ok(Self {
-            id,
+    pub fn from_claims(claims: Claims) -> Self {
+        Self {
+            id: claims.sub,
             email: claims.email,
             role: claims.role,
-        })
+        }
+    }
+
+    /// Synthetic admin identity used for trusted service-to-service calls
+    pub fn internal_service() -> Self {
+        Self {
+            id: INTERNAL_SERVICE_ID.to_string(),
+            email: String::new(),
+            role: Role::Admin,
+        }
     }
 }
 
@@ -37,8 +51,22 @@ pub async fn auth_middleware(
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
+    // Internal services authenticate with a shared token instead of a user JWT
+    if let Some(token) = extract_internal_token(&headers) {
+        if !is_valid_internal_token(state.config.internal_service_token.as_deref(), &token) {
+            tracing::warn!("Rejected request with invalid internal service token");
+            return Err(AppError::Unauthorized);
+        }
+
+        tracing::info!(
+            method = %request.method(),
+            path = %request.uri().path(),
+            "Authorized internal service call"
+        );
+        request.extensions_mut().insert(AuthUser::internal_service());
+        return Ok(next.run(request).await);
+    }
 
-    
     let jwt_service = JwtService::new(
         &state.config.jwt_secret_key,
         state.config.jwt_expire_in,
@@ -50,7 +78,11 @@ pub async fn auth_middleware(
         .or_else(|_| extract_token_from_header(&headers))?;
     
     let claims = jwt_service.verify_access_token(&token)?;
-    let auth_user = AuthUser::from_claims(claims)?;
+    let mut auth_user = AuthUser::from_claims(claims);
+
+    if state.config.auth_role_from_db && auth_user.role != Role::User {
+        auth_user.role = current_role(&state, &auth_user.id).await?;
+    }
 
     // Insert auth user into request extensions
     request.extensions_mut().insert(auth_user);
@@ -58,6 +90,86 @@ pub async fn auth_middleware(
     Ok(next.run(request).await)
 }
 
+/// Like `auth_middleware`, but anonymous requests pass through. A valid user
+/// token still attaches `AuthUser` so public routes can personalise responses.
+pub async fn optional_auth_middleware(
+    State(state): State<AppState>,
+    cookies: Cookies,
+    headers: HeaderMap,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let jwt_service = JwtService::new(
+        &state.config.jwt_secret_key,
+        state.config.jwt_expire_in,
+        state.config.jwt_refresh_expire_in,
+    );
+
+    let auth_user = extract_token_from_cookie(&cookies)
+        .or_else(|_| extract_token_from_header(&headers))
+        .and_then(|token| jwt_service.verify_access_token(&token))
+        .map(AuthUser::from_claims);
+
+    if let Ok(mut auth_user) = auth_user {
+        if state.config.auth_role_from_db && auth_user.role != Role::User {
+            match current_role(&state, &auth_user.id).await {
+                Ok(role) => auth_user.role = role,
+                // Treat a vanished user as anonymous rather than failing a public route
+                Err(_) => return next.run(request).await,
+            }
+        }
+        request.extensions_mut().insert(auth_user);
+    }
+
+    next.run(request).await
+}
+
+/// Look up the user's role as currently stored, for tokens that claim more than `User`.
+///
+/// The role claim is fixed when the access token is issued, so trusting it alone means a
+/// demoted admin keeps admin rights until the token expires. Re-checking costs one cached
+/// lookup per elevated request: the cache lives for `ROLE_CACHE_TTL_SECS` and is dropped
+/// whenever a role changes, so demotions apply on the next request. Plain `User` tokens skip
+/// the check since a demotion cannot take anything away from them; a promotion is only
+/// picked up once the client refreshes its token. Deleted users are rejected outright.
+async fn current_role(state: &AppState, user_id: &str) -> Result<Role, AppError> {
+    let cache_key = format!("user:role:{user_id}");
+
+    if let Ok(Some(role)) = state.db.redis.get_json::<Role>(&cache_key).await {
+        return Ok(role);
+    }
+
+    let role = sqlx::query_scalar::<_, Role>(r#"SELECT role FROM "User" WHERE id = $1"#)
+        .bind(user_id)
+        .fetch_optional(&state.db.pool)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    let _ = state
+        .db
+        .redis
+        .set_json(&cache_key, &role, ROLE_CACHE_TTL_SECS)
+        .await;
+
+    Ok(role)
+}
+
+fn extract_internal_token(headers: &HeaderMap) -> Option<String> {
+    headers
+        .get(INTERNAL_TOKEN_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+fn is_valid_internal_token(expected: Option<&str>, token: &str) -> bool {
+    match expected {
+        Some(expected) => {
+            !token.is_empty() && constant_time_eq(token.as_bytes(), expected.as_bytes())
+        }
+        None => false,
+    }
+}
+
 fn extract_token_from_cookie(cookies: &Cookies) -> Result<String, AppError> {
     let token = cookies
         .get("access_token")
@@ -82,11 +194,206 @@ fn extract_token_from_header(headers: &HeaderMap) -> Result<String, AppError> {
     Ok(auth_header[7..].to_string())
 }
 
+/// Returns early with `AppError::Forbidden` unless the authenticated user
+/// holds the given `Role` or one above it (Admin ⊇ Moderator ⊇ User).
 #[macro_export]
 macro_rules! require_role {
-    ($auth_user:expr, $required_role:expr) => {
-        if $auth_user.role != $required_role {
-            return Err(crate::errors::AppError::Forbidden);
+    ($auth_user:expr, $required_role:expr) => {{
+        let required: $crate::models::user_model::Role = $required_role;
+        if !$auth_user.role.satisfies(&required) {
+            return Err($crate::errors::AppError::Forbidden);
         }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use axum::{
+        body::{to_bytes, Body},
+        http::StatusCode,
+        middleware::from_fn_with_state,
+        routing::get,
+        Extension, Router,
     };
+    use tower::ServiceExt;
+    use tower_cookies::CookieManagerLayer;
+
+    const TOKEN: &str = "internal-secret-token";
+
+    fn admin_only(auth_user: &AuthUser) -> Result<(), AppError> {
+        require_role!(auth_user, Role::Admin);
+        Ok(())
+    }
+
+    fn moderator_only(auth_user: &AuthUser) -> Result<(), AppError> {
+        require_role!(auth_user, Role::Moderator);
+        Ok(())
+    }
+
+    fn user_with(role: Role) -> AuthUser {
+        AuthUser {
+            id: "user".to_string(),
+            email: "user@example.com".to_string(),
+            role,
+        }
+    }
+
+    #[test]
+    fn accepts_configured_internal_token() {
+        assert!(is_valid_internal_token(Some(TOKEN), TOKEN));
+    }
+
+    #[test]
+    fn rejects_wrong_or_missing_internal_token() {
+        assert!(!is_valid_internal_token(Some(TOKEN), "internal-secret-tokex"));
+        assert!(!is_valid_internal_token(Some(TOKEN), "internal-secret"));
+        assert!(!is_valid_internal_token(Some(TOKEN), ""));
+        assert!(!is_valid_internal_token(Some(""), ""));
+        assert!(!is_valid_internal_token(None, TOKEN));
+    }
+
+    #[test]
+    fn internal_service_passes_admin_only_check() {
+        assert!(admin_only(&AuthUser::internal_service()).is_ok());
+    }
+
+    #[test]
+    fn admin_passes_admin_only_check() {
+        assert!(admin_only(&user_with(Role::Admin)).is_ok());
+    }
+
+    #[test]
+    fn regular_user_fails_admin_only_check() {
+        assert!(matches!(
+            admin_only(&user_with(Role::User)),
+            Err(AppError::Forbidden)
+        ));
+    }
+
+    #[test]
+    fn moderator_passes_moderator_check_but_not_admin_check() {
+        let moderator = user_with(Role::Moderator);
+        assert!(moderator_only(&moderator).is_ok());
+        assert!(matches!(admin_only(&moderator), Err(AppError::Forbidden)));
+    }
+
+    #[test]
+    fn moderator_check_admits_admins_and_refuses_users() {
+        assert!(moderator_only(&user_with(Role::Admin)).is_ok());
+        assert!(matches!(
+            moderator_only(&user_with(Role::User)),
+            Err(AppError::Forbidden)
+        ));
+    }
+
+    async fn whoami(Extension(auth_user): Extension<AuthUser>) -> String {
+        auth_user.id
+    }
+
+    async fn admin_area(Extension(auth_user): Extension<AuthUser>) -> Result<(), AppError> {
+        require_role!(auth_user, Role::Admin);
+        Ok(())
+    }
+
+    fn app(state: AppState) -> Router {
+        Router::new()
+            .route("/whoami", get(whoami))
+            .route("/admin", get(admin_area))
+            .layer(from_fn_with_state(state.clone(), auth_middleware))
+            .layer(CookieManagerLayer::new())
+            .with_state(state)
+    }
+
+    fn bearer(state: &AppState, user_id: &str, role: Role) -> String {
+        let token = JwtService::new(
+            &state.config.jwt_secret_key,
+            state.config.jwt_expire_in,
+            state.config.jwt_refresh_expire_in,
+        )
+        .generate_access_token(user_id, "reader@example.com", role)
+        .unwrap();
+        format!("Bearer {}", token)
+    }
+
+    #[tokio::test]
+    async fn cuid_subject_round_trips_through_the_middleware() {
+        let state = test_support::app_state(test_support::config());
+        let user_id = cuid2::create_id();
+        let request = Request::get("/whoami")
+            .header(AUTHORIZATION, bearer(&state, &user_id, Role::User))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app(state).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(std::str::from_utf8(&body).unwrap(), user_id);
+    }
+
+    #[tokio::test]
+    async fn missing_token_is_unauthorized() {
+        let state = test_support::app_state(test_support::config());
+        let request = Request::get("/whoami").body(Body::empty()).unwrap();
+
+        let response = app(state).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn internal_token_header_opens_admin_routes_only_when_it_matches() {
+        let mut config = test_support::config();
+        config.internal_service_token = Some(TOKEN.to_string());
+        let state = test_support::app_state(config);
+        let admin_request = |token: &str| {
+            Request::get("/admin")
+                .header(INTERNAL_TOKEN_HEADER, token)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app(state.clone()).oneshot(admin_request(TOKEN)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app(state)
+            .oneshot(admin_request("internal-secret-tokex"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn demoted_admin_loses_access_with_the_old_token(pool: sqlx::PgPool) {
+        let user_id = test_support::seed_user(&pool, "former_admin").await;
+        let set_role = |role: Role| {
+            sqlx::query(r#"UPDATE "User" SET role = $1 WHERE id = $2"#)
+                .bind(role)
+                .bind(user_id.clone())
+                .execute(&pool)
+        };
+        set_role(Role::Admin).await.unwrap();
+
+        let mut config = test_support::config();
+        config.auth_role_from_db = true;
+        let state = test_support::app_state_with(config, test_support::database(pool.clone()));
+        let token = bearer(&state, &user_id, Role::Admin);
+        let admin_request = || {
+            Request::get("/admin")
+                .header(AUTHORIZATION, token.clone())
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = app(state.clone()).oneshot(admin_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // The token still claims Admin, but the stored role now wins
+        set_role(Role::User).await.unwrap();
+        let response = app(state).oneshot(admin_request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,97 @@
+use crate::errors::AppError;
+use crate::AppState;
+use axum::{
+    extract::{Request, State},
+    http::{header::USER_AGENT, HeaderMap},
+    middleware::Next,
+    response::Response,
+};
+use chrono::Utc;
+
+const RATE_LIMIT_WINDOW_SECS: i64 = 60;
+
+/// Applies a stricter per-minute limit to requests from known crawlers
+pub async fn bot_control_middleware(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    // Crawlers must always be able to read the policy itself
+    if request.uri().path() == "/robots.txt" {
+        return Ok(next.run(request).await);
+    }
+
+    // Counters live in Redis; without it crawlers are only governed by robots.txt
+    if !state.db.redis.is_enabled() {
+        return Ok(next.run(request).await);
+    }
+
+    let user_agent = headers
+        .get(USER_AGENT)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or("");
+
+    if let Some(crawler) = detect_crawler(user_agent, &state.config.crawler_user_agents) {
+        let window = Utc::now().timestamp() / RATE_LIMIT_WINDOW_SECS;
+        let key = format!("ratelimit:crawler:{}:{}", crawler, window);
+        let redis = &state.db.redis;
+
+        match redis.incr(&key).await {
+            Ok(count) => {
+                if count == 1 {
+                    let _ = redis.expire(&key, RATE_LIMIT_WINDOW_SECS).await;
+                }
+                if count > state.config.crawler_rate_limit_per_minute {
+                    tracing::warn!(crawler = %crawler, user_agent = %user_agent, "Crawler rate limit exceeded");
+                    return Err(AppError::TooManyRequests);
+                }
+            }
+            // Fail open: a Redis outage shouldn't take the API down for crawlers
+            Err(e) => tracing::warn!(error = %e, "Failed to check crawler rate limit"),
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Returns the matched crawler fragment if the user agent looks like a bot
+pub fn detect_crawler<'a>(user_agent: &str, crawler_agents: &'a [String]) -> Option<&'a str> {
+    let user_agent = user_agent.to_lowercase();
+    crawler_agents
+        .iter()
+        .find(|fragment| user_agent.contains(fragment.as_str()))
+        .map(|fragment| fragment.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn agents(fragments: &[&str]) -> Vec<String> {
+        fragments.iter().map(|f| f.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_crawlers_case_insensitively() {
+        let crawlers = agents(&["bot", "spider"]);
+        let googlebot = "Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)";
+        assert_eq!(detect_crawler(googlebot, &crawlers), Some("bot"));
+        assert_eq!(detect_crawler("Baiduspider/2.0", &crawlers), Some("spider"));
+    }
+
+    #[test]
+    fn browsers_are_not_crawlers() {
+        let crawlers = agents(&["bot", "spider"]);
+        let firefox = "Mozilla/5.0 (X11; Linux x86_64; rv:128.0) Gecko/20100101 Firefox/128.0";
+        assert_eq!(detect_crawler(firefox, &crawlers), None);
+        assert_eq!(detect_crawler("", &crawlers), None);
+    }
+
+    #[test]
+    fn crawler_limit_is_stricter_than_the_global_one() {
+        let config = test_support::config();
+        assert!(config.crawler_rate_limit_per_minute < config.rate_limit_per_minute);
+    }
+}
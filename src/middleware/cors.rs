@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::middleware::idempotency::IDEMPOTENCY_KEY_HEADER;
+use axum::http::{header, HeaderValue, Method};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// CORS policy from config. Origins and methods come from the allowlists; the
+/// dev-mode escape hatch reflects any origin so cookie-based auth keeps working.
+pub fn cors_layer(config: &Config) -> CorsLayer {
+    let allow_origin = if config.cors_allow_any_origin {
+        tracing::warn!("CORS_ALLOW_ANY_ORIGIN is set; every origin is allowed");
+        AllowOrigin::mirror_request()
+    } else {
+        // Entries were checked by Config::validate
+        AllowOrigin::list(
+            config
+                .cors_allowed_origins
+                .iter()
+                .filter_map(|origin| origin.parse::<HeaderValue>().ok()),
+        )
+    };
+
+    let methods: Vec<Method> = config
+        .cors_allowed_methods
+        .iter()
+        .filter_map(|method| Method::from_bytes(method.as_bytes()).ok())
+        .collect();
+
+    CorsLayer::new()
+        .allow_origin(allow_origin)
+        .allow_methods(methods)
+        .allow_headers([
+            header::CONTENT_TYPE,
+            header::AUTHORIZATION,
+            header::ACCEPT,
+            header::IF_NONE_MATCH,
+            header::HeaderName::from_static("x-api-key"),
+            header::HeaderName::from_static(IDEMPOTENCY_KEY_HEADER),
+        ])
+        .expose_headers([header::ETAG, header::RETRY_AFTER])
+        .allow_credentials(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(allowed: &[&str]) -> Router {
+        let mut config = test_support::config();
+        config.cors_allowed_origins = allowed.iter().map(|o| o.to_string()).collect();
+        config.cors_allow_any_origin = false;
+        Router::new()
+            .route("/books", get(|| async { "ok" }))
+            .layer(cors_layer(&config))
+    }
+
+    async fn allowed_origin(app: Router, request: Request<Body>) -> Option<HeaderValue> {
+        let response = app.oneshot(request).await.unwrap();
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .cloned()
+    }
+
+    fn get_from(origin: &str) -> Request<Body> {
+        Request::get("/books")
+            .header(header::ORIGIN, origin)
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn allowed_origin_is_reflected() {
+        let app = app(&["https://app.example.com"]);
+        assert_eq!(
+            allowed_origin(app, get_from("https://app.example.com")).await,
+            Some(HeaderValue::from_static("https://app.example.com"))
+        );
+    }
+
+    #[tokio::test]
+    async fn disallowed_origin_is_not_reflected() {
+        let app = app(&["https://app.example.com"]);
+        assert_eq!(
+            allowed_origin(app.clone(), get_from("https://evil.example.com")).await,
+            None
+        );
+        // Near misses on scheme or subdomain don't count either
+        assert_eq!(
+            allowed_origin(app.clone(), get_from("http://app.example.com")).await,
+            None
+        );
+        assert_eq!(
+            allowed_origin(app, get_from("https://app.example.com.evil.io")).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn disallowed_preflight_gets_no_allow_origin() {
+        let request = Request::options("/books")
+            .header(header::ORIGIN, "https://evil.example.com")
+            .header(header::ACCESS_CONTROL_REQUEST_METHOD, "DELETE")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(
+            allowed_origin(app(&["https://app.example.com"]), request).await,
+            None
+        );
+    }
+}
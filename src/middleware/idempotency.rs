@@ -0,0 +1,170 @@
+use crate::errors::AppError;
+use crate::middleware::auth::AuthUser;
+use crate::AppState;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use chrono::{Duration, Utc};
+
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+/// Set on responses served from a stored result rather than by running the handler
+const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+const IDEMPOTENCY_TTL_HOURS: i64 = 24;
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Replays the stored response when a signed-in caller retries a POST with the
+/// same `Idempotency-Key`. Must run inside `auth_middleware`. Only successful
+/// responses are kept, so a failed attempt can be retried with the same key.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+
+    let key = key
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LENGTH)
+        .ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Idempotency-Key must be 1 to {} visible ASCII characters",
+                MAX_KEY_LENGTH
+            ))
+        })?
+        .to_string();
+
+    let user_id = request
+        .extensions()
+        .get::<AuthUser>()
+        .map(|user| user.id.clone())
+        .ok_or(AppError::Unauthorized)?;
+    let path = request.uri().path().to_string();
+    let pool = &state.db.pool;
+    let now = Utc::now();
+
+    sqlx::query(r#"DELETE FROM "IdempotencyKey" WHERE expires_at < $1"#)
+        .bind(now)
+        .execute(pool)
+        .await?;
+
+    // Claiming the key first means concurrent retries can't both run the handler
+    let claimed = sqlx::query_scalar::<_, String>(
+        r#"
+        INSERT INTO "IdempotencyKey" (user_id, key, request_path, created_at, expires_at)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (user_id, key) DO NOTHING
+        RETURNING key
+        "#,
+    )
+    .bind(&user_id)
+    .bind(&key)
+    .bind(&path)
+    .bind(now)
+    .bind(now + Duration::hours(IDEMPOTENCY_TTL_HOURS))
+    .fetch_optional(pool)
+    .await?;
+
+    if claimed.is_none() {
+        return replay(&state, &user_id, &key, &path).await;
+    }
+
+    let response = next.run(request).await;
+
+    if !response.status().is_success() {
+        sqlx::query(r#"DELETE FROM "IdempotencyKey" WHERE user_id = $1 AND key = $2"#)
+            .bind(&user_id)
+            .bind(&key)
+            .execute(pool)
+            .await?;
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| AppError::Internal(format!("Failed to buffer response: {}", e)))?;
+
+    sqlx::query(
+        r#"
+        UPDATE "IdempotencyKey"
+        SET status_code = $3, response_body = $4
+        WHERE user_id = $1 AND key = $2
+        "#,
+    )
+    .bind(&user_id)
+    .bind(&key)
+    .bind(i32::from(parts.status.as_u16()))
+    .bind(bytes.as_ref())
+    .execute(pool)
+    .await?;
+
+    Ok(Response::from_parts(parts, Body::from(bytes)))
+}
+
+/// Answer a repeated key with the original response, or explain why we can't yet
+async fn replay(
+    state: &AppState,
+    user_id: &str,
+    key: &str,
+    path: &str,
+) -> Result<Response, AppError> {
+    let stored = sqlx::query_as::<_, (String, Option<i32>, Option<Vec<u8>>)>(
+        r#"
+        SELECT request_path, status_code, response_body
+        FROM "IdempotencyKey"
+        WHERE user_id = $1 AND key = $2
+        "#,
+    )
+    .bind(user_id)
+    .bind(key)
+    .fetch_optional(&state.db.pool)
+    .await?;
+
+    // The first attempt failed and released the key between our insert and this read
+    let Some((stored_path, status_code, body)) = stored else {
+        return Err(AppError::Conflict(
+            "A request with this Idempotency-Key just failed, please retry".to_string(),
+        ));
+    };
+
+    if stored_path != path {
+        return Err(AppError::Unprocessable(
+            "Idempotency-Key was already used for a different request".to_string(),
+        ));
+    }
+
+    let Some(status_code) = status_code else {
+        return Err(AppError::Conflict(
+            "A request with this Idempotency-Key is still in progress".to_string(),
+        ));
+    };
+
+    let status = u16::try_from(status_code)
+        .ok()
+        .and_then(|code| StatusCode::from_u16(code).ok())
+        .unwrap_or(StatusCode::OK);
+
+    Ok((
+        status,
+        [
+            (
+                header::CONTENT_TYPE,
+                HeaderValue::from_static("application/json"),
+            ),
+            (
+                HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+                HeaderValue::from_static("true"),
+            ),
+        ],
+        body.unwrap_or_default(),
+    )
+        .into_response())
+}
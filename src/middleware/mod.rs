@@ -1,2 +1,6 @@
 pub mod auth;
-pub mod api_key;
\ No newline at end of file
+pub mod api_key;
+pub mod bot_control;
+pub mod rate_limit;
+pub mod cors;
+pub mod idempotency;
@@ -0,0 +1,159 @@
+use crate::errors::AppError;
+use crate::middleware::api_key::{find_api_key, API_KEY_HEADER};
+use crate::AppState;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::HeaderMap,
+    middleware::Next,
+    response::Response,
+};
+use std::net::{IpAddr, SocketAddr};
+
+/// Probes must keep answering even when a client is being throttled
+const EXEMPT_PATHS: &[&str] = &["/healthy", "/db-health", "/livez", "/readyz"];
+
+/// Token-bucket limit per API key, falling back to the client IP
+pub async fn rate_limit_middleware(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    // Without Redis there is nowhere to keep buckets, so limiting is off
+    if EXEMPT_PATHS.contains(&request.uri().path()) || !state.db.redis.is_enabled() {
+        return Ok(next.run(request).await);
+    }
+
+    let client = client_key(&state, &headers, &request);
+    let key = format!("ratelimit:client:{}", client);
+    let refill_per_sec = state.config.rate_limit_per_minute as f64 / 60.0;
+
+    match state
+        .db
+        .redis
+        .take_token(&key, state.config.rate_limit_burst, refill_per_sec)
+        .await
+    {
+        Ok((true, _)) => {}
+        Ok((false, retry_after_ms)) => {
+            tracing::warn!(client = %client, "Rate limit exceeded");
+            return Err(AppError::RateLimited {
+                retry_after_secs: retry_after_ms.div_ceil(1000).max(1),
+            });
+        }
+        // Fail open: a Redis outage shouldn't take the API down
+        Err(e) => tracing::warn!(error = %e, "Failed to check rate limit"),
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// Bucket identity for the caller. Only configured keys get their own bucket,
+/// keyed by label, so unknown keys can't mint fresh buckets and secrets never
+/// land in Redis.
+fn client_key(state: &AppState, headers: &HeaderMap, request: &Request) -> String {
+    let api_key = headers
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|key| find_api_key(&state.config.api_keys, key));
+
+    if let Some(api_key) = api_key {
+        return format!("key:{}", api_key.label);
+    }
+
+    let ip = client_ip(
+        headers,
+        request.extensions().get::<ConnectInfo<SocketAddr>>(),
+        &state.config.trusted_proxies,
+    );
+
+    format!("ip:{}", ip)
+}
+
+/// The caller's address. `x-forwarded-for` is only believed when the peer is a
+/// trusted proxy, and then only the hops appended by trusted proxies: the client
+/// is the rightmost hop that isn't one of them, since anything left of it is
+/// whatever the client chose to send.
+pub fn client_ip(
+    headers: &HeaderMap,
+    connect_info: Option<&ConnectInfo<SocketAddr>>,
+    trusted_proxies: &[IpAddr],
+) -> String {
+    let Some(peer) = connect_info.map(|info| info.0.ip()) else {
+        return "unknown".to_string();
+    };
+
+    if !trusted_proxies.contains(&peer) {
+        return peer.to_string();
+    }
+
+    let hops: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    for hop in hops.into_iter().rev() {
+        match hop.parse::<IpAddr>() {
+            Ok(ip) if trusted_proxies.contains(&ip) => continue,
+            Ok(ip) => return ip.to_string(),
+            Err(_) => break,
+        }
+    }
+
+    peer.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peer(ip: &str) -> ConnectInfo<SocketAddr> {
+        ConnectInfo(SocketAddr::new(ip.parse().unwrap(), 443))
+    }
+
+    fn forwarded_for(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-forwarded-for", value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn ignores_forwarded_for_without_trusted_proxies() {
+        let headers = forwarded_for("1.2.3.4");
+        let ip = client_ip(&headers, Some(&peer("203.0.113.9")), &[]);
+        assert_eq!(ip, "203.0.113.9");
+    }
+
+    #[test]
+    fn ignores_forwarded_for_from_untrusted_peer() {
+        let proxies = ["10.0.0.1".parse().unwrap()];
+        let headers = forwarded_for("1.2.3.4");
+        let ip = client_ip(&headers, Some(&peer("203.0.113.9")), &proxies);
+        assert_eq!(ip, "203.0.113.9");
+    }
+
+    #[test]
+    fn takes_rightmost_untrusted_hop_behind_trusted_proxy() {
+        let proxies = ["10.0.0.1".parse().unwrap(), "10.0.0.2".parse().unwrap()];
+        // The client spoofed the first hop; 198.51.100.7 is what the edge proxy saw
+        let headers = forwarded_for("1.2.3.4, 198.51.100.7, 10.0.0.2");
+        let ip = client_ip(&headers, Some(&peer("10.0.0.1")), &proxies);
+        assert_eq!(ip, "198.51.100.7");
+    }
+
+    #[test]
+    fn falls_back_to_peer_on_garbage_hop() {
+        let proxies = ["10.0.0.1".parse().unwrap()];
+        let headers = forwarded_for("1.2.3.4, not-an-ip");
+        let ip = client_ip(&headers, Some(&peer("10.0.0.1")), &proxies);
+        assert_eq!(ip, "10.0.0.1");
+    }
+
+    #[test]
+    fn unknown_without_connect_info() {
+        assert_eq!(client_ip(&HeaderMap::new(), None, &[]), "unknown");
+    }
+}
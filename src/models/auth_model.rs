@@ -1,5 +1,6 @@
 use crate::models::user_model::SafeUser;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct LoginDto {
@@ -7,10 +8,13 @@ pub struct LoginDto {
     pub password: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Validate)]
 pub struct RegisterDto {
+    #[validate(length(min = 3, message = "must be at least 3 characters"))]
     pub username: String,
+    #[validate(email(message = "must be a valid email address"))]
     pub email: String,
+    #[validate(length(min = 8, message = "must be at least 8 characters"))]
     pub password: String,
 }
 
@@ -34,23 +38,6 @@ pub struct Auth {
     pub refresh_token: String,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct AuthData {
-    pub user: SafeUser,
-}
-
-#[derive(Debug, Serialize)]
-pub struct AuthResponse {
-    pub status: String,
-    pub data: Auth,
-}
-
-#[derive(Debug, Serialize)]
-pub struct AuthResponseWithoutTokens {
-    pub status: String,
-    pub data: AuthData,
-}
-
 impl Auth {
     pub fn new(user: SafeUser, access_token: String, refresh_token: String) -> Self {
         Self {
@@ -60,21 +47,3 @@ impl Auth {
         }
     }
 }
-
-impl AuthResponse {
-    pub fn success(auth: Auth) -> Self {
-        Self {
-            status: "success".to_string(),
-            data: auth,
-        }
-    }
-}
-
-impl AuthResponseWithoutTokens {
-    pub fn success(user: SafeUser) -> Self {
-        Self {
-            status: "success".to_string(),
-            data: AuthData { user },
-        }
-    }
-}
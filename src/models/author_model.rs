@@ -0,0 +1,80 @@
+use crate::errors::AppResult;
+use crate::models::paging_model::{
+    default_page, default_page_size, deserialize_page, deserialize_page_size, validate_pagination,
+};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Author {
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthorDto {
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    /// Only populated in the author listing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book_count: Option<i64>,
+}
+
+/// Author row joined with how many books they wrote
+#[derive(Debug, Clone, FromRow)]
+pub struct AuthorWithBookCount {
+    #[sqlx(flatten)]
+    pub author: Author,
+    pub book_count: i64,
+}
+
+impl From<Author> for AuthorDto {
+    fn from(author: Author) -> Self {
+        Self {
+            id: author.id,
+            name: author.name,
+            created_at: author.created_at,
+            updated_at: author.updated_at,
+            book_count: None,
+        }
+    }
+}
+
+impl From<AuthorWithBookCount> for AuthorDto {
+    fn from(row: AuthorWithBookCount) -> Self {
+        Self {
+            book_count: Some(row.book_count),
+            ..row.author.into()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthorQuery {
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(
+        default = "default_page_size",
+        deserialize_with = "deserialize_page_size"
+    )]
+    pub page_size: i64,
+    pub search: Option<String>,
+}
+
+impl AuthorQuery {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_pagination(self.page, self.page_size)
+    }
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateAuthorDto {
+    #[validate(length(min = 1, message = "must not be empty"))]
+    pub name: String,
+}
@@ -1,32 +1,134 @@
-use chrono::NaiveDateTime;
-use serde::{Deserialize, Serialize};
+use crate::errors::{AppError, AppResult};
+use crate::models::genre_model::GenreDto;
+use crate::models::paging_model::{
+    default_page, default_page_size, deserialize_limit, deserialize_page, deserialize_page_size,
+    validate_pagination, MAX_PAGE_SIZE,
+};
+use crate::models::review_model::RatingSummary;
+use crate::models::series_model::SeriesInfo;
+use crate::models::tag_model::TagDto;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
+use std::str::FromStr;
+use validator::{Validate, ValidationError};
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+/// Serialized as a lowercase name; parsed case-insensitively from the name or its ISO 639-1 code
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Default)]
 #[sqlx(type_name = "Language", rename_all = "PascalCase")]
+#[serde(rename_all = "lowercase", try_from = "String")]
 pub enum Language {
     English,
     Japanese,
+    #[default]
     Korean,
+    Chinese,
+    Indonesian,
 }
 
-impl Default for Language {
-    fn default() -> Self {
-        Language::Korean
+impl FromStr for Language {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "english" | "en" => Ok(Language::English),
+            "japanese" | "ja" => Ok(Language::Japanese),
+            "korean" | "ko" => Ok(Language::Korean),
+            "chinese" | "zh" => Ok(Language::Chinese),
+            "indonesian" | "id" => Ok(Language::Indonesian),
+            _ => Err(format!(
+                "Unknown language '{}'. Expected one of: english, japanese, korean, chinese, indonesian",
+                s
+            )),
+        }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq)]
+impl TryFrom<String> for Language {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// Serialized as a lowercase name; parsed case-insensitively
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, PartialEq, Default)]
 #[sqlx(type_name = "Status", rename_all = "PascalCase")]
+#[serde(rename_all = "lowercase", try_from = "String")]
 pub enum Status {
+    #[default]
     Ongoing,
     Completed,
-    Drop,
+    Hiatus,
+    Dropped,
+}
+
+impl FromStr for Status {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "ongoing" => Ok(Status::Ongoing),
+            "completed" => Ok(Status::Completed),
+            "hiatus" => Ok(Status::Hiatus),
+            // "drop" is kept for older clients
+            "dropped" | "drop" => Ok(Status::Dropped),
+            _ => Err(format!(
+                "Unknown status '{}'. Expected one of: ongoing, completed, hiatus, dropped",
+                s
+            )),
+        }
+    }
 }
 
-impl Default for Status {
-    fn default() -> Self {
-        Status::Ongoing
+impl TryFrom<String> for Status {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// A release date as sent by clients: `YYYY-MM-DD`, or a bare year from before
+/// `release_date` became a date column
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReleaseDateInput {
+    Year(i32),
+    Text(String),
+}
+
+/// Parse an optional release date; bare years map to January 1st like the migration did
+fn deserialize_release_date<'de, D>(deserializer: D) -> Result<Option<NaiveDate>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let year_start = |year: i32| {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .filter(|_| (1..=9999).contains(&year))
+            .ok_or_else(|| de::Error::custom(format!("release_date year {} is out of range", year)))
+    };
+
+    match Option::<ReleaseDateInput>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(ReleaseDateInput::Year(year)) => year_start(year).map(Some),
+        Some(ReleaseDateInput::Text(text)) => {
+            let text = text.trim();
+            if text.is_empty() {
+                return Ok(None);
+            }
+            if let Ok(year) = text.parse::<i32>() {
+                return year_start(year).map(Some);
+            }
+            NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                .map(Some)
+                .map_err(|_| {
+                    de::Error::custom(format!("release_date '{}' must be a YYYY-MM-DD date", text))
+                })
+        }
     }
 }
 
@@ -34,14 +136,24 @@ impl Default for Status {
 pub struct Book {
     pub id: String,
     pub title: String,
+    pub slug: String,
+    /// Copy of the linked `Author.name`, kept so listings and search need no join
     pub author: String,
+    pub author_id: String,
     pub cover: String,
     pub description: String,
     pub asset: Option<String>,
+    pub series_id: Option<String>,
+    pub series_position: Option<i32>,
     pub status: Status,
     pub language: Language,
-    pub release_date: Option<i32>,
+    /// Stored as a DATE since 20260118090000; rows that held a bare year were
+    /// converted to January 1st of that year (see that migration to reverse it)
+    pub release_date: Option<NaiveDate>,
     pub popular: bool,
+    pub views: i64,
+    /// Bumped on every edit; see `UpdateBookDto::version`
+    pub version: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -50,16 +162,46 @@ pub struct Book {
 pub struct BookDto {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub author: String,
+    pub author_id: String,
     pub cover: String,
     pub description: String,
     pub asset: Option<String>,
+    pub series_id: Option<String>,
+    pub series_position: Option<i32>,
     pub status: Status,
     pub language: Language,
-    pub release_date: Option<i32>,
+    pub release_date: Option<NaiveDate>,
     pub popular: bool,
+    pub views: i64,
+    pub version: i32,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genres: Option<Vec<GenreDto>>,
+    /// Only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<TagDto>>,
+    /// Only populated when fetching a single book that belongs to a series
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub series: Option<SeriesInfo>,
+    /// Only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rating: Option<RatingSummary>,
+    /// Only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark_count: Option<i64>,
+    /// Only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_count: Option<i64>,
+    /// Sum of the chapters' `word_count`; only populated when fetching a single book
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_word_count: Option<i64>,
+    /// Whether the signed-in caller bookmarked the book; null for anonymous listings
+    #[serde(default)]
+    pub is_bookmarked: Option<bool>,
 }
 
 impl From<Book> for BookDto {
@@ -67,53 +209,215 @@ impl From<Book> for BookDto {
         Self {
             id: book.id,
             title: book.title,
+            slug: book.slug,
             author: book.author,
+            author_id: book.author_id,
             cover: book.cover,
             description: book.description,
             asset: book.asset,
+            series_id: book.series_id,
+            series_position: book.series_position,
             status: book.status,
             language: book.language,
             release_date: book.release_date,
             popular: book.popular,
+            views: book.views,
+            version: book.version,
             created_at: book.created_at,
             updated_at: book.updated_at,
+            genres: None,
+            tags: None,
+            series: None,
+            rating: None,
+            bookmark_count: None,
+            chapter_count: None,
+            total_word_count: None,
+            is_bookmarked: None,
         }
     }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize)]
+pub struct BookmarkCountDto {
+    pub book_id: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+#[validate(schema(function = "validate_book_author"))]
 pub struct CreateBookDto {
+    #[validate(length(min = 1, message = "must not be empty"))]
     pub title: String,
+    /// Author name, used to find or create the author when `author_id` is absent
+    #[serde(default)]
     pub author: String,
+    pub author_id: Option<String>,
     pub cover: String,
     pub description: String,
     pub asset: Option<String>,
+    /// Both set or both absent; positions start at 1
+    pub series_id: Option<String>,
+    pub series_position: Option<i32>,
     #[serde(default)]
     pub status: Status,
     #[serde(default)]
     pub language: Language,
-    pub release_date: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_release_date")]
+    pub release_date: Option<NaiveDate>,
     #[serde(default)]
     pub popular: bool,
 }
 
+/// Body of `PUT /api/book/{id}`: every field of the book plus the `version` it was read at
+#[derive(Debug, Deserialize)]
+pub struct ReplaceBookDto {
+    /// The `version` the client last read; a stale value is rejected with 409
+    pub version: i32,
+    #[serde(flatten)]
+    pub book: CreateBookDto,
+}
+
+fn check_series_position(position: Option<i32>) -> AppResult<()> {
+    if position.is_some_and(|p| p < 1) {
+        return Err(AppError::BadRequest(
+            "series_position must be at least 1".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+impl CreateBookDto {
+    pub fn check_series(&self) -> AppResult<()> {
+        if self.series_id.is_some() != self.series_position.is_some() {
+            return Err(AppError::BadRequest(
+                "series_id and series_position must be given together".to_string(),
+            ));
+        }
+        check_series_position(self.series_position)
+    }
+}
+
+impl UpdateBookDto {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.author.is_none()
+            && self.author_id.is_none()
+            && self.cover.is_none()
+            && self.description.is_none()
+            && self.asset.is_none()
+            && self.series_id.is_none()
+            && self.series_position.is_none()
+            && self.status.is_none()
+            && self.language.is_none()
+            && self.release_date.is_none()
+            && self.popular.is_none()
+    }
+
+    pub fn check_series(&self) -> AppResult<()> {
+        if self.series_id.is_some() && self.series_position.is_none() {
+            return Err(AppError::BadRequest(
+                "series_position is required when changing series_id".to_string(),
+            ));
+        }
+        check_series_position(self.series_position)
+    }
+}
+
+fn validate_book_author(dto: &CreateBookDto) -> Result<(), ValidationError> {
+    if dto.author_id.is_none() && dto.author.trim().is_empty() {
+        return Err(ValidationError::new("author_required")
+            .with_message("either author or author_id is required".into()));
+    }
+    Ok(())
+}
+
+/// One row of a CSV import, keyed by header name; blank optional cells take the create defaults
+#[derive(Debug, Deserialize)]
+pub struct BookCsvRow {
+    pub title: String,
+    pub author: String,
+    #[serde(default)]
+    pub cover: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub asset: Option<String>,
+    #[serde(default)]
+    pub status: Option<Status>,
+    #[serde(default)]
+    pub language: Option<Language>,
+    #[serde(default, deserialize_with = "deserialize_release_date")]
+    pub release_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub popular: Option<bool>,
+}
+
+impl From<BookCsvRow> for CreateBookDto {
+    fn from(row: BookCsvRow) -> Self {
+        Self {
+            title: row.title,
+            author: row.author,
+            author_id: None,
+            cover: row.cover,
+            description: row.description,
+            asset: row.asset.filter(|a| !a.is_empty()),
+            series_id: None,
+            series_position: None,
+            status: row.status.unwrap_or_default(),
+            language: row.language.unwrap_or_default(),
+            release_date: row.release_date,
+            popular: row.popular.unwrap_or_default(),
+        }
+    }
+}
+
+/// A CSV row that was not imported; `row` is the 1-based line the record starts on
+#[derive(Debug, Serialize)]
+pub struct BookImportError {
+    pub row: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct BookImportSummary {
+    pub inserted: usize,
+    pub skipped: usize,
+    pub errors: Vec<BookImportError>,
+}
+
+/// Bumped whenever the export bundle layout changes incompatibly
+pub const BOOK_EXPORT_SCHEMA_VERSION: u32 = 1;
+
+/// Everything in a book export except the chapters, which are streamed after it
+#[derive(Debug, Serialize)]
+pub struct BookExportHeader {
+    pub schema_version: u32,
+    pub exported_at: NaiveDateTime,
+    pub book: BookDto,
+    pub genres: Vec<GenreDto>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct UpdateBookDto {
+    /// The `version` the client last read; a stale value is rejected with 409
+    pub version: i32,
     pub title: Option<String>,
+    /// Ignored when `author_id` is also given
     pub author: Option<String>,
+    pub author_id: Option<String>,
     pub cover: Option<String>,
     pub description: Option<String>,
     pub asset: Option<String>,
+    /// Moving a book to another series needs `series_position` too
+    pub series_id: Option<String>,
+    pub series_position: Option<i32>,
     pub status: Option<Status>,
     pub language: Option<Language>,
-    pub release_date: Option<i32>,
+    #[serde(default, deserialize_with = "deserialize_release_date")]
+    pub release_date: Option<NaiveDate>,
     pub popular: Option<bool>,
 }
 
-
-
-
-
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct Bookmark {
     pub id: String,
@@ -140,4 +444,208 @@ impl From<Bookmark> for BookmarkDto {
             created_at: bookmark.created_at,
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookSearchParams {
+    pub q: Option<String>,
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+}
+
+impl BookSearchParams {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_pagination(self.page, self.page_size)
+    }
+}
+
+/// Query parameters for the book listing
+#[derive(Debug, Deserialize)]
+pub struct BookQuery {
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+    pub search: Option<String>,
+    pub genres: Option<String>,
+    /// Comma-separated; only books carrying all of them match
+    pub tags: Option<String>,
+    pub status: Option<Status>,
+    pub language: Option<Language>,
+    pub popular: Option<bool>,
+    /// Inclusive bounds on `release_date`, as `YYYY-MM-DD`
+    pub released_after: Option<NaiveDate>,
+    pub released_before: Option<NaiveDate>,
+    pub sort: Option<String>,
+    /// Keyset pagination: passing `cursor` or `limit` switches the listing to cursor mode
+    pub cursor: Option<String>,
+    #[serde(default, deserialize_with = "deserialize_limit")]
+    pub limit: Option<i64>,
+}
+
+impl BookQuery {
+    pub fn is_cursor_mode(&self) -> bool {
+        self.cursor.is_some() || self.limit.is_some()
+    }
+
+    pub fn cursor_limit(&self) -> i64 {
+        self.limit.unwrap_or_else(default_page_size)
+    }
+
+    pub fn validate(&self) -> AppResult<()> {
+        if let (Some(after), Some(before)) = (self.released_after, self.released_before) {
+            if after > before {
+                return Err(AppError::BadRequest(
+                    "released_after must not be later than released_before".to_string(),
+                ));
+            }
+        }
+
+        if !self.is_cursor_mode() {
+            return validate_pagination(self.page, self.page_size);
+        }
+
+        if !(1..=MAX_PAGE_SIZE).contains(&self.cursor_limit()) {
+            return Err(AppError::BadRequest(format!(
+                "limit must be between 1 and {}",
+                MAX_PAGE_SIZE
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Query for the admin book listing; `created_*` are inclusive RFC 3339 timestamps
+#[derive(Debug, Deserialize)]
+pub struct AdminBookQuery {
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl AdminBookQuery {
+    pub fn validate(&self) -> AppResult<()> {
+        if let (Some(after), Some(before)) = (self.created_after, self.created_before) {
+            if after > before {
+                return Err(AppError::BadRequest(
+                    "created_after must not be later than created_before".to_string(),
+                ));
+            }
+        }
+
+        validate_pagination(self.page, self.page_size)
+    }
+}
+
+/// Position of the last book on a cursor page, ordered by `(created_at, id)` descending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BookCursor {
+    pub created_at: NaiveDateTime,
+    pub id: String,
+}
+
+impl BookCursor {
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).unwrap_or_default();
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(cursor: &str) -> AppResult<Self> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| AppError::BadRequest("Invalid cursor".to_string()))
+    }
+}
+
+/// Supported orderings for the book listing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BookSort {
+    #[default]
+    Newest,
+    Oldest,
+    TitleAsc,
+    Popular,
+}
+
+impl BookSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BookSort::Newest => "newest",
+            BookSort::Oldest => "oldest",
+            BookSort::TitleAsc => "title_asc",
+            BookSort::Popular => "popular",
+        }
+    }
+
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            BookSort::Newest => " ORDER BY created_at DESC",
+            BookSort::Oldest => " ORDER BY created_at ASC",
+            BookSort::TitleAsc => " ORDER BY title ASC",
+            BookSort::Popular => " ORDER BY popular DESC, created_at DESC",
+        }
+    }
+}
+
+impl FromStr for BookSort {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "newest" => Ok(BookSort::Newest),
+            "oldest" => Ok(BookSort::Oldest),
+            // "alphabetical" is kept for older clients
+            "title_asc" | "alphabetical" => Ok(BookSort::TitleAsc),
+            "popular" => Ok(BookSort::Popular),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown sort '{}'. Expected one of: newest, oldest, title_asc, popular",
+                other
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, response::IntoResponse, Json};
+
+    #[test]
+    fn parses_languages_case_insensitively() {
+        assert_eq!("English".parse::<Language>().unwrap(), Language::English);
+        assert_eq!(" KOREAN ".parse::<Language>().unwrap(), Language::Korean);
+        assert_eq!("ja".parse::<Language>().unwrap(), Language::Japanese);
+        assert_eq!("Chinese".parse::<Language>().unwrap(), Language::Chinese);
+        assert_eq!("id".parse::<Language>().unwrap(), Language::Indonesian);
+    }
+
+    #[test]
+    fn rejects_unknown_languages() {
+        assert!("klingon".parse::<Language>().is_err());
+        assert!("".parse::<Language>().is_err());
+    }
+
+    #[test]
+    fn serializes_languages_as_lowercase_codes() {
+        assert_eq!(serde_json::to_value(Language::Indonesian).unwrap(), "indonesian");
+        assert_eq!(serde_json::to_value(Language::English).unwrap(), "english");
+    }
+
+    #[test]
+    fn unknown_language_in_a_body_is_unprocessable() {
+        let body = br#"{"title": "T", "cover": "", "description": "", "language": "klingon"}"#;
+        let rejection = Json::<CreateBookDto>::from_bytes(body).unwrap_err();
+        assert_eq!(rejection.into_response().status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+        let body = br#"{"title": "T", "cover": "", "description": "", "language": "Japanese"}"#;
+        let Json(book) = Json::<CreateBookDto>::from_bytes(body).unwrap();
+        assert_eq!(book.language, Language::Japanese);
+    }
+}
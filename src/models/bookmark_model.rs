@@ -1,6 +1,13 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::paging_model::{
+    default_page, default_page_size, deserialize_page, deserialize_page_size, validate_pagination,
+};
+use crate::models::reading_progress_model::ReadingProgressDto;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::str::FromStr;
+use validator::Validate;
 
 #[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
 pub struct Bookmark {
@@ -23,14 +30,76 @@ pub struct BookmarkWithBook {
     pub book_cover: String,
     pub book_author: String,
     pub book_description: String,
+    // Latest reading progress, if any
+    pub progress_chapter_id: Option<String>,
+    pub progress_chapter_num: Option<i32>,
+    pub progress_percent: Option<f64>,
+    pub progress_updated_at: Option<NaiveDateTime>,
 }
 
 /// DTO for creating a bookmark
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Validate)]
 pub struct CreateBookmarkDto {
+    #[validate(length(min = 1, message = "must not be empty"))]
     pub book_id: String,
 }
 
+/// Query for listing the current user's bookmarks
+#[derive(Debug, Deserialize)]
+pub struct BookmarkQuery {
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+    pub sort: Option<String>,
+}
+
+impl BookmarkQuery {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_pagination(self.page, self.page_size)
+    }
+
+    pub fn sort(&self) -> AppResult<BookmarkSort> {
+        self.sort
+            .as_deref()
+            .map(BookmarkSort::from_str)
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// Supported orderings for the bookmark list
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BookmarkSort {
+    #[default]
+    Recent,
+    Title,
+}
+
+impl BookmarkSort {
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            BookmarkSort::Recent => "ORDER BY b.created_at DESC, b.id DESC",
+            BookmarkSort::Title => "ORDER BY bk.title ASC, b.id ASC",
+        }
+    }
+}
+
+impl FromStr for BookmarkSort {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "recent" => Ok(BookmarkSort::Recent),
+            "title" => Ok(BookmarkSort::Title),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown sort '{}'. Expected one of: recent, title",
+                other
+            ))),
+        }
+    }
+}
+
 /// Response DTO for bookmark
 #[derive(Debug, Clone, Serialize)]
 pub struct BookmarkResponse {
@@ -46,6 +115,7 @@ pub struct BookmarkWithBookResponse {
     pub book_id: String,
     pub created_at: NaiveDateTime,
     pub book: BookSummary,
+    pub progress: Option<ReadingProgressDto>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -76,6 +146,21 @@ impl From<Bookmark> for BookmarkResponse {
 
 impl From<BookmarkWithBook> for BookmarkWithBookResponse {
     fn from(b: BookmarkWithBook) -> Self {
+        let progress = match (
+            b.progress_chapter_id,
+            b.progress_chapter_num,
+            b.progress_updated_at,
+        ) {
+            (Some(chapter_id), Some(chapter_num), Some(updated_at)) => Some(ReadingProgressDto {
+                book_id: b.book_id.clone(),
+                chapter_id,
+                chapter_num,
+                percent: b.progress_percent.unwrap_or_default(),
+                updated_at,
+            }),
+            _ => None,
+        };
+
         Self {
             id: b.id,
             book_id: b.book_id.clone(),
@@ -87,6 +172,7 @@ impl From<BookmarkWithBook> for BookmarkWithBookResponse {
                 author: b.book_author,
                 description: b.book_description,
             },
+            progress,
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::utils::reading_time::reading_time_minutes;
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
@@ -12,6 +13,8 @@ pub struct Chapter {
     pub updated_at: NaiveDateTime,
     pub content: String,
     pub chapter_num: i32,
+    /// Words in `content` with HTML stripped, kept in step on every content write
+    pub word_count: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,10 +27,13 @@ pub struct ChapterDto {
     pub updated_at: NaiveDateTime,
     pub content: String,
     pub chapter_num: i32,
+    pub word_count: i32,
+    pub reading_time_minutes: i32,
 }
 
-impl From<Chapter> for ChapterDto {
-    fn from(chapter: Chapter) -> Self {
+impl ChapterDto {
+    /// Reading time depends on the configured speed, so it is derived here rather than stored
+    pub fn new(chapter: Chapter, reading_wpm: u32) -> Self {
         Self {
             id: chapter.id,
             title: chapter.title,
@@ -37,6 +43,36 @@ impl From<Chapter> for ChapterDto {
             updated_at: chapter.updated_at,
             content: chapter.content,
             chapter_num: chapter.chapter_num,
+            word_count: chapter.word_count,
+            reading_time_minutes: reading_time_minutes(chapter.word_count, reading_wpm),
+        }
+    }
+}
+
+/// Chapter row joined with the ids of its neighbours within the same book
+#[derive(Debug, Clone, FromRow)]
+pub struct ChapterWithNeighbors {
+    #[sqlx(flatten)]
+    pub chapter: Chapter,
+    pub prev_chapter_id: Option<String>,
+    pub next_chapter_id: Option<String>,
+}
+
+/// Single-chapter read response with prefetch hints for reader apps
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChapterDetailDto {
+    #[serde(flatten)]
+    pub chapter: ChapterDto,
+    pub prev_chapter_id: Option<String>,
+    pub next_chapter_id: Option<String>,
+}
+
+impl ChapterDetailDto {
+    pub fn new(row: ChapterWithNeighbors, reading_wpm: u32) -> Self {
+        Self {
+            chapter: ChapterDto::new(row.chapter, reading_wpm),
+            prev_chapter_id: row.prev_chapter_id,
+            next_chapter_id: row.next_chapter_id,
         }
     }
 }
@@ -47,7 +83,8 @@ pub struct CreateChapterDto {
     pub book_id: String,
     pub description: String,
     pub content: String,
-    pub chapter_num: i32,
+    /// Defaults to the book's highest chapter number + 1 when omitted
+    pub chapter_num: Option<i32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -58,3 +95,8 @@ pub struct UpdateChapterDto {
     pub chapter_num: Option<i32>,
 }
 
+/// Full new ordering of a book's chapters; must list every chapter exactly once
+#[derive(Debug, Deserialize)]
+pub struct ReorderChaptersDto {
+    pub chapter_ids: Vec<String>,
+}
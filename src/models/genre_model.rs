@@ -1,11 +1,17 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::paging_model::{
+    default_page, default_page_size, deserialize_page, deserialize_page_size, validate_pagination,
+};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use std::str::FromStr;
 
 #[derive(Debug, Clone, FromRow)]
 pub struct Genre {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub description: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
@@ -16,9 +22,84 @@ pub struct Genre {
 pub struct GenreDto {
     pub id: String,
     pub title: String,
+    pub slug: String,
     pub description: String,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
+    /// Only populated in the genre listing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub book_count: Option<i64>,
+}
+
+/// Genre row joined with how many books carry it
+#[derive(Debug, Clone, FromRow)]
+pub struct GenreWithBookCount {
+    #[sqlx(flatten)]
+    pub genre: Genre,
+    pub book_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenreQuery {
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+    pub search: Option<String>,
+    pub sort: Option<String>,
+}
+
+impl GenreQuery {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_pagination(self.page, self.page_size)
+    }
+
+    pub fn sort(&self) -> AppResult<GenreSort> {
+        self.sort
+            .as_deref()
+            .map(GenreSort::from_str)
+            .transpose()
+            .map(Option::unwrap_or_default)
+    }
+}
+
+/// Supported orderings for the genre listing
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum GenreSort {
+    #[default]
+    Name,
+    Popular,
+}
+
+impl GenreSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenreSort::Name => "name",
+            GenreSort::Popular => "popular",
+        }
+    }
+
+    pub fn order_by(&self) -> &'static str {
+        match self {
+            GenreSort::Name => " ORDER BY g.title ASC, g.id ASC",
+            GenreSort::Popular => " ORDER BY book_count DESC, g.title ASC, g.id ASC",
+        }
+    }
+}
+
+impl FromStr for GenreSort {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "name" => Ok(GenreSort::Name),
+            "popular" => Ok(GenreSort::Popular),
+            other => Err(AppError::BadRequest(format!(
+                "Unknown sort '{}'. Expected one of: name, popular",
+                other
+            ))),
+        }
+    }
 }
 
 #[derive(Deserialize, Clone)]
@@ -34,14 +115,68 @@ pub struct UpdateGenreDto {
     pub description:Option<String>,
 }
 
+/// `force=true` deletes the genre even while books still reference it
+#[derive(Debug, Default, Deserialize)]
+pub struct DeleteGenreQuery {
+    #[serde(default)]
+    pub force: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AddBookGenresDto {
+    pub genre_ids: Vec<String>,
+}
+
 impl From<Genre> for GenreDto {
     fn from(genre: Genre) -> Self {
         Self {
             id: genre.id,
             title: genre.title,
+            slug: genre.slug,
             description: genre.description,
             created_at: genre.created_at,
             updated_at: genre.updated_at,
+            book_count: None,
         }
     }
 }
+
+impl From<GenreWithBookCount> for GenreDto {
+    fn from(row: GenreWithBookCount) -> Self {
+        Self {
+            book_count: Some(row.book_count),
+            ..row.genre.into()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenreTrendParams {
+    #[serde(default = "default_trend_days")]
+    pub days: i64,
+    #[serde(default = "default_trend_limit")]
+    pub limit: i64,
+}
+
+fn default_trend_days() -> i64 {
+    30
+}
+
+fn default_trend_limit() -> i64 {
+    10
+}
+
+/// Activity counts for one genre within the trend window
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct GenreTrendDto {
+    pub genre_id: String,
+    pub title: String,
+    pub new_books: i64,
+    pub new_bookmarks: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GenreTrendsResponse {
+    pub days: i64,
+    pub genres: Vec<GenreTrendDto>,
+}
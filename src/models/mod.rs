@@ -1,9 +1,16 @@
 pub mod auth_model;
+pub mod author_model;
 pub mod book_model;
 pub mod bookmark_model;
 pub mod chapter_model;
 pub mod genre_model;
+pub mod notification_model;
 pub mod paging_model;
+pub mod reading_progress_model;
 pub mod response_model;
+pub mod series_model;
+pub mod review_model;
+pub mod stats_model;
+pub mod tag_model;
 pub mod upload_model;
 pub mod user_model;
@@ -0,0 +1,40 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Database model for an in-app notification
+#[derive(Debug, Clone, FromRow)]
+pub struct Notification {
+    pub id: String,
+    pub user_id: String,
+    pub title: String,
+    pub body: String,
+    pub novel_id: Option<String>,
+    pub chapter_id: Option<String>,
+    pub read_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+/// Inbox entry returned to the owning user
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationDto {
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    pub novel_id: Option<String>,
+    pub chapter_id: Option<String>,
+    pub read: bool,
+    pub created_at: NaiveDateTime,
+}
+
+/// Response for bulk mark-as-read
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkAllReadResponse {
+    pub marked: u64,
+}
+
+/// Per-user push preferences
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct NotificationPreferences {
+    pub notify_new_chapters: bool,
+}
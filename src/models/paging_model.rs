@@ -1,29 +1,199 @@
-use serde::{Deserialize, Serialize};
+use crate::errors::{AppError, AppResult};
+use crate::models::response_model::ApiResponse;
+use serde::{Deserialize, Deserializer, Serialize};
+
+pub const MAX_PAGE_SIZE: i64 = 100;
 
 #[derive(Debug, Deserialize)]
 pub struct PaginationParams {
-    #[serde(default = "default_page")]
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
     pub page: i64,
-    #[serde(default = "default_page_size")]
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
     pub page_size: i64,
     pub search: Option<String>,
-    pub genres: Option<String>,
-    pub sort: Option<String>, // newest, oldest, popular, alphabetical
 }
 
-fn default_page() -> i64 {
+impl PaginationParams {
+    pub fn validate(&self) -> AppResult<()> {
+        validate_pagination(self.page, self.page_size)
+    }
+}
+
+pub fn default_page() -> i64 {
     1
 }
 
-fn default_page_size() -> i64 {
+pub fn default_page_size() -> i64 {
     10
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct PaginatedResponse<T> {
-    pub data: Vec<T>,
-    pub page: i64,
+/// Pages below 1 fall back to the first page
+pub fn deserialize_page<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let page = i64::deserialize(deserializer)?;
+    Ok(if page < 1 { default_page() } else { page })
+}
+
+/// Non-positive sizes fall back to the default and oversized ones are clamped to `MAX_PAGE_SIZE`
+pub fn deserialize_page_size<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let page_size = i64::deserialize(deserializer)?;
+    Ok(normalize_page_size(page_size))
+}
+
+/// Same normalization as `deserialize_page_size`, for optional limits such as cursor pages
+pub fn deserialize_limit<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let limit = Option::<i64>::deserialize(deserializer)?;
+    Ok(limit.map(normalize_page_size))
+}
+
+fn normalize_page_size(page_size: i64) -> i64 {
+    if page_size < 1 {
+        default_page_size()
+    } else {
+        page_size.min(MAX_PAGE_SIZE)
+    }
+}
+
+/// Shared bounds check for every query type that carries `page`/`page_size`.
+/// Both are normalized while deserializing, so what's left to reject is a page
+/// so far out that its offset doesn't fit in an `i64`.
+pub fn validate_pagination(page: i64, page_size: i64) -> AppResult<()> {
+    page_offset(page, page_size).map(|_| ())
+}
+
+/// Rows skipped before `page`, or 400 when the offset would overflow
+pub fn page_offset(page: i64, page_size: i64) -> AppResult<i64> {
+    page.checked_sub(1)
+        .and_then(|skipped| skipped.checked_mul(page_size))
+        .filter(|offset| *offset >= 0)
+        .ok_or_else(|| AppError::BadRequest("page is out of range".to_string()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaginationMeta {
+    pub current_page: i64,
     pub page_size: i64,
     pub total_items: i64,
     pub total_pages: i64,
+    pub has_next: bool,
+    pub has_previous: bool,
+}
+
+impl PaginationMeta {
+    pub fn new(page: i64, page_size: i64, total_items: i64) -> Self {
+        let total_pages = if page_size > 0 {
+            (total_items + page_size - 1) / page_size
+        } else {
+            0
+        };
+        Self {
+            current_page: page,
+            page_size,
+            total_items,
+            total_pages,
+            has_next: page < total_pages,
+            has_previous: page > 1,
+        }
+    }
+}
+
+/// Offset-paginated list in the standard envelope
+pub type PaginatedApiResponse<T> = ApiResponse<Vec<T>, PaginationMeta>;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PaginatedResponse<T> {
+    pub data: Vec<T>,
+    #[serde(flatten)]
+    pub meta: PaginationMeta,
+}
+
+impl<T> PaginatedResponse<T> {
+    pub fn new(data: Vec<T>, page: i64, page_size: i64, total_items: i64) -> Self {
+        Self {
+            data,
+            meta: PaginationMeta::new(page, page_size, total_items),
+        }
+    }
+
+    /// `{status, data, meta}`, the same top-level shape as unpaginated responses
+    pub fn into_api_response(self) -> PaginatedApiResponse<T>
+    where
+        T: Serialize,
+    {
+        ApiResponse::with_meta(self.data, self.meta)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CursorMeta {
+    pub limit: i64,
+    /// Opaque cursor for the next page, `None` once the listing is exhausted
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CursorPaginatedResponse<T> {
+    pub data: Vec<T>,
+    pub meta: CursorMeta,
+}
+
+impl<T> CursorPaginatedResponse<T> {
+    pub fn into_api_response(self) -> ApiResponse<Vec<T>, CursorMeta>
+    where
+        T: Serialize,
+    {
+        ApiResponse::with_meta(self.data, self.meta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(value: serde_json::Value) -> PaginationParams {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn missing_values_use_defaults() {
+        let p = params(json!({}));
+        assert_eq!((p.page, p.page_size), (1, 10));
+    }
+
+    #[test]
+    fn non_positive_values_fall_back_to_defaults() {
+        let p = params(json!({ "page": 0, "page_size": 0 }));
+        assert_eq!((p.page, p.page_size), (1, 10));
+
+        let p = params(json!({ "page": -5, "page_size": -1 }));
+        assert_eq!((p.page, p.page_size), (1, 10));
+    }
+
+    #[test]
+    fn page_size_is_clamped_to_max() {
+        assert_eq!(params(json!({ "page_size": MAX_PAGE_SIZE })).page_size, MAX_PAGE_SIZE);
+        assert_eq!(params(json!({ "page_size": 100_000 })).page_size, MAX_PAGE_SIZE);
+    }
+
+    #[test]
+    fn offset_of_first_and_later_pages() {
+        assert_eq!(page_offset(1, 10).unwrap(), 0);
+        assert_eq!(page_offset(3, 25).unwrap(), 50);
+    }
+
+    #[test]
+    fn huge_page_is_rejected() {
+        let p = params(json!({ "page": i64::MAX, "page_size": MAX_PAGE_SIZE }));
+        assert!(matches!(p.validate(), Err(AppError::BadRequest(_))));
+        assert!(page_offset(i64::MAX, 2).is_err());
+    }
 }
@@ -0,0 +1,43 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Database model for a user's position in a book
+#[derive(Debug, Clone, FromRow)]
+pub struct ReadingProgress {
+    pub user_id: String,
+    pub book_id: String,
+    pub chapter_id: String,
+    pub chapter_num: i32,
+    pub percent: f64,
+    pub updated_at: NaiveDateTime,
+}
+
+/// DTO for saving reading progress; `chapter_num` is taken from the chapter itself
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateReadingProgressDto {
+    pub chapter_id: String,
+    #[serde(default)]
+    pub percent: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadingProgressDto {
+    pub book_id: String,
+    pub chapter_id: String,
+    pub chapter_num: i32,
+    pub percent: f64,
+    pub updated_at: NaiveDateTime,
+}
+
+impl From<ReadingProgress> for ReadingProgressDto {
+    fn from(progress: ReadingProgress) -> Self {
+        Self {
+            book_id: progress.book_id,
+            chapter_id: progress.chapter_id,
+            chapter_num: progress.chapter_num,
+            percent: progress.percent,
+            updated_at: progress.updated_at,
+        }
+    }
+}
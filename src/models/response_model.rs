@@ -6,26 +6,47 @@ use axum::{
 };
 use serde::Serialize;
 
+/// Envelope for every successful JSON response: `{status, message?, data, meta?}`.
+/// Failures use the `AppError` body instead.
 #[derive(Serialize)]
-pub struct ApiResponse<T> {
+pub struct ApiResponse<T, M = ()> {
+    pub status: &'static str,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<T>,
+    /// Pagination details for list responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<M>,
+}
+
+impl<T: Serialize, M: Serialize> ApiResponse<T, M> {
+    pub fn with_meta(data: T, meta: M) -> Self {
+        Self {
+            status: "success",
+            message: None,
+            data: Some(data),
+            meta: Some(meta),
+        }
+    }
 }
 
 impl<T: Serialize> ApiResponse<T> {
     pub fn success(data: T) -> Self {
         Self {
+            status: "success",
             message: None,
             data: Some(data),
+            meta: None,
         }
     }
 
     pub fn with_message(message: impl Into<String>, data: T) -> Self {
         Self {
+            status: "success",
             message: Some(message.into()),
             data: Some(data),
+            meta: None,
         }
     }
 }
@@ -39,4 +60,29 @@ impl IntoResponse for ErrorResponse {
     fn into_response(self) -> Response {
         (StatusCode::INTERNAL_SERVER_ERROR, Json(self)).into_response()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn success_envelope_omits_empty_fields() {
+        let body = serde_json::to_value(ApiResponse::success(json!({"id": "b1"}))).unwrap();
+        assert_eq!(body, json!({"status": "success", "data": {"id": "b1"}}));
+    }
+
+    #[test]
+    fn message_and_meta_sit_beside_data() {
+        let body = serde_json::to_value(ApiResponse::with_message("Created", 7)).unwrap();
+        assert_eq!(body, json!({"status": "success", "message": "Created", "data": 7}));
+
+        let paged = ApiResponse::with_meta(vec![1, 2], json!({"total_items": 2}));
+        let body = serde_json::to_value(paged).unwrap();
+        assert_eq!(
+            body,
+            json!({"status": "success", "data": [1, 2], "meta": {"total_items": 2}})
+        );
+    }
+}
@@ -0,0 +1,62 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+pub const MIN_RATING: i16 = 1;
+pub const MAX_RATING: i16 = 5;
+
+/// Database model for a user's review of a book
+#[derive(Debug, Clone, FromRow)]
+pub struct Review {
+    pub id: String,
+    pub user_id: String,
+    pub book_id: String,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewDto {
+    pub id: String,
+    pub user_id: String,
+    pub book_id: String,
+    pub rating: i16,
+    pub body: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CreateReviewDto {
+    pub rating: i16,
+    pub body: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateReviewDto {
+    pub rating: Option<i16>,
+    pub body: Option<String>,
+}
+
+/// Aggregate rating for a book
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct RatingSummary {
+    pub average: Option<f64>,
+    pub count: i64,
+}
+
+impl From<Review> for ReviewDto {
+    fn from(review: Review) -> Self {
+        Self {
+            id: review.id,
+            user_id: review.user_id,
+            book_id: review.book_id,
+            rating: review.rating,
+            body: review.body,
+            created_at: review.created_at,
+            updated_at: review.updated_at,
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use validator::Validate;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Series {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesDto {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub description: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+    pub book_count: i64,
+}
+
+/// Series row joined with how many books it holds
+#[derive(Debug, Clone, FromRow)]
+pub struct SeriesWithBookCount {
+    #[sqlx(flatten)]
+    pub series: Series,
+    pub book_count: i64,
+}
+
+impl From<SeriesWithBookCount> for SeriesDto {
+    fn from(row: SeriesWithBookCount) -> Self {
+        Self {
+            id: row.series.id,
+            title: row.series.title,
+            slug: row.series.slug,
+            description: row.series.description,
+            created_at: row.series.created_at,
+            updated_at: row.series.updated_at,
+            book_count: row.book_count,
+        }
+    }
+}
+
+/// Where a book sits in its series, enough for "Book 2 of 5"
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SeriesInfo {
+    pub id: String,
+    pub title: String,
+    pub slug: String,
+    pub position: i32,
+    pub book_count: i64,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct CreateSeriesDto {
+    #[validate(length(min = 1, message = "must not be empty"))]
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+}
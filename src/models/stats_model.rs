@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Site-wide totals for the admin dashboard
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct SiteStatsDto {
+    pub total_books: i64,
+    pub total_chapters: i64,
+    pub total_users: i64,
+    pub total_bookmarks: i64,
+    pub books_last_7_days: i64,
+}
@@ -0,0 +1,83 @@
+use crate::errors::{AppError, AppResult};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow)]
+pub struct Tag {
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TagDto {
+    pub id: String,
+    pub name: String,
+    pub created_at: NaiveDateTime,
+}
+
+impl From<Tag> for TagDto {
+    fn from(tag: Tag) -> Self {
+        Self {
+            id: tag.id,
+            name: tag.name,
+            created_at: tag.created_at,
+        }
+    }
+}
+
+/// Tag names to attach to a book; unknown tags are created
+#[derive(Debug, Deserialize)]
+pub struct AddBookTagsDto {
+    pub tags: Vec<String>,
+}
+
+impl AddBookTagsDto {
+    /// Lowercased, trimmed and deduplicated tag names
+    pub fn normalized(&self) -> AppResult<Vec<String>> {
+        let mut names = self
+            .tags
+            .iter()
+            .map(|tag| normalize_tag(tag))
+            .collect::<AppResult<Vec<_>>>()?;
+
+        if names.is_empty() {
+            return Err(AppError::BadRequest("tags must not be empty".to_string()));
+        }
+
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+}
+
+pub fn normalize_tag(tag: &str) -> AppResult<String> {
+    let name = tag.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(AppError::BadRequest(
+            "tag names must not be blank".to_string(),
+        ));
+    }
+    if name.contains(',') {
+        return Err(AppError::BadRequest(
+            "tag names must not contain commas".to_string(),
+        ));
+    }
+    Ok(name)
+}
+
+/// Split a comma-separated `tags` filter into normalized names, ignoring blanks
+pub fn parse_tags(tags: Option<&str>) -> Vec<String> {
+    let mut names: Vec<String> = tags
+        .map(|t| {
+            t.split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names.dedup();
+    names
+}
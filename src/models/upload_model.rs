@@ -21,8 +21,11 @@ pub struct UploadedImage {
     pub upload_id: String,
     pub original_path: String,
     pub cdn_url: String,
+    pub thumbnail_url: Option<String>,
     pub content_type: String,
     pub size: i64,
+    /// Hex SHA-256 of the image; unset for images stored before deduplication
+    pub content_hash: Option<String>,
     pub created_at: NaiveDateTime,
 }
 
@@ -34,6 +37,9 @@ pub struct ContentUploadResponse {
     pub images: Vec<ImageInfoDto>,
     pub format: String,
     pub created_at: NaiveDateTime,
+    /// Non-fatal extraction problems; only reported by the upload itself, never stored
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
 }
 
 /// Image info DTO
@@ -41,10 +47,29 @@ pub struct ContentUploadResponse {
 pub struct ImageInfoDto {
     pub filename: String,
     pub url: String,
+    pub thumbnail_url: Option<String>,
     pub content_type: String,
     pub size: u64,
 }
 
+/// Request for a presigned direct-to-storage upload
+#[derive(Debug, Deserialize)]
+pub struct PresignUploadDto {
+    pub filename: String,
+    pub content_type: String,
+    /// Optional book to group the asset under
+    pub book_id: Option<String>,
+}
+
+/// Presigned upload URL plus where the asset will be served from
+#[derive(Debug, Serialize)]
+pub struct PresignUploadResponse {
+    pub upload_url: String,
+    pub public_url: String,
+    pub key: String,
+    pub expires_in: i64,
+}
+
 /// Request for creating a chapter from uploaded content
 #[derive(Debug, Deserialize)]
 pub struct CreateChapterFromUploadDto {
@@ -63,6 +88,7 @@ impl From<ContentUpload> for ContentUploadResponse {
             images: vec![], // Images loaded separately
             format: upload.format,
             created_at: upload.created_at,
+            warnings: Vec::new(),
         }
     }
 }
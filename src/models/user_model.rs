@@ -1,3 +1,7 @@
+use crate::models::paging_model::{
+    default_page, default_page_size, deserialize_page, deserialize_page_size, PaginationParams,
+};
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -6,10 +10,29 @@ use sqlx::FromRow;
 #[derive(PartialEq)]
 pub enum Role {
     User,
+    Moderator,
     Admin,
 }
 
-#[derive(Clone, Debug, FromRow, Serialize, Deserialize)]
+impl Role {
+    /// Position in the hierarchy; a higher rank inherits every lower role's permissions.
+    fn rank(&self) -> u8 {
+        match self {
+            Role::User => 0,
+            Role::Moderator => 1,
+            Role::Admin => 2,
+        }
+    }
+
+    /// Whether this role grants at least the permissions of `required`
+    pub fn satisfies(&self, required: &Role) -> bool {
+        self.rank() >= required.rank()
+    }
+}
+
+/// Full user row including the password hash. Deliberately not `Serialize`:
+/// handlers must convert to `SafeUser` or `UserDto` before responding.
+#[derive(Clone, Debug, FromRow, Deserialize)]
 pub struct User {
     pub id: String,
     profile_pic: Option<String>,
@@ -18,6 +41,9 @@ pub struct User {
     pub role: Role,
     pub email: String,
     pub password:String,
+    pub notify_new_chapters: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
 }
 
 
@@ -29,6 +55,8 @@ pub struct SafeUser {
     pub bio: Option<String>,
     pub role: Role,
     pub email: String,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
 }
 
 impl From<User> for SafeUser {
@@ -40,6 +68,8 @@ impl From<User> for SafeUser {
             username: user.username,
             role: user.role,
             email: user.email,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
         }
     }
 }
@@ -60,3 +90,93 @@ impl From<User> for UserDto {
         }
     }
 }
+
+/// Query for the admin user search; an empty `q` matches every user
+#[derive(Debug, Deserialize)]
+pub struct UserSearchQuery {
+    pub q: Option<String>,
+    #[serde(default = "default_page", deserialize_with = "deserialize_page")]
+    pub page: i64,
+    #[serde(default = "default_page_size", deserialize_with = "deserialize_page_size")]
+    pub page_size: i64,
+}
+
+impl From<UserSearchQuery> for PaginationParams {
+    fn from(query: UserSearchQuery) -> Self {
+        Self {
+            page: query.page,
+            page_size: query.page_size,
+            search: query.q,
+        }
+    }
+}
+
+/// Password confirmation for deleting one's own account
+#[derive(Debug, Deserialize)]
+pub struct DeleteAccountDto {
+    pub password: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateUserRoleDto {
+    pub role: Role,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn admin_satisfies_every_role() {
+        assert!(Role::Admin.satisfies(&Role::Admin));
+        assert!(Role::Admin.satisfies(&Role::Moderator));
+        assert!(Role::Admin.satisfies(&Role::User));
+    }
+
+    #[test]
+    fn moderator_sits_between_user_and_admin() {
+        assert!(Role::Moderator.satisfies(&Role::Moderator));
+        assert!(Role::Moderator.satisfies(&Role::User));
+        assert!(!Role::Moderator.satisfies(&Role::Admin));
+    }
+
+    #[test]
+    fn user_satisfies_only_user() {
+        assert!(Role::User.satisfies(&Role::User));
+        assert!(!Role::User.satisfies(&Role::Moderator));
+        assert!(!Role::User.satisfies(&Role::Admin));
+    }
+
+    #[test]
+    fn moderator_round_trips_through_json() {
+        let role: Role = serde_json::from_str(r#""Moderator""#).unwrap();
+        assert_eq!(role, Role::Moderator);
+        assert_eq!(serde_json::to_string(&role).unwrap(), r#""Moderator""#);
+    }
+
+    #[test]
+    fn safe_user_json_never_carries_the_password() {
+        let now = chrono::Utc::now().naive_utc();
+        let user = User {
+            id: "ckx1user".to_string(),
+            profile_pic: None,
+            bio: Some("bio".to_string()),
+            username: "reader".to_string(),
+            role: Role::User,
+            email: "reader@example.com".to_string(),
+            password: "$argon2id$v=19$secret-hash".to_string(),
+            notify_new_chapters: true,
+            created_at: now,
+            updated_at: now,
+        };
+
+        for json in [
+            serde_json::to_value(SafeUser::from(user.clone())).unwrap(),
+            serde_json::to_value(UserDto::from(user)).unwrap(),
+        ] {
+            assert!(json.get("password").is_none());
+            assert!(!json.to_string().contains("argon2id"));
+            assert_eq!(json["email"], "reader@example.com");
+        }
+    }
+}
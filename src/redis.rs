@@ -1,16 +1,66 @@
-use redis::{aio::ConnectionManager, Client};
+use redis::{aio::{ConnectionManager, ConnectionManagerConfig}, Client};
 use anyhow::Result;
 use serde::{Serialize, de::DeserializeOwned};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Upper bound on connecting to and waiting for Redis, so an unhealthy cache
+/// degrades to a database read instead of stalling the request
+const REDIS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Refill-then-take on a hash of {tokens, ts}; runs atomically so concurrent
+/// requests on different instances can't both spend the last token
+const TOKEN_BUCKET_SCRIPT: &str = r#"
+local capacity = tonumber(ARGV[1])
+local refill_per_ms = tonumber(ARGV[2])
+local now = tonumber(ARGV[3])
+
+local state = redis.call('HMGET', KEYS[1], 'tokens', 'ts')
+local tokens = tonumber(state[1]) or capacity
+local ts = tonumber(state[2]) or now
+tokens = math.min(capacity, tokens + math.max(0, now - ts) * refill_per_ms)
+
+local allowed = 0
+local retry_after = 0
+if tokens >= 1 then
+    tokens = tokens - 1
+    allowed = 1
+else
+    retry_after = math.ceil((1 - tokens) / refill_per_ms)
+end
+
+redis.call('HSET', KEYS[1], 'tokens', tostring(tokens), 'ts', now)
+redis.call('PEXPIRE', KEYS[1], math.ceil(capacity / refill_per_ms))
+return {allowed, retry_after}
+"#;
+
+/// Hit/miss counts for cached JSON reads since startup
+#[derive(Debug, Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
 
+/// Redis access for caching and rate limiting. Without a connection every command
+/// fails fast, which callers already treat as a cache miss or a fail-open check.
 #[derive(Clone)]
 pub struct RedisClient {
-    pub connection: ConnectionManager,
+    pub connection: Option<ConnectionManager>,
+    counters: Arc<CacheCounters>,
 }
 
 impl std::fmt::Debug for RedisClient {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RedisClient")
-            .field("connection", &"ConnectionManager")
+            .field("connection", &self.connection.as_ref().map(|_| "ConnectionManager"))
+            .field("counters", &self.counters)
             .finish()
     }
 }
@@ -18,16 +68,47 @@ impl std::fmt::Debug for RedisClient {
 impl RedisClient {
     pub async fn new(redis_url: &str) -> Result<Self> {
         let client = Client::open(redis_url)?;
-        let connection = ConnectionManager::new(client).await?;
-        
-        Ok(Self { connection })
+        let config = ConnectionManagerConfig::new()
+            .set_connection_timeout(REDIS_TIMEOUT)
+            .set_response_timeout(REDIS_TIMEOUT);
+        let connection = ConnectionManager::new_with_config(client, config).await?;
+
+        Ok(Self {
+            connection: Some(connection),
+            counters: Arc::default(),
+        })
+    }
+
+    /// Client with no Redis behind it; every read is a miss
+    pub fn disabled() -> Self {
+        Self {
+            connection: None,
+            counters: Arc::default(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.connection.is_some()
+    }
+
+    pub fn cache_stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    fn conn(&self) -> Result<ConnectionManager> {
+        self.connection
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Redis is not configured"))
     }
 
     /// Test
     pub async fn test_connection(&self) -> Result<()> {
         tracing::info!("Testing Redis connection...");
         
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let _: String = redis::cmd("PING")
             .query_async(&mut conn)
             .await?;
@@ -38,7 +119,7 @@ impl RedisClient {
 
     /// Set string value dengan expiration (dalam detik)
     pub async fn set_ex(&self, key: &str, value: &str, ttl: i64) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("SETEX")
             .arg(key)
             .arg(ttl)
@@ -48,9 +129,23 @@ impl RedisClient {
         Ok(())
     }
 
+    /// Set a value with expiration only if the key is absent; returns whether it was set
+    pub async fn set_nx_ex(&self, key: &str, value: &str, ttl: i64) -> Result<bool> {
+        let mut conn = self.conn()?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl)
+            .query_async(&mut conn)
+            .await?;
+        Ok(result.is_some())
+    }
+
     /// Set value tanpa expiration
     pub async fn set(&self, key: &str, value: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("SET")
             .arg(key)
             .arg(value)
@@ -61,7 +156,7 @@ impl RedisClient {
 
     /// Get string value
     pub async fn get(&self, key: &str) -> Result<Option<String>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Option<String> = redis::cmd("GET")
             .arg(key)
             .query_async(&mut conn)
@@ -71,7 +166,7 @@ impl RedisClient {
 
     /// Delete key
     pub async fn del(&self, key: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("DEL")
             .arg(key)
             .query_async::<()>(&mut conn)
@@ -85,8 +180,18 @@ impl RedisClient {
         self.set_ex(key, &json, ttl).await
     }
 
-    /// Get JSON value
+    /// Get JSON value, counting the lookup as a cache hit or miss
     pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
+        let result = self.read_json(key).await;
+        let counter = match result {
+            Ok(Some(_)) => &self.counters.hits,
+            _ => &self.counters.misses,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    async fn read_json<T: DeserializeOwned>(&self, key: &str) -> Result<Option<T>> {
         if let Some(json_str) = self.get(key).await? {
             let value: T = serde_json::from_str(&json_str)?;
             Ok(Some(value))
@@ -97,7 +202,7 @@ impl RedisClient {
 
     /// Check if key exists
     pub async fn exists(&self, key: &str) -> Result<bool> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: i32 = redis::cmd("EXISTS")
             .arg(key)
             .query_async(&mut conn)
@@ -107,7 +212,7 @@ impl RedisClient {
 
     /// Set expiration pada key yang sudah ada
     pub async fn expire(&self, key: &str, ttl: i64) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("EXPIRE")
             .arg(key)
             .arg(ttl)
@@ -118,7 +223,7 @@ impl RedisClient {
 
     /// Get TTL dari key
     pub async fn ttl(&self, key: &str) -> Result<i64> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: i64 = redis::cmd("TTL")
             .arg(key)
             .query_async(&mut conn)
@@ -128,7 +233,7 @@ impl RedisClient {
 
     /// Increment value
     pub async fn incr(&self, key: &str) -> Result<i64> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: i64 = redis::cmd("INCR")
             .arg(key)
             .query_async(&mut conn)
@@ -138,7 +243,7 @@ impl RedisClient {
 
     /// Decrement value
     pub async fn decr(&self, key: &str) -> Result<i64> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: i64 = redis::cmd("DECR")
             .arg(key)
             .query_async(&mut conn)
@@ -148,7 +253,7 @@ impl RedisClient {
 
     /// Get multiple keys
     pub async fn mget(&self, keys: Vec<&str>) -> Result<Vec<Option<String>>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Vec<Option<String>> = redis::cmd("MGET")
             .arg(keys)
             .query_async(&mut conn)
@@ -158,7 +263,7 @@ impl RedisClient {
 
     /// Set multiple key-value pairs
     pub async fn mset(&self, pairs: Vec<(&str, &str)>) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let mut cmd = redis::cmd("MSET");
         for (key, value) in pairs {
             cmd.arg(key).arg(value);
@@ -169,7 +274,7 @@ impl RedisClient {
 
     /// Add to set
     pub async fn sadd(&self, key: &str, member: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("SADD")
             .arg(key)
             .arg(member)
@@ -180,7 +285,7 @@ impl RedisClient {
 
     /// Check if member exists in set
     pub async fn sismember(&self, key: &str, member: &str) -> Result<bool> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: i32 = redis::cmd("SISMEMBER")
             .arg(key)
             .arg(member)
@@ -191,7 +296,7 @@ impl RedisClient {
 
     /// Get all members of set
     pub async fn smembers(&self, key: &str) -> Result<Vec<String>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Vec<String> = redis::cmd("SMEMBERS")
             .arg(key)
             .query_async(&mut conn)
@@ -201,7 +306,7 @@ impl RedisClient {
 
     /// Remove from set
     pub async fn srem(&self, key: &str, member: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("SREM")
             .arg(key)
             .arg(member)
@@ -212,7 +317,7 @@ impl RedisClient {
 
     /// Push to list (left)
     pub async fn lpush(&self, key: &str, value: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("LPUSH")
             .arg(key)
             .arg(value)
@@ -223,7 +328,7 @@ impl RedisClient {
 
     /// Push to list (right)
     pub async fn rpush(&self, key: &str, value: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("RPUSH")
             .arg(key)
             .arg(value)
@@ -234,7 +339,7 @@ impl RedisClient {
 
     /// Get list range
     pub async fn lrange(&self, key: &str, start: i64, stop: i64) -> Result<Vec<String>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Vec<String> = redis::cmd("LRANGE")
             .arg(key)
             .arg(start)
@@ -246,7 +351,7 @@ impl RedisClient {
 
     /// Hash set field
     pub async fn hset(&self, key: &str, field: &str, value: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("HSET")
             .arg(key)
             .arg(field)
@@ -258,7 +363,7 @@ impl RedisClient {
 
     /// Hash get field
     pub async fn hget(&self, key: &str, field: &str) -> Result<Option<String>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Option<String> = redis::cmd("HGET")
             .arg(key)
             .arg(field)
@@ -269,7 +374,7 @@ impl RedisClient {
 
     /// Hash get all fields
     pub async fn hgetall(&self, key: &str) -> Result<Vec<(String, String)>> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let result: Vec<(String, String)> = redis::cmd("HGETALL")
             .arg(key)
             .query_async(&mut conn)
@@ -279,16 +384,39 @@ impl RedisClient {
 
     /// Flush database (clear all keys) - gunakan dengan hati-hati!
     pub async fn flush_db(&self) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         redis::cmd("FLUSHDB")
             .query_async::<()>(&mut conn)
             .await?;
         Ok(())
     }
 
+    /// Take one token from a bucket refilled continuously at `refill_per_sec`,
+    /// holding at most `capacity`. Returns whether a token was available and,
+    /// if not, how many milliseconds until one will be.
+    pub async fn take_token(
+        &self,
+        key: &str,
+        capacity: i64,
+        refill_per_sec: f64,
+    ) -> Result<(bool, u64)> {
+        let mut conn = self.conn()?;
+        let now_ms = chrono::Utc::now().timestamp_millis();
+
+        let (allowed, retry_after_ms): (i64, i64) = redis::Script::new(TOKEN_BUCKET_SCRIPT)
+            .key(key)
+            .arg(capacity)
+            .arg(refill_per_sec / 1000.0)
+            .arg(now_ms)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok((allowed == 1, retry_after_ms.max(0) as u64))
+    }
+
     /// Delete with prefix
      pub async fn del_prefix(&self, prefix: &str) -> Result<()> {
-        let mut conn = self.connection.clone();
+        let mut conn = self.conn()?;
         let mut cursor: u64 = 0;
 
         loop {
@@ -1,20 +1,36 @@
 use crate::{
     handlers::{
         auth_handler::AuthHandler,
+        author_handler::AuthorHandler,
         book_handler::BookHandler,
         bookmark_handler::BookmarkHandler,
         chapter_handler::ChapterHandler,
         genre_handler::GenreHandler,
-        health_handler::{db_health_check, health_checker_handler},
+        health_handler::{db_health_check, health_checker_handler, readiness_check},
+        notification_handler::NotificationHandler,
+        reading_progress_handler::ReadingProgressHandler,
+        review_handler::ReviewHandler,
+        robots_handler::robots_txt_handler,
+        series_handler::SeriesHandler,
+        stats_handler::StatsHandler,
+        tag_handler::TagHandler,
         upload_handler::UploadHandler,
+        user_handler::UserHandler,
     },
-    middleware::{api_key::api_key_middleware, auth::auth_middleware},
+    middleware::{
+        api_key::api_key_middleware,
+        auth::{auth_middleware, optional_auth_middleware},
+        bot_control::bot_control_middleware,
+        idempotency::idempotency_middleware,
+        rate_limit::rate_limit_middleware,
+    },
+    services::{auth_service::MAX_AVATAR_BYTES, book_service::MAX_COVER_BYTES},
     AppState,
 };
 use axum::{
     extract::DefaultBodyLimit,
     middleware as axum_middleware,
-    routing::{delete, get, post, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use tower_cookies::CookieManagerLayer;
@@ -23,8 +39,19 @@ use tower_http::cors::CorsLayer;
 pub fn create_routes(app_state: AppState, cors: CorsLayer) -> Router {
     Router::new()
         .nest("/api", api_routes(app_state.clone()))
+        .route("/livez", get(health_checker_handler))
+        .route("/readyz", get(readiness_check))
         .route("/healthy", get(health_checker_handler))
         .route("/db-health", get(db_health_check))
+        .route("/robots.txt", get(robots_txt_handler))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            rate_limit_middleware,
+        ))
+        .layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            bot_control_middleware,
+        ))
         .with_state(app_state)
         .layer(tower_http::trace::TraceLayer::new_for_http())
         .layer(CookieManagerLayer::new())
@@ -35,10 +62,15 @@ fn api_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
         .nest("/auth", auth_routes(app_state.clone()))
         .merge(genre_routes(app_state.clone()))
+        .merge(author_routes(app_state.clone()))
+        .merge(series_routes(app_state.clone()))
         .merge(book_routes(app_state.clone()))
         .merge(chapter_routes(app_state.clone()))
         .merge(bookmark_routes(app_state.clone()))
         .merge(upload_routes(app_state.clone()))
+        .merge(notification_routes(app_state.clone()))
+        .merge(user_routes(app_state.clone()))
+        .merge(admin_routes(app_state.clone()))
 }
 
 fn auth_routes(app_state: AppState) -> Router<AppState> {
@@ -52,7 +84,12 @@ fn auth_routes(app_state: AppState) -> Router<AppState> {
         .route("/logout", post(AuthHandler::logout))
         .route("/profile", put(AuthHandler::update_profile))
         .route("/password", put(AuthHandler::change_password))
-        .route("/avatar", post(AuthHandler::upload_avatar))
+        // Older path for POST /api/users/me/avatar
+        .route(
+            "/avatar",
+            post(AuthHandler::update_avatar)
+                .layer(DefaultBodyLimit::max(MAX_AVATAR_BYTES + 64 * 1024)),
+        )
         .route("/fcm-token", post(AuthHandler::save_fcm_token))
         .route_layer(axum_middleware::from_fn_with_state(
             app_state,
@@ -66,6 +103,8 @@ fn genre_routes(app_state: AppState) -> Router<AppState> {
     let public = Router::new()
         .route("/genres", get(GenreHandler::get_genres))
         .route("/genre/{id}", get(GenreHandler::get_genre))
+        .route("/genres/slug/{slug}", get(GenreHandler::get_genre_by_slug))
+        .route("/genres/{id}/books", get(GenreHandler::get_books_by_genre))
         .route_layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
             api_key_middleware,
@@ -85,21 +124,126 @@ fn genre_routes(app_state: AppState) -> Router<AppState> {
     public.merge(protected)
 }
 
+fn author_routes(app_state: AppState) -> Router<AppState> {
+    let public = Router::new()
+        .route("/authors", get(AuthorHandler::get_authors))
+        .route("/authors/{id}", get(AuthorHandler::get_author))
+        .route(
+            "/authors/{id}/books",
+            get(AuthorHandler::get_books_by_author),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            api_key_middleware,
+        ));
+
+    let protected = Router::new()
+        .route("/authors", post(AuthorHandler::create_author))
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state,
+            auth_middleware,
+        ));
+
+    public.merge(protected)
+}
+
+fn series_routes(app_state: AppState) -> Router<AppState> {
+    let public = Router::new()
+        .route("/series/{id}", get(SeriesHandler::get_series))
+        .route(
+            "/series/{id}/books",
+            get(SeriesHandler::get_books_in_series),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            api_key_middleware,
+        ));
+
+    let protected = Router::new()
+        .route("/series", post(SeriesHandler::create_series))
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state,
+            auth_middleware,
+        ));
+
+    public.merge(protected)
+}
+
 fn book_routes(app_state: AppState) -> Router<AppState> {
     let public = Router::new()
         .route("/books", get(BookHandler::get_books))
+        .route("/books/search", get(BookHandler::search_books))
+        .route("/books/popular", get(BookHandler::get_popular_books))
         .route("/book/{id}", get(BookHandler::get_book))
+        .route("/books/slug/{slug}", get(BookHandler::get_book_by_slug))
         .route("/book/{id}/genres", get(GenreHandler::get_genres_by_book))
+        .route("/book/{id}/tags", get(TagHandler::get_tags_by_book))
+        .route(
+            "/books/{id}/bookmark-count",
+            get(BookHandler::get_bookmark_count),
+        )
+        .route("/books/{id}/view", post(BookHandler::record_view))
+        .route(
+            "/books/{id}/reviews",
+            get(ReviewHandler::get_reviews_by_book),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state.clone(),
+            optional_auth_middleware,
+        ))
         .route_layer(axum_middleware::from_fn_with_state(
             app_state.clone(),
             api_key_middleware,
         ));
 
     let protected = Router::new()
-        .route("/book", post(BookHandler::create_book))
+        .route(
+            "/book",
+            post(BookHandler::create_book).layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                idempotency_middleware,
+            )),
+        )
+        .route(
+            "/books/bulk",
+            post(BookHandler::create_books).layer(DefaultBodyLimit::max(10 * 1024 * 1024)),
+        )
+        .route(
+            "/books/import",
+            post(BookHandler::import_books).layer(DefaultBodyLimit::max(50 * 1024 * 1024)),
+        )
         .route(
             "/book/{id}",
-            put(BookHandler::update_book).delete(BookHandler::delete_book),
+            put(BookHandler::replace_book)
+                .patch(BookHandler::update_book)
+                .delete(BookHandler::delete_book),
+        )
+        .route("/books/{id}", patch(BookHandler::update_book))
+        .route(
+            "/books/{id}/cover",
+            // Leave headroom for the multipart envelope around the image
+            post(BookHandler::upload_cover)
+                .layer(DefaultBodyLimit::max(MAX_COVER_BYTES + 64 * 1024)),
+        )
+        .route("/books/{id}/export", get(BookHandler::export_book))
+        .route("/books/{id}/genres", post(GenreHandler::add_genres_to_book))
+        .route(
+            "/books/{id}/genres/{genre_id}",
+            delete(GenreHandler::remove_genre_from_book),
+        )
+        .route("/books/{id}/tags", post(TagHandler::add_tags_to_book))
+        .route(
+            "/books/{id}/tags/{tag}",
+            delete(TagHandler::remove_tag_from_book),
+        )
+        .route(
+            "/books/{id}/progress",
+            get(ReadingProgressHandler::get_progress).put(ReadingProgressHandler::upsert_progress),
+        )
+        .route("/books/{id}/reviews", post(ReviewHandler::create_review))
+        .route(
+            "/reviews/{id}",
+            put(ReviewHandler::update_review).delete(ReviewHandler::delete_review),
         )
         .route_layer(axum_middleware::from_fn_with_state(
             app_state,
@@ -124,10 +268,18 @@ fn chapter_routes(app_state: AppState) -> Router<AppState> {
 
     let protected = Router::new()
         .route("/chapter", post(ChapterHandler::create_chapter))
+        .route(
+            "/chapters/from-upload",
+            post(ChapterHandler::create_chapter_from_upload),
+        )
         .route(
             "/chapter/{id}",
             put(ChapterHandler::update_chapter).delete(ChapterHandler::delete_chapter),
         )
+        .route(
+            "/books/{id}/chapters/reorder",
+            patch(ChapterHandler::reorder_chapters),
+        )
         .route_layer(axum_middleware::from_fn_with_state(
             app_state,
             auth_middleware,
@@ -137,20 +289,33 @@ fn chapter_routes(app_state: AppState) -> Router<AppState> {
 }
 
 fn upload_routes(app_state: AppState) -> Router<AppState> {
-    Router::new()
+    // Requested by <img> tags in chapter HTML, which carry neither API key nor token
+    let public = Router::new().route("/images/{*key}", get(UploadHandler::redirect_image));
+
+    let protected = Router::new()
         .route("/upload/content", post(UploadHandler::upload_content))
+        .route("/upload/presign", post(UploadHandler::presign_upload))
         .route("/upload/{id}", get(UploadHandler::get_upload))
         .route("/upload/{id}", delete(UploadHandler::delete_upload))
         .layer(DefaultBodyLimit::max(50 * 1024 * 1024)) // 50MB limit for file uploads
         .route_layer(axum_middleware::from_fn_with_state(
             app_state,
             auth_middleware,
-        ))
+        ));
+
+    public.merge(protected)
 }
 
 fn bookmark_routes(app_state: AppState) -> Router<AppState> {
     Router::new()
-        .route("/bookmark", post(BookmarkHandler::create_bookmark))
+        .route(
+            "/bookmark",
+            post(BookmarkHandler::create_bookmark).layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                idempotency_middleware,
+            )),
+        )
+        .route("/bookmark/toggle", post(BookmarkHandler::toggle_bookmark))
         .route("/bookmark/{id}", delete(BookmarkHandler::delete_bookmark))
         .route(
             "/bookmark/book/{book_id}",
@@ -166,3 +331,64 @@ fn bookmark_routes(app_state: AppState) -> Router<AppState> {
             auth_middleware,
         ))
 }
+
+fn notification_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route(
+            "/notifications",
+            get(NotificationHandler::get_notifications),
+        )
+        .route(
+            "/notifications/{id}/read",
+            patch(NotificationHandler::mark_read),
+        )
+        .route(
+            "/notifications/read-all",
+            post(NotificationHandler::mark_all_read),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state,
+            auth_middleware,
+        ))
+}
+
+fn user_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/users/me", delete(UserHandler::delete_account))
+        .route(
+            "/users/me/avatar",
+            // Leave headroom for the multipart envelope around the image
+            post(AuthHandler::update_avatar)
+                .layer(DefaultBodyLimit::max(MAX_AVATAR_BYTES + 64 * 1024)),
+        )
+        .route(
+            "/users/me/fcm-token",
+            put(AuthHandler::save_fcm_token).delete(AuthHandler::delete_fcm_token),
+        )
+        .route(
+            "/users/me/notifications",
+            put(NotificationHandler::update_preferences),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state,
+            auth_middleware,
+        ))
+}
+
+fn admin_routes(app_state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/admin/books", get(BookHandler::list_books_admin))
+        .route("/admin/genre-trends", get(GenreHandler::get_genre_trends))
+        .route("/admin/stats", get(StatsHandler::get_stats))
+        .route("/admin/users", get(UserHandler::list_users))
+        .route("/admin/users/search", get(UserHandler::search_users))
+        .route("/admin/users/{id}", delete(UserHandler::delete_user))
+        .route(
+            "/admin/users/{id}/role",
+            patch(UserHandler::update_user_role),
+        )
+        .route_layer(axum_middleware::from_fn_with_state(
+            app_state,
+            auth_middleware,
+        ))
+}
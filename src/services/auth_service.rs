@@ -3,10 +3,18 @@ use crate::errors::{AppError, AppResult};
 use crate::models::auth_model::{Auth, LoginDto, RegisterDto};
 use crate::models::user_model::Role;
 use crate::models::user_model::{SafeUser, User};
+use crate::services::content_extractor::ContentExtractor;
 use crate::services::storage_service::StorageService;
 use crate::utils;
 use crate::utils::jwt::JwtService;
 use chrono::Utc;
+use tracing::warn;
+
+/// Avatars are shown small; anything bigger is a wasted upload
+pub const MAX_AVATAR_BYTES: usize = 2 * 1024 * 1024;
+
+/// FCM registration tokens are ~160 chars today; leave headroom without accepting junk
+const MAX_FCM_TOKEN_LENGTH: usize = 4096;
 
 pub struct AuthService {
     db: Database,
@@ -25,11 +33,11 @@ impl AuthService {
 
     pub async fn register(&self, request: RegisterDto) -> AppResult<Auth> {
         if self.email_exists(&request.email).await? {
-            return Err(AppError::BadRequest("Email already exists".to_string()));
+            return Err(AppError::EmailExists);
         }
 
         if self.username_exists(&request.username).await? {
-            return Err(AppError::BadRequest("Username already exists".to_string()));
+            return Err(AppError::UsernameExists);
         }
 
         let hashed_password = utils::password::PasswordService::hash_password(&request.password)
@@ -40,7 +48,7 @@ impl AuthService {
             r#"
     INSERT INTO "User" (id, username, email, password, created_at, updated_at, role)
     VALUES ($1, $2, $3, $4, $5, $6, $7)
-    RETURNING id, username, email, role, bio, profile_pic
+    RETURNING id, username, email, role, bio, profile_pic, created_at, updated_at
     "#,
         )
         .bind(&user_id)
@@ -94,7 +102,7 @@ impl AuthService {
             self.jwt_service
                 .generate_refresh_token(&user.id, &user.email, user.role.clone())?;
 
-        Ok(Auth::new(user.into(), new_access_token, new_refresh_token))
+        Ok(Auth::new(user, new_access_token, new_refresh_token))
     }
 
     async fn email_exists(&self, email: &str) -> AppResult<bool> {
@@ -128,7 +136,8 @@ impl AuthService {
     async fn get_user_by_email(&self, email: &str) -> AppResult<User> {
         let user = sqlx::query_as::<_, User>(
             r#"
-            SELECT id, username, email, password, role, bio, profile_pic
+            SELECT id, username, email, password, role, bio, profile_pic, notify_new_chapters,
+                   created_at, updated_at
             FROM "User"
             WHERE email = $1
             "#,
@@ -151,15 +160,15 @@ impl AuthService {
 
         let user = sqlx::query_as::<_, SafeUser>(
             r#"
-            SELECT id, username, email, bio, profile_pic, role
+            SELECT id, username, email, bio, profile_pic, role, created_at, updated_at
             FROM "User"
             WHERE id = $1
             "#,
         )
         .bind(id)
-        .fetch_one(&self.db.pool)
-        .await
-        .map_err(|e| AppError::NotFound(e.to_string()))?;
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
         let _ = redis.set_json(&cache_key, &user, 600).await;
         Ok(user)
@@ -195,7 +204,7 @@ impl AuthService {
                 profile_pic = COALESCE($4, profile_pic),
                 updated_at = $5
             WHERE id = $1
-            RETURNING id, username, email, bio, profile_pic, role
+            RETURNING id, username, email, bio, profile_pic, role, created_at, updated_at
             "#,
         )
         .bind(user_id)
@@ -222,7 +231,12 @@ impl AuthService {
 
         // Get user with password
         let user = sqlx::query_as::<_, User>(
-            r#"SELECT id, username, email, password, role, bio, profile_pic FROM "User" WHERE id = $1"#
+            r#"
+            SELECT id, username, email, password, role, bio, profile_pic, notify_new_chapters,
+                   created_at, updated_at
+            FROM "User"
+            WHERE id = $1
+            "#,
         )
         .bind(user_id)
         .fetch_one(&self.db.pool)
@@ -254,44 +268,88 @@ impl AuthService {
         Ok(())
     }
 
-    pub async fn upload_avatar(
-        &self,
-        user_id: &str,
-        bytes: Vec<u8>,
-        content_type: &str,
-    ) -> AppResult<String> {
+    /// Store a new avatar, point `profile_pic` at it and drop the previous object
+    pub async fn upload_avatar(&self, user_id: &str, bytes: Vec<u8>) -> AppResult<SafeUser> {
         let redis = &self.db.redis;
 
-        // Generate unique filename
-        let extension = match content_type {
-            "image/png" => "png",
-            "image/gif" => "gif",
-            "image/webp" => "webp",
-            _ => "jpg",
+        if bytes.len() > MAX_AVATAR_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Avatar must be at most {} bytes",
+                MAX_AVATAR_BYTES
+            )));
+        }
+
+        // Trust the bytes rather than the client-supplied content type
+        let (content_type, extension) = match ContentExtractor::sniff_image_type(&bytes) {
+            Some("image/jpeg") => ("image/jpeg", "jpg"),
+            Some("image/png") => ("image/png", "png"),
+            Some("image/gif") => ("image/gif", "gif"),
+            Some("image/webp") => ("image/webp", "webp"),
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Avatar must be a JPEG, PNG, GIF or WebP image".to_string(),
+                ))
+            }
         };
-        let filename = format!("avatars/{}/{}.{}", user_id, cuid2::create_id(), extension);
 
-        // Upload to R2 via storage service
+        let previous: Option<String> =
+            sqlx::query_scalar(r#"SELECT profile_pic FROM "User" WHERE id = $1"#)
+                .bind(user_id)
+                .fetch_optional(&self.db.pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let filename = format!("{}.{}", cuid2::create_id(), extension);
         let url = self
             .storage
-            .upload_bytes(&filename, bytes, content_type)
+            .upload_image("avatars", user_id, &filename, bytes, content_type)
             .await?;
 
-        // Update user profile_pic in database
-        sqlx::query(r#"UPDATE "User" SET profile_pic = $2, updated_at = $3 WHERE id = $1"#)
-            .bind(user_id)
-            .bind(&url)
-            .bind(Utc::now())
-            .execute(&self.db.pool)
-            .await?;
+        let user = sqlx::query_as::<_, SafeUser>(
+            r#"
+            UPDATE "User"
+            SET profile_pic = $2, updated_at = $3
+            WHERE id = $1
+            RETURNING id, username, email, bio, profile_pic, role, created_at, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&url)
+        .bind(Utc::now())
+        .fetch_one(&self.db.pool)
+        .await?;
 
-        // Clear cache
         let _ = redis.del(&format!("user:{user_id}")).await;
 
-        Ok(url)
+        // Only remove objects we own; external profile_pic URLs are left alone
+        let avatar_prefix = format!("avatars/{}/", user_id);
+        if let Some(key) = previous
+            .as_deref()
+            .and_then(|old| self.storage.key_from_public_url(old))
+            .filter(|key| key.starts_with(&avatar_prefix))
+        {
+            if let Err(e) = self.storage.delete_file(&key).await {
+                warn!(error = ?e, key = %key, "Failed to delete previous avatar");
+            }
+        }
+
+        Ok(user)
     }
 
     pub async fn save_fcm_token(&self, user_id: &str, fcm_token: &str) -> AppResult<()> {
+        let fcm_token = fcm_token.trim();
+        if fcm_token.is_empty() {
+            return Err(AppError::Validation(
+                "FCM token must not be empty".to_string(),
+            ));
+        }
+        if fcm_token.len() > MAX_FCM_TOKEN_LENGTH {
+            return Err(AppError::Validation(format!(
+                "FCM token must be at most {} characters",
+                MAX_FCM_TOKEN_LENGTH
+            )));
+        }
+
         sqlx::query(r#"UPDATE "User" SET fcm_token = $2, updated_at = $3 WHERE id = $1"#)
             .bind(user_id)
             .bind(fcm_token)
@@ -301,4 +359,14 @@ impl AuthService {
 
         Ok(())
     }
+
+    pub async fn clear_fcm_token(&self, user_id: &str) -> AppResult<()> {
+        sqlx::query(r#"UPDATE "User" SET fcm_token = NULL, updated_at = $2 WHERE id = $1"#)
+            .bind(user_id)
+            .bind(Utc::now())
+            .execute(&self.db.pool)
+            .await?;
+
+        Ok(())
+    }
 }
@@ -0,0 +1,204 @@
+use crate::database::Database;
+use crate::errors::{AppError, AppResult};
+use crate::models::author_model::{
+    Author, AuthorDto, AuthorQuery, AuthorWithBookCount, CreateAuthorDto,
+};
+use crate::models::book_model::{Book, BookDto};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use chrono::Utc;
+use sqlx::PgConnection;
+
+pub struct AuthorService {
+    db: Database,
+}
+
+impl AuthorService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Resolve a book's author to `(id, name)`: by id when given, otherwise by
+    /// case-insensitive name, creating the author on first use
+    pub async fn resolve(
+        conn: &mut PgConnection,
+        author_id: Option<&str>,
+        name: &str,
+    ) -> AppResult<Author> {
+        if let Some(author_id) = author_id {
+            return sqlx::query_as::<_, Author>(
+                r#"SELECT id, name, created_at, updated_at FROM "Author" WHERE id = $1"#,
+            )
+            .bind(author_id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::BadRequest("Author not found".to_string()));
+        }
+
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::BadRequest(
+                "either author or author_id is required".to_string(),
+            ));
+        }
+
+        // The no-op update makes RETURNING yield the existing row on conflict
+        let author = sqlx::query_as::<_, Author>(
+            r#"
+            INSERT INTO "Author" (id, name, created_at, updated_at)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT ((LOWER(name))) DO UPDATE SET name = "Author".name
+            RETURNING id, name, created_at, updated_at
+            "#,
+        )
+        .bind(cuid2::create_id())
+        .bind(name)
+        .bind(Utc::now())
+        .fetch_one(&mut *conn)
+        .await?;
+
+        Ok(author)
+    }
+
+    pub async fn get_authors(
+        &self,
+        params: AuthorQuery,
+    ) -> AppResult<PaginatedResponse<AuthorDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+        let search = params.search.as_deref().map(str::trim).unwrap_or("");
+        let cache_key = format!(
+            "author:list:page:{}:size:{}:search:{}",
+            params.page, params.page_size, search
+        );
+
+        if let Ok(Some(cached)) = redis
+            .get_json::<PaginatedResponse<AuthorDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached);
+        }
+
+        let search_pattern = (!search.is_empty()).then(|| format!("%{}%", search));
+
+        let total_items = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM "Author" WHERE $1::TEXT IS NULL OR name ILIKE $1"#,
+        )
+        .bind(&search_pattern)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        let authors = sqlx::query_as::<_, AuthorWithBookCount>(
+            r#"
+            SELECT a.id, a.name, a.created_at, a.updated_at,
+                   COUNT(b.id) AS book_count
+            FROM "Author" a
+            LEFT JOIN "Book" b ON b.author_id = a.id
+            WHERE $1::TEXT IS NULL OR a.name ILIKE $1
+            GROUP BY a.id
+            ORDER BY a.name ASC, a.id ASC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&search_pattern)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<AuthorDto> = authors.into_iter().map(Into::into).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
+
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
+    }
+
+    pub async fn get_author(&self, id: String) -> AppResult<AuthorDto> {
+        let author = sqlx::query_as::<_, Author>(
+            r#"SELECT id, name, created_at, updated_at FROM "Author" WHERE id = $1"#,
+        )
+        .bind(&id)
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Author not found".to_string()))?;
+
+        Ok(author.into())
+    }
+
+    pub async fn create_author(&self, request: CreateAuthorDto) -> AppResult<AuthorDto> {
+        let author = sqlx::query_as::<_, Author>(
+            r#"
+            INSERT INTO "Author" (id, name, created_at, updated_at)
+            VALUES ($1, $2, $3, $3)
+            RETURNING id, name, created_at, updated_at
+            "#,
+        )
+        .bind(cuid2::create_id())
+        .bind(request.name.trim())
+        .bind(Utc::now())
+        .fetch_one(&self.db.pool)
+        .await
+        .map_err(|e| match AppError::from(e) {
+            AppError::Conflict(_) => AppError::Conflict("Author already exists".to_string()),
+            e => e,
+        })?;
+
+        let _ = self.db.redis.del_prefix("author:list").await;
+
+        Ok(author.into())
+    }
+
+    pub async fn get_books_by_author(
+        &self,
+        author_id: String,
+        params: PaginationParams,
+    ) -> AppResult<PaginatedResponse<BookDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+        let cache_key = format!(
+            "books:list:author:{}:page:{}:size:{}",
+            author_id, params.page, params.page_size
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<PaginatedResponse<BookDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
+        }
+
+        // Distinguish an unknown author from one without books
+        self.get_author(author_id.clone()).await?;
+
+        let total_items =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Book" WHERE author_id = $1"#)
+                .bind(&author_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        let books = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT id, title, slug, author, author_id, cover, description, asset,
+                   series_id, series_position,
+                   status, language, release_date, popular, views, version,
+                   created_at, updated_at
+            FROM "Book"
+            WHERE author_id = $1
+            ORDER BY created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&author_id)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
+
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
+    }
+}
@@ -1,66 +1,354 @@
 use crate::database::Database;
-use crate::errors::AppResult;
-use crate::models::book_model::{Book, BookDto, CreateBookDto, UpdateBookDto};
-use crate::models::paging_model::{PaginatedResponse, PaginationParams};
-use chrono::Utc;
+use crate::errors::{map_still_referenced, AppError, AppResult};
+use crate::models::book_model::{
+    AdminBookQuery, Book, BookCsvRow, BookCursor, BookDto, BookExportHeader, BookImportError, BookImportSummary,
+    BookQuery, BookSearchParams, BookSort, CreateBookDto, Language, ReplaceBookDto, Status, UpdateBookDto,
+    BOOK_EXPORT_SCHEMA_VERSION,
+};
+use crate::models::chapter_model::{Chapter, ChapterDto};
+use crate::models::paging_model::{
+    page_offset, CursorMeta, CursorPaginatedResponse, PaginatedResponse, PaginationParams,
+};
+use crate::models::tag_model::parse_tags;
+use crate::services::author_service::AuthorService;
+use crate::services::genre_service::GenreService;
+use crate::services::content_extractor::ContentExtractor;
+use crate::services::review_service::ReviewService;
+use crate::services::series_service::SeriesService;
+use crate::services::storage_service::StorageService;
+use crate::services::tag_service::TagService;
+use crate::utils::channel_reader::ChannelReader;
+use crate::utils::field_limits::{FieldLimits, LengthLimited};
+use crate::utils::slug::{map_slug_conflict, unique_slug};
+use axum::body::{Body, Bytes};
+use chrono::{NaiveDate, NaiveDateTime, Utc};
 use cuid2;
-use sqlx::QueryBuilder;
+use futures_util::{stream, StreamExt};
+use sqlx::{Acquire, PgConnection, Postgres, QueryBuilder};
+use tokio::sync::mpsc;
+use tracing::warn;
+use validator::Validate;
+
+pub const MAX_COVER_BYTES: usize = 5 * 1024 * 1024;
+pub const MAX_BULK_BOOKS: usize = 500;
+
+/// Repeat views of a book by the same viewer within this window count once
+const VIEW_DEBOUNCE_SECS: i64 = 30 * 60;
+
+/// A reader active within the popularity window counts this many bookmarks
+const RECENT_READER_WEIGHT: i64 = 2;
+
+/// Optional filters for the book listing, applied identically to the count and page queries
+struct BookFilters<'a> {
+    search_pattern: Option<&'a str>,
+    genres: &'a [String],
+    /// Books must carry every one of these tags
+    tags: &'a [String],
+    status: Option<&'a Status>,
+    language: Option<&'a Language>,
+    popular: Option<bool>,
+    released_after: Option<NaiveDate>,
+    released_before: Option<NaiveDate>,
+    created_after: Option<NaiveDateTime>,
+    created_before: Option<NaiveDateTime>,
+    after: Option<&'a BookCursor>,
+}
+
+impl BookFilters<'_> {
+    fn push_where(&self, builder: &mut QueryBuilder<'_, Postgres>) {
+        let mut has_condition = false;
+        let mut push_condition = |builder: &mut QueryBuilder<'_, Postgres>| {
+            builder.push(if has_condition { " AND " } else { " WHERE " });
+            has_condition = true;
+        };
+
+        if let Some(pattern) = self.search_pattern {
+            push_condition(builder);
+            builder.push("title ILIKE ").push_bind(pattern.to_string());
+        }
+
+        if !self.genres.is_empty() {
+            push_condition(builder);
+            builder.push(
+                r#"EXISTS (
+                SELECT 1 FROM "BookGenre" bg
+                INNER JOIN "Genre" g ON bg.genre_id = g.id
+                WHERE bg.book_id = "Book".id
+                AND LOWER(g.title) IN ("#,
+            );
+            let mut separated = builder.separated(", ");
+            for genre in self.genres {
+                separated.push_bind(genre.clone());
+            }
+            builder.push("))");
+        }
+
+        if !self.tags.is_empty() {
+            push_condition(builder);
+            builder.push(
+                r#"(
+                SELECT COUNT(*) FROM "BookTag" bt
+                INNER JOIN "Tag" t ON bt.tag_id = t.id
+                WHERE bt.book_id = "Book".id
+                AND t.name = ANY("#,
+            );
+            builder
+                .push_bind(self.tags.to_vec())
+                .push(")) = ")
+                .push_bind(self.tags.len() as i64);
+        }
+
+        if let Some(status) = self.status {
+            push_condition(builder);
+            builder.push("status = ").push_bind(status.clone());
+        }
+
+        if let Some(language) = self.language {
+            push_condition(builder);
+            builder.push("language = ").push_bind(language.clone());
+        }
+
+        if let Some(popular) = self.popular {
+            push_condition(builder);
+            builder.push("popular = ").push_bind(popular);
+        }
+
+        if let Some(date) = self.released_after {
+            push_condition(builder);
+            builder.push("release_date >= ").push_bind(date);
+        }
+
+        if let Some(date) = self.released_before {
+            push_condition(builder);
+            builder.push("release_date <= ").push_bind(date);
+        }
+
+        if let Some(created_at) = self.created_after {
+            push_condition(builder);
+            builder.push("created_at >= ").push_bind(created_at);
+        }
+
+        if let Some(created_at) = self.created_before {
+            push_condition(builder);
+            builder.push("created_at <= ").push_bind(created_at);
+        }
+
+        if let Some(cursor) = self.after {
+            push_condition(builder);
+            builder
+                .push("(created_at, id) < (")
+                .push_bind(cursor.created_at)
+                .push(", ")
+                .push_bind(cursor.id.clone())
+                .push(")");
+        }
+    }
+}
+
+/// A parsed CSV record, or why it could not be turned into a `CreateBookDto`
+type CsvRecord = (u64, Result<CreateBookDto, String>);
+
+/// Parse a book CSV on a blocking thread, sending each record as soon as it is read.
+/// Only problems that make the whole file unreadable are returned as errors.
+fn parse_book_csv(reader: ChannelReader, rows: mpsc::Sender<CsvRecord>) -> AppResult<()> {
+    // The csv reader strips a leading UTF-8 BOM and handles quoted fields
+    let mut csv = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .flexible(true)
+        .from_reader(reader);
+
+    let headers = csv
+        .headers()
+        .map_err(|e| AppError::BadRequest(format!("Failed to read CSV headers: {}", e)))?
+        .clone();
+    for required in ["title", "author"] {
+        if !headers.iter().any(|h| h == required) {
+            return Err(AppError::BadRequest(format!(
+                "CSV is missing the required '{}' column",
+                required
+            )));
+        }
+    }
+
+    let mut record = csv::StringRecord::new();
+    loop {
+        let parsed = match csv.read_record(&mut record) {
+            Ok(false) => return Ok(()),
+            Ok(true) => {
+                let row = record.position().map(|p| p.line()).unwrap_or_default();
+                let dto = record
+                    .deserialize::<BookCsvRow>(Some(&headers))
+                    .map(CreateBookDto::from)
+                    .map_err(|e| e.to_string());
+                (row, dto)
+            }
+            Err(e) => match e.position() {
+                // A malformed record (e.g. invalid UTF-8) only costs that row
+                Some(position) => (position.line(), Err(e.to_string())),
+                None => {
+                    return Err(AppError::BadRequest(format!("Failed to read CSV: {}", e)))
+                }
+            },
+        };
+
+        if rows.blocking_send(parsed).is_err() {
+            // The importer gave up; no point reading the rest
+            return Ok(());
+        }
+    }
+}
+
+/// Slug races and taken series positions both surface as unique violations
+fn map_book_conflict(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_err)
+            if db_err.constraint() == Some("uq_book_series_position") =>
+        {
+            AppError::Conflict("Another book already holds that series position".to_string())
+        }
+        e => map_slug_conflict(e),
+    }
+}
+
+fn stale_book_version() -> AppError {
+    AppError::Conflict("Book was modified by someone else; reload it and retry".to_string())
+}
+
+fn parse_genres(genres: Option<&str>) -> Vec<String> {
+    genres
+        .map(|g| {
+            g.split(',')
+                .map(|s| s.trim().to_lowercase())
+                .filter(|s| !s.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
 
 pub struct BookService {
     db: Database,
+    storage: StorageService,
 }
 
 impl BookService {
-    pub fn new(db: Database) -> Self {
-        Self { db }
+    pub fn new(db: Database, storage: StorageService) -> Self {
+        Self { db, storage }
     }
 
-    pub async fn create_book(&self, request: CreateBookDto) -> AppResult<BookDto> {
+    /// Insert one book on `conn`, so single and bulk creates share slug handling
+    async fn insert_book(conn: &mut PgConnection, request: &CreateBookDto) -> AppResult<Book> {
+        request.check_series()?;
+        let slug = unique_slug(&mut *conn, "Book", &request.title, "book", None).await?;
+        let author =
+            AuthorService::resolve(&mut *conn, request.author_id.as_deref(), &request.author)
+                .await?;
+
         let book = sqlx::query_as::<_, Book>(
             r#"
             INSERT INTO "Book" (
-                id, title, author, cover, description, asset,
+                id, title, slug, author, author_id, cover, description, asset,
+                series_id, series_position,
                 status, language, release_date, popular,
                 created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
-            RETURNING id, title, author, cover, description, asset,
-                      status, language, release_date, popular,
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+            RETURNING id, title, slug, author, author_id, cover, description, asset,
+                      series_id, series_position,
+                      status, language, release_date, popular, views, version,
                       created_at, updated_at
             "#,
         )
         .bind(cuid2::create_id())
         .bind(&request.title)
-        .bind(&request.author)
+        .bind(&slug)
+        .bind(&author.name)
+        .bind(&author.id)
         .bind(&request.cover)
         .bind(&request.description)
         .bind(&request.asset)
-        .bind(request.status)
+        .bind(&request.series_id)
+        .bind(request.series_position)
+        .bind(request.status.clone())
         .bind(&request.language)
-        .bind(&request.release_date)
+        .bind(request.release_date)
         .bind(request.popular)
         .bind(Utc::now())
         .bind(Utc::now())
-        .fetch_one(&self.db.pool)
-        .await?;
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(map_book_conflict)?;
+
+        Ok(book)
+    }
+
+    pub async fn create_book(&self, request: CreateBookDto) -> AppResult<BookDto> {
+        let mut conn = self.db.pool.acquire().await?;
+        let book = Self::insert_book(&mut conn, &request).await?;
+
+        let redis = &self.db.redis;
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+        let _ = redis.del_prefix("author:list").await;
+        if let Some(ref series_id) = book.series_id {
+            SeriesService::new(self.db.clone())
+                .invalidate(series_id)
+                .await;
+        }
 
         Ok(book.into())
     }
 
-    pub async fn get_books(
-        &self,
-        params: PaginationParams,
-    ) -> AppResult<PaginatedResponse<BookDto>> {
-        let offset = (params.page - 1) * params.page_size;
+    /// Insert every book in one transaction; any failure rolls back the whole batch
+    pub async fn create_books(&self, requests: Vec<CreateBookDto>) -> AppResult<Vec<BookDto>> {
+        let redis = &self.db.redis;
+        let mut tx = self.db.pool.begin().await?;
+        let mut books = Vec::with_capacity(requests.len());
+
+        for (index, request) in requests.iter().enumerate() {
+            let book = Self::insert_book(&mut tx, request)
+                .await
+                .map_err(|e| match e {
+                    AppError::Conflict(_) => AppError::InvalidBatchItem {
+                        index,
+                        source: Box::new(e),
+                    },
+                    e => e,
+                })?;
+            books.push(book.into());
+        }
+
+        tx.commit().await?;
+
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+        let _ = redis.del_prefix("author:list").await;
+        let _ = redis.del_prefix("series:").await;
+
+        Ok(books)
+    }
+
+    pub async fn get_books(&self, params: BookQuery) -> AppResult<PaginatedResponse<BookDto>> {
+        let sort = match params.sort.as_deref() {
+            Some(sort) => sort.parse::<BookSort>()?,
+            None => BookSort::default(),
+        };
+        let offset = page_offset(params.page, params.page_size)?;
+        let tag_list = parse_tags(params.tags.as_deref());
         let redis = &self.db.redis;
 
         let cache_key = format!(
-            "books:list:page:{}:size:{}:search:{}:genres:{}:sort:{}",
+            "books:list:page:{}:size:{}:search:{}:genres:{}:tags:{}:status:{:?}:language:{:?}:popular:{:?}:released:{:?}..{:?}:sort:{}",
             params.page,
             params.page_size,
             params.search.as_deref().unwrap_or(""),
             params.genres.as_deref().unwrap_or(""),
-            params.sort.as_deref().unwrap_or("newest")
+            tag_list.join(","),
+            params.status,
+            params.language,
+            params.popular,
+            params.released_after,
+            params.released_before,
+            sort.as_str()
         );
 
         if let Ok(Some(cached_response)) = redis
@@ -70,118 +358,348 @@ impl BookService {
             return Ok(cached_response);
         }
 
-        let mut where_conditions = Vec::new();
-        let mut bind_index = 1;
-
         let search_pattern = params.search.as_ref().map(|s| format!("%{}%", s));
+        let genre_list = parse_genres(params.genres.as_deref());
 
-        if search_pattern.is_some() {
-            where_conditions.push(format!("title ILIKE ${}", bind_index));
-            bind_index += 1;
+        if !genre_list.is_empty() {
+            tracing::info!("Filtering by genres: {:?}", genre_list);
         }
 
-        let genre_list: Option<Vec<String>> = params.genres.as_ref().map(|g| {
-            g.split(',')
-                .map(|s| s.trim().to_lowercase())
-                .filter(|s| !s.is_empty())
-                .collect()
-        });
+        let filters = BookFilters {
+            search_pattern: search_pattern.as_deref(),
+            genres: &genre_list,
+            tags: &tag_list,
+            status: params.status.as_ref(),
+            language: params.language.as_ref(),
+            popular: params.popular,
+            released_after: params.released_after,
+            released_before: params.released_before,
+            created_after: None,
+            created_before: None,
+            after: None,
+        };
 
-        let genre_count = genre_list.as_ref().map(|g| g.len()).unwrap_or(0);
+        let mut count_builder = QueryBuilder::<Postgres>::new(r#"SELECT COUNT(*) FROM "Book""#);
+        filters.push_where(&mut count_builder);
+        let total_items = count_builder
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.db.pool)
+            .await?;
 
-        if genre_count > 0 {
-            tracing::info!("Filtering by genres: {:?}", genre_list);
+        let mut fetch_builder = QueryBuilder::<Postgres>::new(
+            r#"
+        SELECT id, title, slug, author, author_id, cover, description, asset,
+               series_id, series_position,
+               status, language, release_date, popular, views, version,
+               created_at, updated_at
+        FROM "Book""#,
+        );
+        filters.push_where(&mut fetch_builder);
+        fetch_builder.push(sort.order_by());
+        fetch_builder.push(" LIMIT ").push_bind(params.page_size);
+        fetch_builder.push(" OFFSET ").push_bind(offset);
 
-            let genre_placeholders: Vec<String> = (0..genre_count)
-                .map(|i| format!("${}", bind_index + i))
-                .collect();
+        let books = fetch_builder
+            .build_query_as::<Book>()
+            .fetch_all(&self.db.pool)
+            .await?;
 
-            where_conditions.push(format!(
-                r#"EXISTS (
-                SELECT 1 FROM "BookGenre" bg
-                INNER JOIN "Genre" g ON bg.genre_id = g.id
-                WHERE bg.book_id = "Book".id
-                AND LOWER(g.title) IN ({})
-            )"#,
-                genre_placeholders.join(", ")
-            ));
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
-            bind_index += genre_count;
-        }
+        let _ = redis.set_json(&cache_key, &response, 600).await;
 
-        let where_clause = if where_conditions.is_empty() {
-            String::new()
-        } else {
-            format!("WHERE {}", where_conditions.join(" AND "))
+        Ok(response)
+    }
+
+    /// Uncached listing for admin reports, newest first
+    pub async fn list_books_for_admin(
+        &self,
+        params: AdminBookQuery,
+    ) -> AppResult<PaginatedResponse<BookDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let filters = BookFilters {
+            search_pattern: None,
+            genres: &[],
+            tags: &[],
+            status: None,
+            language: None,
+            popular: None,
+            released_after: None,
+            released_before: None,
+            created_after: params.created_after.map(|at| at.naive_utc()),
+            created_before: params.created_before.map(|at| at.naive_utc()),
+            after: None,
         };
 
-        tracing::info!("Generated WHERE clause: {}", where_clause);
+        let mut count_builder = QueryBuilder::<Postgres>::new(r#"SELECT COUNT(*) FROM "Book""#);
+        filters.push_where(&mut count_builder);
+        let total_items = count_builder
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.db.pool)
+            .await?;
+
+        let mut fetch_builder = QueryBuilder::<Postgres>::new(
+            r#"
+        SELECT id, title, slug, author, author_id, cover, description, asset,
+               series_id, series_position,
+               status, language, release_date, popular, views, version,
+               created_at, updated_at
+        FROM "Book""#,
+        );
+        filters.push_where(&mut fetch_builder);
+        fetch_builder.push(" ORDER BY created_at DESC, id DESC");
+        fetch_builder.push(" LIMIT ").push_bind(params.page_size);
+        fetch_builder.push(" OFFSET ").push_bind(offset);
 
-        let count_query = format!(r#"SELECT COUNT(*) FROM "Book" {}"#, where_clause);
-        let mut count_query_builder = sqlx::query_scalar::<_, i64>(&count_query);
+        let books = fetch_builder
+            .build_query_as::<Book>()
+            .fetch_all(&self.db.pool)
+            .await?;
 
-        if let Some(ref pattern) = search_pattern {
-            count_query_builder = count_query_builder.bind(pattern);
-        }
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        Ok(PaginatedResponse::new(
+            data,
+            params.page,
+            params.page_size,
+            total_items,
+        ))
+    }
 
-        if let Some(ref genres) = genre_list {
-            for genre in genres {
-                count_query_builder = count_query_builder.bind(genre);
+    pub async fn get_books_by_cursor(
+        &self,
+        params: BookQuery,
+    ) -> AppResult<CursorPaginatedResponse<BookDto>> {
+        if let Some(sort) = params.sort.as_deref() {
+            if sort.parse::<BookSort>()? != BookSort::Newest {
+                return Err(AppError::BadRequest(
+                    "Cursor pagination only supports sort=newest".to_string(),
+                ));
             }
         }
 
-        let total_items = count_query_builder.fetch_one(&self.db.pool).await?;
+        let limit = params.cursor_limit();
+        let after = params
+            .cursor
+            .as_deref()
+            .map(BookCursor::decode)
+            .transpose()?;
+        let tag_list = parse_tags(params.tags.as_deref());
+        let redis = &self.db.redis;
+
+        let cache_key = format!(
+            "books:list:cursor:{}:limit:{}:search:{}:genres:{}:tags:{}:status:{:?}:language:{:?}:popular:{:?}:released:{:?}..{:?}",
+            params.cursor.as_deref().unwrap_or(""),
+            limit,
+            params.search.as_deref().unwrap_or(""),
+            params.genres.as_deref().unwrap_or(""),
+            tag_list.join(","),
+            params.status,
+            params.language,
+            params.popular,
+            params.released_after,
+            params.released_before
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<CursorPaginatedResponse<BookDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
+        }
+
+        let search_pattern = params.search.as_ref().map(|s| format!("%{}%", s));
+        let genre_list = parse_genres(params.genres.as_deref());
 
-        // Determine ORDER BY clause based on sort parameter
-        let order_by = match params.sort.as_deref() {
-            Some("oldest") => "ORDER BY created_at ASC",
-            Some("popular") => "ORDER BY popular DESC, created_at DESC",
-            Some("alphabetical") => "ORDER BY title ASC",
-            _ => "ORDER BY created_at DESC", // default: newest
+        let filters = BookFilters {
+            search_pattern: search_pattern.as_deref(),
+            genres: &genre_list,
+            tags: &tag_list,
+            status: params.status.as_ref(),
+            language: params.language.as_ref(),
+            popular: params.popular,
+            released_after: params.released_after,
+            released_before: params.released_before,
+            created_after: None,
+            created_before: None,
+            after: after.as_ref(),
         };
 
-        let fetch_query = format!(
+        let mut fetch_builder = QueryBuilder::<Postgres>::new(
             r#"
-        SELECT id, title, author, cover, description, asset,
-               status, language, release_date, popular,
+        SELECT id, title, slug, author, author_id, cover, description, asset,
+               series_id, series_position,
+               status, language, release_date, popular, views, version,
                created_at, updated_at
-        FROM "Book"
-        {}
-        {}
-        LIMIT ${} OFFSET ${}
-        "#,
-            where_clause,
-            order_by,
-            bind_index,
-            bind_index + 1
+        FROM "Book""#,
         );
+        filters.push_where(&mut fetch_builder);
+        fetch_builder.push(" ORDER BY created_at DESC, id DESC");
+        // One extra row tells us whether another page exists
+        fetch_builder.push(" LIMIT ").push_bind(limit + 1);
+
+        let mut books = fetch_builder
+            .build_query_as::<Book>()
+            .fetch_all(&self.db.pool)
+            .await?;
 
-        let mut fetch_query_builder = sqlx::query_as::<_, Book>(&fetch_query);
+        let has_more = books.len() as i64 > limit;
+        books.truncate(limit as usize);
 
-        if let Some(ref pattern) = search_pattern {
-            fetch_query_builder = fetch_query_builder.bind(pattern);
-        }
+        let next_cursor = if has_more {
+            books.last().map(|book| {
+                BookCursor {
+                    created_at: book.created_at,
+                    id: book.id.clone(),
+                }
+                .encode()
+            })
+        } else {
+            None
+        };
 
-        if let Some(ref genres) = genre_list {
-            for genre in genres {
-                fetch_query_builder = fetch_query_builder.bind(genre);
-            }
+        let response = CursorPaginatedResponse {
+            data: books.into_iter().map(BookDto::from).collect(),
+            meta: CursorMeta { limit, next_cursor },
+        };
+
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
+    }
+
+    /// Rank books by bookmarks plus readers active within the last `window_days`,
+    /// falling back to the editorial `popular` flag and recency on ties
+    pub async fn get_popular_books(
+        &self,
+        params: PaginationParams,
+        window_days: i64,
+    ) -> AppResult<PaginatedResponse<BookDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+
+        let cache_key = format!(
+            "books:list:popular:window:{}:page:{}:size:{}",
+            window_days, params.page, params.page_size
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<PaginatedResponse<BookDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
         }
 
-        fetch_query_builder = fetch_query_builder.bind(params.page_size).bind(offset);
+        let total_items = sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Book""#)
+            .fetch_one(&self.db.pool)
+            .await?;
+
+        let since = Utc::now().naive_utc() - chrono::Duration::days(window_days);
 
-        let books = fetch_query_builder.fetch_all(&self.db.pool).await?;
+        let books = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT b.id, b.title, b.slug, b.author, b.author_id, b.cover, b.description, b.asset,
+                   b.series_id, b.series_position,
+                   b.status, b.language, b.release_date, b.popular, b.views, b.version,
+                   b.created_at, b.updated_at
+            FROM "Book" b
+            LEFT JOIN (
+                SELECT book_id, COUNT(*) AS bookmarks
+                FROM "Bookmark"
+                GROUP BY book_id
+            ) bm ON bm.book_id = b.id
+            LEFT JOIN (
+                SELECT book_id, COUNT(*) AS readers
+                FROM "ReadingProgress"
+                WHERE updated_at >= $1
+                GROUP BY book_id
+            ) rp ON rp.book_id = b.id
+            ORDER BY COALESCE(bm.bookmarks, 0) + $2 * COALESCE(rp.readers, 0) DESC,
+                     b.popular DESC,
+                     b.created_at DESC,
+                     b.id DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(since)
+        .bind(RECENT_READER_WEIGHT)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
 
         let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
-        let total_pages = (total_items as f64 / params.page_size as f64).ceil() as i64;
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
-        let response = PaginatedResponse {
-            data,
-            page: params.page,
-            page_size: params.page_size,
-            total_items,
-            total_pages,
-        };
+        // Rankings drift slowly, so a short TTL is enough
+        let _ = redis.set_json(&cache_key, &response, 300).await;
+
+        Ok(response)
+    }
+
+    pub async fn search_books(
+        &self,
+        params: BookSearchParams,
+    ) -> AppResult<PaginatedResponse<BookDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+        let query = params.q.as_deref().map(str::trim).unwrap_or("");
+
+        let cache_key = format!(
+            "books:search:q:{}:page:{}:size:{}",
+            query.to_lowercase(),
+            params.page,
+            params.page_size
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<PaginatedResponse<BookDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
+        }
+
+        // Escape LIKE wildcards so user input is matched literally; empty query matches all
+        let escaped = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = format!("%{}%", escaped);
+
+        let total_items = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM "Book"
+            WHERE title ILIKE $1 OR author ILIKE $1 OR description ILIKE $1
+            "#,
+        )
+        .bind(&pattern)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        // Exact title matches first, then title hits, then author/description hits
+        let books = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT id, title, slug, author, author_id, cover, description, asset,
+                   series_id, series_position,
+                   status, language, release_date, popular, views, version,
+                   created_at, updated_at
+            FROM "Book"
+            WHERE title ILIKE $1 OR author ILIKE $1 OR description ILIKE $1
+            ORDER BY (LOWER(title) = LOWER($2)) DESC,
+                     (title ILIKE $1) DESC,
+                     created_at DESC
+            LIMIT $3 OFFSET $4
+            "#,
+        )
+        .bind(&pattern)
+        .bind(query)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
         let _ = redis.set_json(&cache_key, &response, 600).await;
 
@@ -198,68 +716,271 @@ impl BookService {
 
         let book = sqlx::query_as::<_, Book>(
             r#"
-            SELECT id, title, author, cover, description, asset, status, language, release_date, popular,
+            SELECT id, title, slug, author, author_id, cover, description, asset, status, language, release_date, popular, views, version,
+                   series_id, series_position,
                    created_at, updated_at
             FROM "Book" WHERE id = $1
             "#,
         )
         .bind(&id)
-        .fetch_one(&self.db.pool)
-        .await?;
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
 
-        let data: BookDto = book.into();
+        let mut data: BookDto = book.into();
+        data.rating = Some(
+            ReviewService::new(self.db.clone())
+                .get_rating_summary(&id)
+                .await?,
+        );
+        data.bookmark_count = Some(self.count_bookmarks(&id).await?);
+        let (chapter_count, total_word_count) = self.count_chapter_words(&id).await?;
+        data.chapter_count = Some(chapter_count);
+        data.total_word_count = Some(total_word_count);
+        data.genres = Some(
+            GenreService::new(self.db.clone())
+                .get_genres_by_book(id.clone())
+                .await?,
+        );
+        data.series = SeriesService::new(self.db.clone())
+            .get_series_info(&id)
+            .await?;
+        data.tags = Some(TagService::new(self.db.clone()).get_tags_by_book(id).await?);
         let _ = redis.set_json(&cache_key, &data, 600).await;
 
         Ok(data)
     }
 
+    pub async fn get_book_by_slug(&self, slug: &str) -> AppResult<BookDto> {
+        let id = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Book" WHERE slug = $1"#)
+            .bind(slug)
+            .fetch_optional(&self.db.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
+
+        self.get_book(id).await
+    }
+
+    /// Flag which of `books` the user has bookmarked, in one query for the whole page
+    pub async fn mark_bookmarked(&self, user_id: &str, books: &mut [BookDto]) -> AppResult<()> {
+        if books.is_empty() {
+            return Ok(());
+        }
+
+        let ids: Vec<String> = books.iter().map(|book| book.id.clone()).collect();
+        let bookmarked: Vec<String> = sqlx::query_scalar(
+            r#"SELECT book_id FROM "Bookmark" WHERE user_id = $1 AND book_id = ANY($2)"#,
+        )
+        .bind(user_id)
+        .bind(&ids)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        for book in books.iter_mut() {
+            book.is_bookmarked = Some(bookmarked.contains(&book.id));
+        }
+
+        Ok(())
+    }
+
+    /// Number of readers who bookmarked the book; 404 if the book doesn't exist
+    pub async fn get_bookmark_count(&self, id: &str) -> AppResult<i64> {
+        let redis = &self.db.redis;
+        let cache_key = format!("book:{id}:bookmarks");
+
+        if let Ok(Some(count)) = redis.get_json::<i64>(&cache_key).await {
+            return Ok(count);
+        }
+
+        let exists =
+            sqlx::query_scalar::<_, bool>(r#"SELECT EXISTS(SELECT 1 FROM "Book" WHERE id = $1)"#)
+                .bind(id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        if !exists {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let count = self.count_bookmarks(id).await?;
+        let _ = redis.set_json(&cache_key, &count, 600).await;
+
+        Ok(count)
+    }
+
+    async fn count_bookmarks(&self, id: &str) -> AppResult<i64> {
+        let count =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Bookmark" WHERE book_id = $1"#)
+                .bind(id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        Ok(count)
+    }
+
+    /// Number of chapters and their summed word counts
+    async fn count_chapter_words(&self, id: &str) -> AppResult<(i64, i64)> {
+        let counts = sqlx::query_as::<_, (i64, i64)>(
+            r#"
+            SELECT COUNT(*), COALESCE(SUM(word_count), 0)::BIGINT
+            FROM "Chapter"
+            WHERE book_id = $1
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        Ok(counts)
+    }
+
+    /// Count a view of the book, at most once per viewer per `VIEW_DEBOUNCE_SECS`.
+    /// The increment is a single atomic UPDATE; cached book responses are left to
+    /// expire rather than invalidated on every view.
+    pub async fn record_view(&self, id: &str, viewer: &str) -> AppResult<()> {
+        let redis = &self.db.redis;
+        let debounce_key = format!("book:view:{}:{}", id, viewer);
+
+        // Without Redis (or if it errors) every view counts rather than none
+        if let Ok(false) = redis.set_nx_ex(&debounce_key, "1", VIEW_DEBOUNCE_SECS).await {
+            return Ok(());
+        }
+
+        let result = sqlx::query(r#"UPDATE "Book" SET views = views + 1 WHERE id = $1"#)
+            .bind(id)
+            .execute(&self.db.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Drop cached counts after a bookmark on `id` is added or removed
+    pub async fn invalidate_bookmark_count(&self, id: &str) {
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("book:{id}")).await;
+        let _ = redis.del(&format!("book:{id}:bookmarks")).await;
+    }
+
+    /// Apply the fields of `request` that differ from the stored book. When none
+    /// do, nothing is written and `updated_at` keeps its value.
     pub async fn update_book(&self, id: String, request: UpdateBookDto) -> AppResult<BookDto> {
+        if request.is_empty() {
+            return Err(AppError::BadRequest("No fields to update".to_string()));
+        }
+        request.check_series()?;
         let redis = &self.db.redis;
         let cache_key = format!("book:{id}");
 
+        let current = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT id, title, slug, author, author_id, cover, description, asset,
+                   series_id, series_position,
+                   status, language, release_date, popular, views, version,
+                   created_at, updated_at
+            FROM "Book" WHERE id = $1
+            "#,
+        )
+        .bind(&id)
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
+
+        if current.version != request.version {
+            return Err(stale_book_version());
+        }
+
         let mut builder = QueryBuilder::new(r#"UPDATE "Book" SET "#);
         let mut separated = builder.separated(", ");
         let mut has_updates = false;
 
-        if let Some(ref title) = request.title {
+        if let Some(title) = request.title.as_ref().filter(|t| **t != current.title) {
+            let slug = unique_slug(&self.db.pool, "Book", title, "book", Some(&id)).await?;
             separated.push("title = ").push_bind_unseparated(title);
+            separated.push("slug = ").push_bind_unseparated(slug);
             has_updates = true;
         }
-        if let Some(ref author) = request.author {
-            separated.push("author = ").push_bind_unseparated(author);
-            has_updates = true;
+        let mut author_changed = false;
+        if request.author_id.is_some() || request.author.is_some() {
+            let mut conn = self.db.pool.acquire().await?;
+            let author = AuthorService::resolve(
+                &mut conn,
+                request.author_id.as_deref(),
+                request.author.as_deref().unwrap_or_default(),
+            )
+            .await?;
+            if author.id != current.author_id {
+                separated.push("author = ").push_bind_unseparated(author.name);
+                separated.push("author_id = ").push_bind_unseparated(author.id);
+                author_changed = true;
+                has_updates = true;
+            }
         }
-        if let Some(ref cover) = request.cover {
+        if let Some(cover) = request.cover.as_ref().filter(|c| **c != current.cover) {
             separated.push("cover = ").push_bind_unseparated(cover);
             has_updates = true;
         }
-        if let Some(ref description) = request.description {
+        let series_changed = request.series_id.is_some() && request.series_id != current.series_id
+            || request.series_position.is_some()
+                && request.series_position != current.series_position;
+        if series_changed {
+            if let Some(ref series_id) = request.series_id {
+                separated
+                    .push("series_id = ")
+                    .push_bind_unseparated(series_id);
+            }
+            if let Some(series_position) = request.series_position {
+                separated
+                    .push("series_position = ")
+                    .push_bind_unseparated(series_position);
+            }
+            has_updates = true;
+        }
+        if let Some(description) = request
+            .description
+            .as_ref()
+            .filter(|d| **d != current.description)
+        {
             separated
                 .push("description = ")
                 .push_bind_unseparated(description);
             has_updates = true;
         }
-        if let Some(ref asset) = request.asset {
+        if let Some(asset) = request
+            .asset
+            .as_ref()
+            .filter(|a| Some(*a) != current.asset.as_ref())
+        {
             separated.push("asset = ").push_bind_unseparated(asset);
             has_updates = true;
         }
-        if let Some(ref status) = request.status {
+        if let Some(status) = request.status.as_ref().filter(|s| **s != current.status) {
             separated.push("status = ").push_bind_unseparated(status);
             has_updates = true;
         }
-        if let Some(ref language) = request.language {
+        if let Some(language) = request
+            .language
+            .as_ref()
+            .filter(|l| **l != current.language)
+        {
             separated
                 .push("language = ")
                 .push_bind_unseparated(language);
             has_updates = true;
         }
-        if let Some(ref release_date) = request.release_date {
+        if let Some(release_date) = request
+            .release_date
+            .filter(|d| Some(*d) != current.release_date)
+        {
             separated
                 .push("release_date = ")
                 .push_bind_unseparated(release_date);
             has_updates = true;
         }
-        if let Some(ref popular) = request.popular {
+        if let Some(popular) = request.popular.filter(|p| *p != current.popular) {
             separated.push("popular = ").push_bind_unseparated(popular);
             has_updates = true;
         }
@@ -271,34 +992,583 @@ impl BookService {
         separated
             .push("updated_at = ")
             .push_bind_unseparated(Utc::now());
+        separated.push("version = version + 1");
         builder.push(" WHERE id = ").push_bind(id);
-        builder.push(" RETURNING *");
+        builder.push(" AND version = ").push_bind(request.version);
+        builder.push(
+            " RETURNING id, title, slug, author, author_id, cover, description, asset, \
+             series_id, series_position, status, language, release_date, popular, views, version, \
+             created_at, updated_at",
+        );
 
         let updated_book = builder
             .build_query_as::<Book>()
-            .fetch_one(&self.db.pool)
-            .await?;
+            .fetch_optional(&self.db.pool)
+            .await
+            .map_err(map_book_conflict)?
+            // The row was read above, so losing it now means a concurrent edit won
+            .ok_or_else(stale_book_version)?;
 
         redis.del(&cache_key).await.ok();
         let data: BookDto = updated_book.into();
         let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+        if author_changed {
+            let _ = redis.del_prefix("author:list").await;
+        }
+        if series_changed {
+            // The book may have left another series, so drop every cached series
+            let _ = redis.del_prefix("series:").await;
+        }
         Ok(data)
     }
 
+    /// Overwrite every editable field of a book, resetting omitted optional ones
+    /// to their create defaults
+    pub async fn replace_book(&self, id: String, request: ReplaceBookDto) -> AppResult<BookDto> {
+        let ReplaceBookDto {
+            version,
+            book: request,
+        } = request;
+        request.check_series()?;
+        let redis = &self.db.redis;
+        let mut conn = self.db.pool.acquire().await?;
+
+        let current: i32 = sqlx::query_scalar(r#"SELECT version FROM "Book" WHERE id = $1"#)
+            .bind(&id)
+            .fetch_optional(&mut *conn)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
+
+        if current != version {
+            return Err(stale_book_version());
+        }
+
+        let slug = unique_slug(&mut *conn, "Book", &request.title, "book", Some(&id)).await?;
+        let author =
+            AuthorService::resolve(&mut conn, request.author_id.as_deref(), &request.author)
+                .await?;
+
+        let book = sqlx::query_as::<_, Book>(
+            r#"
+            UPDATE "Book"
+            SET title = $2, slug = $3, author = $4, author_id = $5, cover = $6,
+                description = $7, asset = $8, series_id = $9, series_position = $10,
+                status = $11, language = $12, release_date = $13, popular = $14,
+                updated_at = $15, version = version + 1
+            WHERE id = $1 AND version = $16
+            RETURNING id, title, slug, author, author_id, cover, description, asset,
+                      series_id, series_position,
+                      status, language, release_date, popular, views, version,
+                      created_at, updated_at
+            "#,
+        )
+        .bind(&id)
+        .bind(&request.title)
+        .bind(&slug)
+        .bind(&author.name)
+        .bind(&author.id)
+        .bind(&request.cover)
+        .bind(&request.description)
+        .bind(&request.asset)
+        .bind(&request.series_id)
+        .bind(request.series_position)
+        .bind(request.status.clone())
+        .bind(&request.language)
+        .bind(request.release_date)
+        .bind(request.popular)
+        .bind(Utc::now())
+        .bind(version)
+        .fetch_optional(&mut *conn)
+        .await
+        .map_err(map_book_conflict)?
+        // The row was read above, so losing it now means a concurrent edit won
+        .ok_or_else(stale_book_version)?;
+
+        let _ = redis.del(&format!("book:{id}")).await;
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+        let _ = redis.del_prefix("author:list").await;
+        let _ = redis.del_prefix("series:").await;
+
+        Ok(book.into())
+    }
+
+    /// Stream CSV `chunks` into books, inserting every valid row in one transaction.
+    /// Rows that fail parsing or validation are skipped and reported in the summary.
+    pub async fn import_books(
+        &self,
+        chunks: mpsc::Receiver<Bytes>,
+        limits: &FieldLimits,
+    ) -> AppResult<BookImportSummary> {
+        let redis = &self.db.redis;
+        let (row_tx, mut rows) = mpsc::channel::<CsvRecord>(64);
+        let parser =
+            tokio::task::spawn_blocking(move || parse_book_csv(ChannelReader::new(chunks), row_tx));
+
+        let mut tx = self.db.pool.begin().await?;
+        let mut summary = BookImportSummary::default();
+
+        while let Some((row, parsed)) = rows.recv().await {
+            let checked = parsed.and_then(|dto| {
+                dto.validate().map_err(|e| e.to_string())?;
+                dto.check_lengths(limits).map_err(|e| e.to_string())?;
+                Ok(dto)
+            });
+
+            let outcome = match checked {
+                Ok(dto) => {
+                    // A savepoint per row, so a row the database refuses only loses that row
+                    let mut savepoint = tx.begin().await?;
+                    match Self::insert_book(&mut savepoint, &dto).await {
+                        Ok(_) => {
+                            savepoint.commit().await?;
+                            Ok(())
+                        }
+                        Err(e) => {
+                            savepoint.rollback().await?;
+                            Err(e.to_string())
+                        }
+                    }
+                }
+                Err(message) => Err(message),
+            };
+
+            match outcome {
+                Ok(()) => summary.inserted += 1,
+                Err(message) => {
+                    summary.skipped += 1;
+                    summary.errors.push(BookImportError { row, message });
+                }
+            }
+        }
+
+        parser
+            .await
+            .map_err(|e| AppError::Internal(format!("CSV parser task failed: {}", e)))??;
+
+        tx.commit().await?;
+
+        if summary.inserted > 0 {
+            let _ = redis.del_prefix("books:list:").await;
+            let _ = redis.del_prefix("books:search:").await;
+            let _ = redis.del_prefix("author:list").await;
+        }
+
+        Ok(summary)
+    }
+
+    /// JSON bundle of the book, its genres and every chapter ordered by `chapter_num`.
+    /// Chapters are streamed from the database so large books are never held in memory.
+    /// Returns the book's slug alongside the body for naming the download.
+    pub async fn export_book(&self, id: String, reading_wpm: u32) -> AppResult<(String, Body)> {
+        let mut book = self.get_book(id.clone()).await?;
+        let slug = book.slug.clone();
+        let genres = book.genres.take().unwrap_or_default();
+
+        let header = BookExportHeader {
+            schema_version: BOOK_EXPORT_SCHEMA_VERSION,
+            exported_at: Utc::now().naive_utc(),
+            book,
+            genres,
+        };
+        let mut prefix = serde_json::to_vec(&header)
+            .map_err(|e| AppError::Internal(format!("Failed to serialize export: {}", e)))?;
+        // Reopen the header object so the chapters array can be appended to it
+        prefix.pop();
+        prefix.extend_from_slice(br#","chapters":["#);
+
+        let pool = self.db.pool.clone();
+        let (tx, rx) = mpsc::channel::<Result<Bytes, sqlx::Error>>(16);
+        tokio::spawn(async move {
+            if tx.send(Ok(Bytes::from(prefix))).await.is_err() {
+                return;
+            }
+
+            let mut chapters = sqlx::query_as::<_, Chapter>(
+                r#"
+                SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num,
+                       word_count
+                FROM "Chapter"
+                WHERE book_id = $1
+                ORDER BY chapter_num ASC
+                "#,
+            )
+            .bind(&id)
+            .fetch(&pool);
+
+            let mut separator: &[u8] = b"";
+            while let Some(chapter) = chapters.next().await {
+                let chunk = match chapter {
+                    Ok(chapter) => {
+                        let mut chunk = separator.to_vec();
+                        // ChapterDto only holds strings and numbers, so this cannot fail
+                        let _ = serde_json::to_writer(&mut chunk, &ChapterDto::new(chapter, reading_wpm));
+                        separator = b",";
+                        Ok(Bytes::from(chunk))
+                    }
+                    Err(e) => {
+                        // Ending the body early leaves the client with truncated, invalid JSON
+                        warn!(error = ?e, book_id = %id, "Failed to stream chapters for export");
+                        let _ = tx.send(Err(e)).await;
+                        return;
+                    }
+                };
+
+                // A send error means the client went away
+                if tx.send(chunk).await.is_err() {
+                    return;
+                }
+            }
+
+            let _ = tx.send(Ok(Bytes::from_static(b"]}"))).await;
+        });
+
+        let body = Body::from_stream(stream::unfold(rx, |mut rx| async move {
+            rx.recv().await.map(|chunk| (chunk, rx))
+        }));
+
+        Ok((slug, body))
+    }
+
+    /// Store a new cover image, point `cover` at it and drop the previous object.
+    /// `version` is the one the client last read; a stale value is rejected with 409.
+    pub async fn update_cover(
+        &self,
+        id: &str,
+        bytes: Vec<u8>,
+        version: i32,
+    ) -> AppResult<BookDto> {
+        let redis = &self.db.redis;
+
+        if bytes.len() > MAX_COVER_BYTES {
+            return Err(AppError::BadRequest(format!(
+                "Cover must be at most {} bytes",
+                MAX_COVER_BYTES
+            )));
+        }
+
+        // Trust the bytes rather than the client-supplied content type
+        let (content_type, extension) = match ContentExtractor::sniff_image_type(&bytes) {
+            Some("image/jpeg") => ("image/jpeg", "jpg"),
+            Some("image/png") => ("image/png", "png"),
+            Some("image/gif") => ("image/gif", "gif"),
+            Some("image/webp") => ("image/webp", "webp"),
+            _ => {
+                return Err(AppError::BadRequest(
+                    "Cover must be a JPEG, PNG, GIF or WebP image".to_string(),
+                ))
+            }
+        };
+
+        let (previous, current): (String, i32) =
+            sqlx::query_as(r#"SELECT cover, version FROM "Book" WHERE id = $1"#)
+                .bind(id)
+                .fetch_optional(&self.db.pool)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Book not found".to_string()))?;
+
+        if current != version {
+            return Err(stale_book_version());
+        }
+
+        let filename = format!("{}.{}", cuid2::create_id(), extension);
+        let url = self
+            .storage
+            .upload_image("covers", id, &filename, bytes, content_type)
+            .await?;
+
+        let book = sqlx::query_as::<_, Book>(
+            r#"
+            UPDATE "Book"
+            SET cover = $2, updated_at = $3, version = version + 1
+            WHERE id = $1 AND version = $4
+            RETURNING id, title, slug, author, author_id, cover, description, asset, status, language,
+                      series_id, series_position,
+                      release_date, popular, views, version, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(&url)
+        .bind(Utc::now())
+        .bind(version)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        let Some(book) = book else {
+            // A concurrent edit won; the object just uploaded is not referenced by anything
+            if let Some(key) = self.storage.key_from_public_url(&url) {
+                if let Err(e) = self.storage.delete_file(&key).await {
+                    warn!(error = ?e, key = %key, "Failed to delete unused cover");
+                }
+            }
+            return Err(stale_book_version());
+        };
+
+        let _ = redis.del(&format!("book:{id}")).await;
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+
+        // Only remove objects we own; covers set as external URLs are left alone
+        let cover_prefix = format!("covers/{}/", id);
+        if let Some(key) = self
+            .storage
+            .key_from_public_url(&previous)
+            .filter(|key| key.starts_with(&cover_prefix))
+        {
+            if let Err(e) = self.storage.delete_file(&key).await {
+                warn!(error = ?e, key = %key, "Failed to delete previous cover");
+            }
+        }
+
+        Ok(book.into())
+    }
+
+    /// Delete a book; its chapters, bookmarks, genre links and other dependents
+    /// go with it through `ON DELETE CASCADE`, so the removal is a single statement
     pub async fn delete_book(&self, id: String) -> AppResult<BookDto> {
         let redis = &self.db.redis;
         let book = self.get_book(id.clone()).await?;
 
-        sqlx::query(r#"DELETE FROM "Book" WHERE id = $1"#)
+        let result = sqlx::query(r#"DELETE FROM "Book" WHERE id = $1"#)
             .bind(&id)
             .execute(&self.db.pool)
-            .await?;
+            .await
+            .map_err(map_still_referenced)?;
+
+        // Deleted concurrently between the lookup and here
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
 
         let redis_key = format!("book:{id}");
         if redis.exists(&redis_key).await.unwrap_or(false) {
             let _ = redis.del(&redis_key).await;
         }
-        let data = book.into();
-        Ok(data)
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("books:search:").await;
+        let _ = redis.del_prefix(&format!("chapters:book:{id}")).await;
+        let _ = redis.del_prefix(&format!("chapter:{id}:")).await;
+        if let Some(series_id) = &book.series_id {
+            let _ = redis.del(&format!("series:{series_id}")).await;
+            let _ = redis.del(&format!("series:{series_id}:books")).await;
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use sqlx::PgPool;
+
+    fn service(pool: &PgPool) -> BookService {
+        let config = test_support::config();
+        BookService::new(test_support::database(pool.clone()), StorageService::new(&config))
+    }
+
+    fn book(title: &str) -> CreateBookDto {
+        CreateBookDto {
+            title: title.to_string(),
+            author: "Round Trip Author".to_string(),
+            author_id: None,
+            cover: "https://cdn.example.com/covers/round-trip.png".to_string(),
+            description: "A book that survives the database".to_string(),
+            asset: Some("https://cdn.example.com/assets/round-trip.epub".to_string()),
+            series_id: None,
+            series_position: None,
+            status: Status::Completed,
+            language: Language::English,
+            release_date: NaiveDate::from_ymd_opt(2020, 5, 17),
+            popular: true,
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn created_book_fetches_back_with_every_field(pool: PgPool) {
+        let service = service(&pool);
+        let created = service.create_book(book("Round Trip")).await.unwrap();
+
+        let fetched = service.get_book(created.id.clone()).await.unwrap();
+
+        assert_eq!(fetched.id, created.id);
+        assert_eq!(fetched.title, "Round Trip");
+        assert_eq!(fetched.author, "Round Trip Author");
+        assert_eq!(fetched.author_id, created.author_id);
+        assert_eq!(fetched.description, "A book that survives the database");
+        assert_eq!(
+            fetched.asset.as_deref(),
+            Some("https://cdn.example.com/assets/round-trip.epub")
+        );
+        assert!(fetched.popular);
+        assert_eq!(fetched.status, Status::Completed);
+        assert_eq!(fetched.language, Language::English);
+        assert_eq!(fetched.release_date, NaiveDate::from_ymd_opt(2020, 5, 17));
+        assert_eq!(fetched.version, created.version);
+    }
+
+    fn patch(fields: serde_json::Value) -> UpdateBookDto {
+        serde_json::from_value(fields).unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn empty_patch_is_rejected_without_touching_the_book(pool: PgPool) {
+        let service = service(&pool);
+        let created = service.create_book(book("Untouched")).await.unwrap();
+
+        let empty = patch(serde_json::json!({ "version": created.version }));
+        let result = service.update_book(created.id.clone(), empty).await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+
+        let fetched = service.get_book(created.id).await.unwrap();
+        assert_eq!(fetched.updated_at, created.updated_at);
+        assert_eq!(fetched.version, created.version);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn patch_repeating_current_values_keeps_updated_at(pool: PgPool) {
+        let service = service(&pool);
+        let created = service.create_book(book("Same Again")).await.unwrap();
+
+        let unchanged = service
+            .update_book(
+                created.id.clone(),
+                patch(serde_json::json!({
+                    "version": created.version,
+                    "title": "Same Again",
+                    "popular": true,
+                })),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(unchanged.updated_at, created.updated_at);
+        assert_eq!(unchanged.version, created.version);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn import_skips_rows_the_database_refuses(pool: PgPool) {
+        let service = service(&pool);
+        // Postgres rejects NUL in text columns, which no DTO check catches
+        let csv = "title,author,description\n\
+                   First Import,Importer,ok\n\
+                   Broken Import,Importer,bad\0byte\n\
+                   Third Import,Importer,ok\n";
+        let (chunks_tx, chunks) = mpsc::channel(1);
+        chunks_tx.send(Bytes::from(csv)).await.unwrap();
+        drop(chunks_tx);
+
+        let summary = service
+            .import_books(chunks, &FieldLimits::default())
+            .await
+            .unwrap();
+
+        assert_eq!(summary.inserted, 2);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.errors.len(), 1);
+        assert_eq!(summary.errors[0].row, 3);
+
+        let titles: Vec<String> = sqlx::query_scalar(
+            r#"SELECT title FROM "Book" WHERE title LIKE '% Import' ORDER BY title"#,
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(titles, ["First Import", "Third Import"]);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn stale_version_is_a_conflict_and_changes_nothing(pool: PgPool) {
+        let service = service(&pool);
+        let created = service.create_book(book("Contended")).await.unwrap();
+        let fresh = service
+            .update_book(
+                created.id.clone(),
+                patch(serde_json::json!({ "version": created.version, "title": "Contended 2" })),
+            )
+            .await
+            .unwrap();
+        let stale = created.version;
+
+        let patched = service
+            .update_book(
+                created.id.clone(),
+                patch(serde_json::json!({ "version": stale, "title": "Lost Update" })),
+            )
+            .await;
+        assert!(matches!(patched, Err(AppError::Conflict(_))));
+
+        let mut replacement = book("Lost Replace");
+        replacement.popular = false;
+        let replaced = service
+            .replace_book(
+                created.id.clone(),
+                ReplaceBookDto {
+                    version: stale,
+                    book: replacement,
+                },
+            )
+            .await;
+        assert!(matches!(replaced, Err(AppError::Conflict(_))));
+
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0].to_vec();
+        let covered = service.update_cover(&created.id, png, stale).await;
+        assert!(matches!(covered, Err(AppError::Conflict(_))));
+
+        let after = service.get_book(created.id).await.unwrap();
+        assert_eq!(after.title, "Contended 2");
+        assert!(after.popular);
+        assert_eq!(after.cover, fresh.cover);
+        assert_eq!(after.version, fresh.version);
+        assert_eq!(after.updated_at, fresh.updated_at);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn missing_book_is_not_found(pool: PgPool) {
+        let service = service(&pool);
+
+        let fetched = service.get_book("no-such-book".to_string()).await;
+        assert!(matches!(fetched, Err(AppError::NotFound(_))));
+
+        let deleted = service.delete_book("no-such-book".to_string()).await;
+        assert!(matches!(deleted, Err(AppError::NotFound(_))));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn delete_removes_a_bookmarked_book(pool: PgPool) {
+        let service = service(&pool);
+        let created = service.create_book(book("Bookmarked")).await.unwrap();
+        let reader = test_support::seed_user(&pool, "bookmarker").await;
+        sqlx::query(
+            r#"INSERT INTO "Bookmark" (id, user_id, book_id, updated_at) VALUES ($1, $2, $3, NOW())"#,
+        )
+        .bind(cuid2::create_id())
+        .bind(&reader)
+        .bind(&created.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        service.delete_book(created.id.clone()).await.unwrap();
+
+        let bookmarks: i64 =
+            sqlx::query_scalar(r#"SELECT COUNT(*) FROM "Bookmark" WHERE book_id = $1"#)
+                .bind(&created.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(bookmarks, 0);
+        assert!(matches!(
+            service.get_book(created.id).await,
+            Err(AppError::NotFound(_))
+        ));
     }
 }
@@ -1,32 +1,89 @@
 use crate::database::Database;
-use crate::errors::AppResult;
-use crate::models::chapter_model::{Chapter, ChapterDto, CreateChapterDto, UpdateChapterDto};
-use crate::models::paging_model::{PaginatedResponse, PaginationParams};
+use crate::errors::{map_still_referenced, AppError, AppResult};
+use crate::models::chapter_model::{
+    Chapter, ChapterDetailDto, ChapterDto, ChapterWithNeighbors, CreateChapterDto,
+    ReorderChaptersDto, UpdateChapterDto,
+};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use crate::models::upload_model::CreateChapterFromUploadDto;
 use crate::services::notification_service::NotificationService;
+use crate::utils::reading_time::word_count;
 use chrono::Utc;
 use cuid2;
-use sqlx::QueryBuilder;
+use sqlx::{PgConnection, QueryBuilder};
+use std::collections::HashSet;
+use std::sync::Arc;
 use tracing::error;
 
-pub struct ChapterService<'a> {
+/// Chapter details are cached per book so a write only drops its own book's entries
+fn chapter_cache_key(book_id: &str, id: &str) -> String {
+    format!("chapter:{}:{}", book_id, id)
+}
+
+fn book_chapters_cache_prefix(book_id: &str) -> String {
+    format!("chapter:{}:", book_id)
+}
+
+/// Which book a chapter id belongs to, so reads by id can find the per-book entry
+fn chapter_book_cache_key(id: &str) -> String {
+    format!("chapter-book:{}", id)
+}
+
+pub struct ChapterService {
     db: Database,
-    notification: &'a NotificationService,
+    notification: Arc<NotificationService>,
+    reading_wpm: u32,
 }
 
-impl<'a> ChapterService<'a> {
-    pub fn new(db: Database, notification: &'a NotificationService) -> Self {
-        Self { db, notification }
+impl ChapterService {
+    pub fn new(db: Database, notification: Arc<NotificationService>, reading_wpm: u32) -> Self {
+        Self {
+            db,
+            notification,
+            reading_wpm,
+        }
     }
 
     pub async fn create_chapter(&self, request: CreateChapterDto) -> AppResult<ChapterDto> {
+        let mut tx = self.db.pool.begin().await?;
+        let chapter = Self::insert_chapter(&mut tx, &request).await?;
+        tx.commit().await?;
+
+        self.chapter_created(&chapter).await;
+        Ok(ChapterDto::new(chapter, self.reading_wpm))
+    }
+
+    /// Insert `request` as a new chapter and touch the book's `updated_at`, inside the
+    /// caller's transaction
+    async fn insert_chapter(
+        conn: &mut PgConnection,
+        request: &CreateChapterDto,
+    ) -> AppResult<Chapter> {
+        // Lock the book row so concurrent creates can't pick the same next number
+        let book_exists =
+            sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Book" WHERE id = $1 FOR UPDATE"#)
+                .bind(&request.book_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+
+        if book_exists.is_none() {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let chapter_num = match request.chapter_num {
+            Some(num) => num,
+            None => Self::next_chapter_num(conn, &request.book_id).await?,
+        };
+
         let chapter = sqlx::query_as::<_, Chapter>(
             r#"
             INSERT INTO "Chapter" (
                 id, title, book_id, description, content, chapter_num,
-                created_at, updated_at
+                word_count, created_at, updated_at
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-            RETURNING id, title, book_id, description, created_at, updated_at, content, chapter_num
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING id, title, book_id, description, created_at, updated_at, content, chapter_num,
+                      word_count
             "#,
         )
         .bind(cuid2::create_id())
@@ -34,53 +91,151 @@ impl<'a> ChapterService<'a> {
         .bind(&request.book_id)
         .bind(&request.description)
         .bind(&request.content)
-        .bind(request.chapter_num)
+        .bind(chapter_num)
+        .bind(word_count(&request.content))
         .bind(Utc::now())
         .bind(Utc::now())
-        .fetch_one(&self.db.pool)
-        .await?;
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                AppError::Conflict(format!(
+                    "Chapter {} already exists for this book",
+                    chapter_num
+                ))
+            }
+            e => e.into(),
+        })?;
 
         // Update the book's updated_at timestamp
         sqlx::query(r#"UPDATE "Book" SET updated_at = $1 WHERE id = $2"#)
             .bind(Utc::now())
             .bind(&request.book_id)
-            .execute(&self.db.pool)
+            .execute(&mut *conn)
             .await?;
 
+        Ok(chapter)
+    }
+
+    /// Cache invalidation and notifications once a new chapter is committed
+    async fn chapter_created(&self, chapter: &Chapter) {
         let redis = &self.db.redis;
         let _ = redis
-            .del_prefix(&format!("chapters:book:{}", request.book_id))
+            .del_prefix(&format!("chapters:book:{}", chapter.book_id))
             .await;
         // Also invalidate book cache
-        let _ = redis.del(&format!("book:{}", request.book_id)).await;
+        let _ = redis.del(&format!("book:{}", chapter.book_id)).await;
         let _ = redis.del_prefix("books:").await;
+        // Cached chapters carry prev/next ids that may now point past the new one
+        let _ = redis
+            .del_prefix(&book_chapters_cache_prefix(&chapter.book_id))
+            .await;
 
-        // Send push notification to users who bookmarked this novel
-        let book_title = self
-            .get_book_title(&request.book_id)
-            .await
-            .unwrap_or_else(|_| "Novel".to_string());
-        if let Err(e) = self
-            .notification
-            .notify_new_chapter(
-                &request.book_id,
-                &book_title,
-                request.chapter_num,
-                &request.title,
-                &chapter.id,
-            )
-            .await
-        {
-            error!("Failed to send push notifications: {:?}", e);
-        }
+        // Notify bookmarkers in the background so the response isn't held up by FCM
+        self.spawn_new_chapter_notification(chapter);
+    }
 
-        Ok(chapter.into())
+    async fn next_chapter_num(conn: &mut PgConnection, book_id: &str) -> AppResult<i32> {
+        let next = sqlx::query_scalar::<_, i32>(
+            r#"SELECT COALESCE(MAX(chapter_num), 0) + 1 FROM "Chapter" WHERE book_id = $1"#,
+        )
+        .bind(book_id)
+        .fetch_one(conn)
+        .await?;
+        Ok(next)
     }
 
-    async fn get_book_title(&self, book_id: &str) -> AppResult<String> {
+    /// Turn an unconsumed upload into a chapter. The upload row stays locked until the
+    /// chapter and `consumed_at` are committed together, so it can't be used twice.
+    pub async fn create_chapter_from_upload(
+        &self,
+        request: CreateChapterFromUploadDto,
+    ) -> AppResult<ChapterDto> {
+        let mut tx = self.db.pool.begin().await?;
+
+        let html_content = sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT html_content FROM "ContentUpload"
+            WHERE id = $1 AND consumed_at IS NULL
+            FOR UPDATE
+            "#,
+        )
+        .bind(&request.upload_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let Some(html_content) = html_content else {
+            let exists: bool =
+                sqlx::query_scalar(r#"SELECT EXISTS(SELECT 1 FROM "ContentUpload" WHERE id = $1)"#)
+                    .bind(&request.upload_id)
+                    .fetch_one(&mut *tx)
+                    .await?;
+
+            return Err(if exists {
+                AppError::Conflict("Upload has already been turned into a chapter".to_string())
+            } else {
+                AppError::NotFound("Upload not found".to_string())
+            });
+        };
+
+        let chapter = Self::insert_chapter(
+            &mut tx,
+            &CreateChapterDto {
+                title: request.title,
+                book_id: request.book_id,
+                description: request.description,
+                content: html_content,
+                chapter_num: Some(request.chapter_num),
+            },
+        )
+        .await?;
+
+        sqlx::query(
+            r#"UPDATE "ContentUpload" SET consumed_at = $1, updated_at = $1 WHERE id = $2"#,
+        )
+        .bind(Utc::now())
+        .bind(&request.upload_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.chapter_created(&chapter).await;
+        Ok(ChapterDto::new(chapter, self.reading_wpm))
+    }
+
+    fn spawn_new_chapter_notification(&self, chapter: &Chapter) {
+        let db = self.db.clone();
+        let notification = self.notification.clone();
+        let book_id = chapter.book_id.clone();
+        let chapter_id = chapter.id.clone();
+        let chapter_title = chapter.title.clone();
+        let chapter_num = chapter.chapter_num;
+
+        tokio::spawn(async move {
+            let book_title = Self::get_book_title(&db, &book_id)
+                .await
+                .unwrap_or_else(|_| "Novel".to_string());
+
+            if let Err(e) = notification
+                .notify_new_chapter(
+                    &book_id,
+                    &book_title,
+                    chapter_num,
+                    &chapter_title,
+                    &chapter_id,
+                )
+                .await
+            {
+                error!("Failed to send push notifications: {:?}", e);
+            }
+        });
+    }
+
+    async fn get_book_title(db: &Database, book_id: &str) -> AppResult<String> {
         let title = sqlx::query_scalar::<_, String>(r#"SELECT title FROM "Book" WHERE id = $1"#)
             .bind(book_id)
-            .fetch_one(&self.db.pool)
+            .fetch_one(&db.pool)
             .await?;
         Ok(title)
     }
@@ -89,7 +244,7 @@ impl<'a> ChapterService<'a> {
         &self,
         params: PaginationParams,
     ) -> AppResult<PaginatedResponse<ChapterDto>> {
-        let offset = (params.page - 1) * params.page_size;
+        let offset = page_offset(params.page, params.page_size)?;
         let redis = &self.db.redis;
 
         let cache_key = format!(
@@ -130,7 +285,8 @@ impl<'a> ChapterService<'a> {
 
         let fetch_query = format!(
             r#"
-            SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num
+            SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num,
+                   word_count
             FROM "Chapter"
             {}
             ORDER BY chapter_num ASC
@@ -151,16 +307,11 @@ impl<'a> ChapterService<'a> {
 
         let chapters = fetch_query_builder.fetch_all(&self.db.pool).await?;
 
-        let data: Vec<ChapterDto> = chapters.into_iter().map(ChapterDto::from).collect();
-        let total_pages = (total_items as f64 / params.page_size as f64).ceil() as i64;
-
-        let response = PaginatedResponse {
-            data,
-            page: params.page,
-            page_size: params.page_size,
-            total_items,
-            total_pages,
-        };
+        let data: Vec<ChapterDto> = chapters
+            .into_iter()
+            .map(|chapter| ChapterDto::new(chapter, self.reading_wpm))
+            .collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
         let _ = redis.set_json(&cache_key, &response, 600).await;
 
@@ -172,7 +323,7 @@ impl<'a> ChapterService<'a> {
         book_id: String,
         params: PaginationParams,
     ) -> AppResult<PaginatedResponse<ChapterDto>> {
-        let offset = (params.page - 1) * params.page_size;
+        let offset = page_offset(params.page, params.page_size)?;
         let redis = &self.db.redis;
 
         let cache_key = format!(
@@ -195,7 +346,8 @@ impl<'a> ChapterService<'a> {
 
         let chapters = sqlx::query_as::<_, Chapter>(
             r#"
-            SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num
+            SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num,
+                   word_count
             FROM "Chapter"
             WHERE book_id = $1
             ORDER BY chapter_num ASC
@@ -208,43 +360,61 @@ impl<'a> ChapterService<'a> {
         .fetch_all(&self.db.pool)
         .await?;
 
-        let data: Vec<ChapterDto> = chapters.into_iter().map(ChapterDto::from).collect();
-        let total_pages = (total_items as f64 / params.page_size as f64).ceil() as i64;
-
-        let response = PaginatedResponse {
-            data,
-            page: params.page,
-            page_size: params.page_size,
-            total_items,
-            total_pages,
-        };
+        let data: Vec<ChapterDto> = chapters
+            .into_iter()
+            .map(|chapter| ChapterDto::new(chapter, self.reading_wpm))
+            .collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
         let _ = redis.set_json(&cache_key, &response, 600).await;
 
         Ok(response)
     }
 
-    pub async fn get_chapter(&self, id: String) -> AppResult<ChapterDto> {
+    pub async fn get_chapter(&self, id: String) -> AppResult<ChapterDetailDto> {
         let redis = &self.db.redis;
-        let cache_key = format!("chapter:{}", id);
 
-        if let Ok(Some(cached_chapter)) = redis.get_json::<ChapterDto>(&cache_key).await {
-            return Ok(cached_chapter);
+        if let Ok(Some(book_id)) = redis.get_json::<String>(&chapter_book_cache_key(&id)).await {
+            let cache_key = chapter_cache_key(&book_id, &id);
+            if let Ok(Some(cached_chapter)) = redis.get_json::<ChapterDetailDto>(&cache_key).await {
+                return Ok(cached_chapter);
+            }
         }
 
-        let chapter = sqlx::query_as::<_, Chapter>(
+        // Neighbours are the max-lesser and min-greater chapter_num in the same book, resolved
+        // in the same query so readers can prefetch without extra calls. Both are NULL at the ends.
+        let chapter = sqlx::query_as::<_, ChapterWithNeighbors>(
             r#"
-            SELECT id, title, book_id, description, created_at, updated_at, content, chapter_num
-            FROM "Chapter"
-            WHERE id = $1
+            SELECT c.id, c.title, c.book_id, c.description, c.created_at, c.updated_at,
+                   c.content, c.chapter_num, c.word_count,
+                   (
+                       SELECT p.id FROM "Chapter" p
+                       WHERE p.book_id = c.book_id AND p.chapter_num < c.chapter_num
+                       ORDER BY p.chapter_num DESC
+                       LIMIT 1
+                   ) AS prev_chapter_id,
+                   (
+                       SELECT n.id FROM "Chapter" n
+                       WHERE n.book_id = c.book_id AND n.chapter_num > c.chapter_num
+                       ORDER BY n.chapter_num ASC
+                       LIMIT 1
+                   ) AS next_chapter_id
+            FROM "Chapter" c
+            WHERE c.id = $1
             "#,
         )
         .bind(&id)
         .fetch_one(&self.db.pool)
         .await?;
 
-        let data: ChapterDto = chapter.into();
-        let _ = redis.set_json(&cache_key, &data, 600).await;
+        let data = ChapterDetailDto::new(chapter, self.reading_wpm);
+        let book_id = &data.chapter.book_id;
+        let _ = redis
+            .set_json(&chapter_book_cache_key(&id), book_id, 600)
+            .await;
+        let _ = redis
+            .set_json(&chapter_cache_key(book_id, &id), &data, 600)
+            .await;
 
         Ok(data)
     }
@@ -255,7 +425,6 @@ impl<'a> ChapterService<'a> {
         request: UpdateChapterDto,
     ) -> AppResult<ChapterDto> {
         let redis = &self.db.redis;
-        let cache_key = format!("chapter:{}", id);
 
         let mut builder = QueryBuilder::new(r#"UPDATE "Chapter" SET "#);
         let mut separated = builder.separated(", ");
@@ -273,6 +442,9 @@ impl<'a> ChapterService<'a> {
         }
         if let Some(ref content) = request.content {
             separated.push("content = ").push_bind_unseparated(content);
+            separated
+                .push("word_count = ")
+                .push_bind_unseparated(word_count(content));
             has_updates = true;
         }
         if let Some(ref chapter_num) = request.chapter_num {
@@ -283,7 +455,7 @@ impl<'a> ChapterService<'a> {
         }
 
         if !has_updates {
-            return self.get_chapter(id).await;
+            return self.get_chapter(id).await.map(|detail| detail.chapter);
         }
 
         separated
@@ -298,33 +470,298 @@ impl<'a> ChapterService<'a> {
             .await?;
 
         let book_id = updated_chapter.book_id.clone();
-        redis.del(&cache_key).await.ok();
+        if request.chapter_num.is_some() {
+            // Renumbering changes the prev/next hints of other chapters
+            let _ = redis
+                .del_prefix(&book_chapters_cache_prefix(&book_id))
+                .await;
+        } else {
+            redis.del(&chapter_cache_key(&book_id, &id)).await.ok();
+        }
+        if request.content.is_some() {
+            // The single-book response carries the book's total word count
+            let _ = redis.del(&format!("book:{}", book_id)).await;
+        }
+        let _ = redis.del_prefix("chapters:list:").await;
+        let _ = redis
+            .del_prefix(&format!("chapters:book:{}", book_id))
+            .await;
+
+        let data = ChapterDto::new(updated_chapter, self.reading_wpm);
+        Ok(data)
+    }
+
+    /// Renumber a book's chapters 1..n in the given order, all in one transaction
+    pub async fn reorder_chapters(
+        &self,
+        book_id: String,
+        request: ReorderChaptersDto,
+    ) -> AppResult<Vec<ChapterDto>> {
+        let mut tx = self.db.pool.begin().await?;
+
+        // Lock the book row so chapter creates can't interleave with the renumbering
+        let book_exists =
+            sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Book" WHERE id = $1 FOR UPDATE"#)
+                .bind(&book_id)
+                .fetch_optional(&mut *tx)
+                .await?;
+
+        if book_exists.is_none() {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let existing: HashSet<String> =
+            sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Chapter" WHERE book_id = $1"#)
+                .bind(&book_id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+
+        let requested: HashSet<&str> = request.chapter_ids.iter().map(String::as_str).collect();
+        if requested.len() != request.chapter_ids.len() {
+            return Err(AppError::Unprocessable(
+                "chapter_ids must not contain duplicates".to_string(),
+            ));
+        }
+        if requested.len() != existing.len()
+            || !existing.iter().all(|id| requested.contains(id.as_str()))
+        {
+            return Err(AppError::Unprocessable(format!(
+                "chapter_ids must list all {} chapters of the book exactly once",
+                existing.len()
+            )));
+        }
+
+        // The (book_id, chapter_num) index is checked row by row, so first move every
+        // chapter above both the current numbers and the final range, then assign 1..n
+        sqlx::query(
+            r#"
+            UPDATE "Chapter" c
+            SET chapter_num = o.ord::INT + (
+                SELECT GREATEST(MAX(chapter_num), COUNT(*)::INT)
+                FROM "Chapter" WHERE book_id = $1
+            )
+            FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS o(id, ord)
+            WHERE c.id = o.id AND c.book_id = $1
+            "#,
+        )
+        .bind(&book_id)
+        .bind(&request.chapter_ids)
+        .execute(&mut *tx)
+        .await?;
+
+        let now = Utc::now();
+        let chapters = sqlx::query_as::<_, Chapter>(
+            r#"
+            UPDATE "Chapter" c
+            SET chapter_num = o.ord::INT, updated_at = $3
+            FROM UNNEST($2::TEXT[]) WITH ORDINALITY AS o(id, ord)
+            WHERE c.id = o.id AND c.book_id = $1
+            RETURNING c.id, c.title, c.book_id, c.description, c.created_at, c.updated_at,
+                      c.content, c.chapter_num, c.word_count
+            "#,
+        )
+        .bind(&book_id)
+        .bind(&request.chapter_ids)
+        .bind(now)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        sqlx::query(r#"UPDATE "Book" SET updated_at = $1 WHERE id = $2"#)
+            .bind(now)
+            .bind(&book_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+
+        let redis = &self.db.redis;
+        // Every cached chapter of the book now has different prev/next hints
+        let _ = redis
+            .del_prefix(&book_chapters_cache_prefix(&book_id))
+            .await;
         let _ = redis.del_prefix("chapters:list:").await;
         let _ = redis
             .del_prefix(&format!("chapters:book:{}", book_id))
             .await;
+        let _ = redis.del(&format!("book:{}", book_id)).await;
 
-        let data: ChapterDto = updated_chapter.into();
+        let mut data: Vec<ChapterDto> = chapters
+            .into_iter()
+            .map(|chapter| ChapterDto::new(chapter, self.reading_wpm))
+            .collect();
+        data.sort_by_key(|chapter| chapter.chapter_num);
         Ok(data)
     }
 
     pub async fn delete_chapter(&self, id: String) -> AppResult<ChapterDto> {
         let redis = &self.db.redis;
-        let chapter = self.get_chapter(id.clone()).await?;
+        let chapter = self.get_chapter(id.clone()).await?.chapter;
 
         sqlx::query(r#"DELETE FROM "Chapter" WHERE id = $1"#)
             .bind(&id)
             .execute(&self.db.pool)
-            .await?;
+            .await
+            .map_err(map_still_referenced)?;
 
-        let cache_key = format!("chapter:{}", id);
-        if redis.exists(&cache_key).await.unwrap_or(false) {
-            let _ = redis.del(&cache_key).await;
-        }
+        // Neighbouring chapters cache this id as their prev/next hint
+        let _ = redis
+            .del_prefix(&book_chapters_cache_prefix(&chapter.book_id))
+            .await;
+        let _ = redis.del(&chapter_book_cache_key(&id)).await;
         let _ = redis
             .del_prefix(&format!("chapters:book:{}", chapter.book_id))
             .await;
+        // Drops the book's chapter and word counts
+        let _ = redis.del(&format!("book:{}", chapter.book_id)).await;
 
         Ok(chapter)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use sqlx::PgPool;
+
+    fn service(pool: &PgPool) -> ChapterService {
+        let config = test_support::config();
+        let db = test_support::database(pool.clone());
+        let notification = Arc::new(NotificationService::new(db.clone(), &config));
+        ChapterService::new(db, notification, config.reading_wpm)
+    }
+
+    fn chapter(book_id: &str, title: &str, chapter_num: Option<i32>) -> CreateChapterDto {
+        CreateChapterDto {
+            title: title.to_string(),
+            book_id: book_id.to_string(),
+            description: String::new(),
+            content: "<p>Once upon a time</p>".to_string(),
+            chapter_num,
+        }
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn chapters_without_a_number_follow_the_highest(pool: PgPool) {
+        let book_id = test_support::seed_book(&pool, "Numbered", 0).await;
+        let service = service(&pool);
+
+        let first = service
+            .create_chapter(chapter(&book_id, "One", None))
+            .await
+            .unwrap();
+        let second = service
+            .create_chapter(chapter(&book_id, "Two", None))
+            .await
+            .unwrap();
+        assert_eq!((first.chapter_num, second.chapter_num), (1, 2));
+
+        // Numbering continues from the highest, not from the count
+        service
+            .create_chapter(chapter(&book_id, "Ten", Some(10)))
+            .await
+            .unwrap();
+        let next = service
+            .create_chapter(chapter(&book_id, "Eleven", None))
+            .await
+            .unwrap();
+        assert_eq!(next.chapter_num, 11);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn explicit_duplicate_number_is_a_conflict(pool: PgPool) {
+        let book_id = test_support::seed_book(&pool, "Duplicated", 0).await;
+        let service = service(&pool);
+
+        service
+            .create_chapter(chapter(&book_id, "One", Some(1)))
+            .await
+            .unwrap();
+        let duplicate = service
+            .create_chapter(chapter(&book_id, "Also one", Some(1)))
+            .await;
+        assert!(matches!(duplicate, Err(AppError::Conflict(_))));
+    }
+
+    /// `(prev, next)` neighbour ids of chapter `id` as `get_chapter` reports them
+    async fn neighbours(service: &ChapterService, id: &str) -> (Option<String>, Option<String>) {
+        let detail = service.get_chapter(id.to_string()).await.unwrap();
+        (detail.prev_chapter_id, detail.next_chapter_id)
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn three_chapter_book_links_each_position(pool: PgPool) {
+        let book_id = test_support::seed_book(&pool, "Trilogy", 0).await;
+        let service = service(&pool);
+        let mut ids = Vec::new();
+        for title in ["One", "Two", "Three"] {
+            let created = service
+                .create_chapter(chapter(&book_id, title, None))
+                .await
+                .unwrap();
+            ids.push(created.id);
+        }
+
+        assert_eq!(
+            neighbours(&service, &ids[0]).await,
+            (None, Some(ids[1].clone()))
+        );
+        assert_eq!(
+            neighbours(&service, &ids[1]).await,
+            (Some(ids[0].clone()), Some(ids[2].clone()))
+        );
+        assert_eq!(
+            neighbours(&service, &ids[2]).await,
+            (Some(ids[1].clone()), None)
+        );
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn neighbours_skip_gaps_stay_in_the_book_and_are_null_at_the_ends(pool: PgPool) {
+        let book_id = test_support::seed_book(&pool, "Gappy", 0).await;
+        let other_book = test_support::seed_book(&pool, "Other", 0).await;
+        let service = service(&pool);
+
+        let mut ids = Vec::new();
+        for num in [2, 5, 9] {
+            let created = service
+                .create_chapter(chapter(&book_id, &format!("Chapter {}", num), Some(num)))
+                .await
+                .unwrap();
+            ids.push(created.id);
+        }
+        // Numbers between and around ours in another book must not leak in
+        for num in [1, 3, 7, 10] {
+            service
+                .create_chapter(chapter(&other_book, "Elsewhere", Some(num)))
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(
+            neighbours(&service, &ids[0]).await,
+            (None, Some(ids[1].clone()))
+        );
+        assert_eq!(
+            neighbours(&service, &ids[1]).await,
+            (Some(ids[0].clone()), Some(ids[2].clone()))
+        );
+        assert_eq!(
+            neighbours(&service, &ids[2]).await,
+            (Some(ids[1].clone()), None)
+        );
+
+        // The hints are present as explicit nulls for clients at the boundaries
+        let first =
+            serde_json::to_value(service.get_chapter(ids[0].clone()).await.unwrap()).unwrap();
+        assert_eq!(first["prev_chapter_id"], serde_json::Value::Null);
+        assert!(first.get("prev_chapter_id").is_some());
+        assert_eq!(first["next_chapter_id"], ids[1]);
+    }
+}
@@ -1,18 +1,79 @@
+use futures_util::{stream, StreamExt};
+use lopdf::Document;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
 use regex::Regex;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use std::io::{Cursor, Read};
+use std::sync::LazyLock;
+use tracing::{info, warn};
 use zip::ZipArchive;
 
+use crate::config::{Config, ContentImageUrls, EpubBoilerplate};
 use crate::errors::{AppError, AppResult};
 use crate::services::storage_service::StorageService;
+use crate::utils::reading_time::word_count;
+use crate::utils::thumbnail::{webp_thumbnail, THUMBNAIL_WIDTH};
 
-/// Extracted content from EPUB/DOCX
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]+>").unwrap());
+/// Any `name="value"` pair inside a tag; `xml_attr` picks the one it wants by name
+static ATTR_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"\s([^\s=/>]+)\s*=\s*["']([^"']*)["']"#).unwrap());
+static ITEM_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<(?:\w+:)?item\b[^>]*>").unwrap());
+static ITEMREF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?itemref\b[^>]*>").unwrap());
+static ROOTFILE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"<(?:\w+:)?rootfile\b[^>]*>").unwrap());
+static NAV_TOC_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<nav\b[^>]*epub:type\s*=\s*["'][^"']*\btoc\b[^"']*["'][^>]*>(.*?)</nav>"#)
+        .unwrap()
+});
+static NAV_LINK_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"(?is)<a\b[^>]*\shref\s*=\s*["']([^"']+)["'][^>]*>(.*?)</a>"#).unwrap()
+});
+static NCX_POINT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?is)<navLabel\b[^>]*>\s*<text\b[^>]*>(.*?)</text>\s*</navLabel>\s*<content\b[^>]*\ssrc\s*=\s*["']([^"']+)["']"#,
+    )
+    .unwrap()
+});
+static TITLE_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+static H1_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?is)<h1[^>]*>(.*?)</h1>").unwrap());
+static SRC_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r#"src=["']([^"']+)["']"#).unwrap());
+static XLINK_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"xlink:href=["']([^"']+)["']"#).unwrap());
+
+/// Extracted content from EPUB/DOCX/PDF
 #[derive(Debug, Clone)]
 pub struct ExtractedContent {
-    /// HTML content with image URLs replaced to CDN URLs
+    /// HTML content with image URLs replaced to CDN URLs (or `/api/images/` paths).
+    /// For EPUBs, the documents in reading order separated by `CONTENT_CHAPTER_BREAK`
+    /// (`<!-- Chapter Break -->` by default); with `CONTENT_CHAPTER_SECTIONS` each document
+    /// is also wrapped in `<section data-chapter="n">`, numbered from 1.
     pub html_content: String,
     /// List of extracted and uploaded images
     pub images: Vec<ExtractedImage>,
+    /// Non-fatal problems, e.g. skipped documents or images the HTML never references
+    pub warnings: Vec<String>,
+}
+
+/// Extracted content split into chapters
+#[derive(Debug, Clone)]
+pub struct ExtractedChapters {
+    pub chapters: Vec<ExtractedChapter>,
+    /// Images uploaded across all chapters
+    pub images: Vec<ExtractedImage>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ExtractedChapter {
+    /// From the document's `<title>` or first `<h1>`
+    pub title: String,
+    /// HTML content with image URLs replaced to CDN URLs (or `/api/images/` paths)
+    pub html: String,
 }
 
 #[derive(Debug, Clone)]
@@ -21,10 +82,16 @@ pub struct ExtractedImage {
     pub original_path: String,
     /// CDN URL after upload
     pub cdn_url: String,
+    /// CDN URL of the WebP preview, if one could be generated
+    pub thumbnail_url: Option<String>,
     /// MIME type
     pub content_type: String,
     /// File size in bytes
     pub size: u64,
+    /// Hex SHA-256 of the image bytes, which also names the stored object
+    pub content_hash: String,
+    /// The object was already stored by an earlier upload, so cleanup must leave it alone
+    pub reused: bool,
 }
 
 /// Supported content formats
@@ -32,25 +99,59 @@ pub struct ExtractedImage {
 pub enum ContentFormat {
     Epub,
     Docx,
+    Pdf,
     Unknown,
 }
 
+/// Upper bound on decompressed content per PDF page, guards against compression bombs
+const MAX_PDF_PAGE_CONTENT: usize = 16 * 1024 * 1024;
+
 pub struct ContentExtractor {
     storage: StorageService,
+    image_urls: ContentImageUrls,
+    boilerplate: EpubBoilerplate,
+    chapter_break: String,
+    chapter_sections: bool,
+    upload_concurrency: usize,
 }
 
 impl ContentExtractor {
-    pub fn new(storage: StorageService) -> Self {
-        Self { storage }
+    pub fn new(storage: StorageService, config: &Config) -> Self {
+        Self {
+            storage,
+            image_urls: config.content_image_urls,
+            boilerplate: config.epub_boilerplate.clone(),
+            chapter_break: config.content_chapter_break.clone(),
+            chapter_sections: config.content_chapter_sections,
+            upload_concurrency: config.image_upload_concurrency,
+        }
+    }
+
+    /// What the extracted HTML points at for `image`: its CDN URL, or in relative mode
+    /// the `/api/images/{key}` path that redirects to wherever the CDN lives now
+    fn image_src(&self, image: &ExtractedImage) -> String {
+        match self.image_urls {
+            ContentImageUrls::Absolute => image.cdn_url.clone(),
+            ContentImageUrls::Relative => self
+                .storage
+                .key_from_public_url(&image.cdn_url)
+                .map(|key| format!("/api/images/{}", key))
+                .unwrap_or_else(|| image.cdn_url.clone()),
+        }
     }
 
     /// Detect format from file bytes using magic bytes
     pub fn detect_format(bytes: &[u8]) -> ContentFormat {
-        // Both EPUB and DOCX are ZIP files, need to check internal structure
         if bytes.len() < 4 {
             return ContentFormat::Unknown;
         }
 
+        if bytes.starts_with(b"%PDF") {
+            return ContentFormat::Pdf;
+        }
+
+        // Both EPUB and DOCX are ZIP files, need to check internal structure
+
         // Check ZIP magic bytes (PK..)
         if bytes[0..4] != [0x50, 0x4B, 0x03, 0x04] {
             return ContentFormat::Unknown;
@@ -75,17 +176,113 @@ impl ContentExtractor {
         ContentFormat::Unknown
     }
 
-    /// Extract content from EPUB file
-    pub async fn extract_epub(&self, bytes: &[u8], book_id: &str) -> AppResult<ExtractedContent> {
+    /// Extract content from EPUB file, leaving out cover, copyright and similar front
+    /// matter when `skip_boilerplate` is set
+    pub async fn extract_epub(
+        &self,
+        bytes: &[u8],
+        book_id: &str,
+        skip_boilerplate: bool,
+    ) -> AppResult<ExtractedContent> {
+        let mut warnings = Vec::new();
+        let (images, image_url_map) = self
+            .upload_epub_images(bytes, book_id, &mut warnings)
+            .await?;
+
+        // Second pass: extract HTML/XHTML content in reading order
+        let mut documents = Self::read_epub_documents(bytes, &mut warnings)?;
+        if skip_boilerplate {
+            self.drop_boilerplate(&mut documents, &mut warnings);
+        }
+        let html_parts: Vec<String> = documents
+            .iter()
+            // Replace image references with CDN URLs
+            .map(|(_, content)| Self::replace_image_urls(content, &image_url_map))
+            .enumerate()
+            .map(|(i, html)| {
+                if self.chapter_sections {
+                    format!("<section data-chapter=\"{}\">\n{}\n</section>", i + 1, html)
+                } else {
+                    html
+                }
+            })
+            .collect();
+
+        let html_content = html_parts.join(&self.chapter_break);
+        self.warn_unreferenced_images(&images, &html_content, &mut warnings);
+
+        Ok(ExtractedContent {
+            html_content,
+            images,
+            warnings,
+        })
+    }
+
+    /// Remove documents that look like front matter per the configured heuristics
+    fn drop_boilerplate(&self, documents: &mut Vec<(String, String)>, warnings: &mut Vec<String>) {
+        documents.retain(|(path, content)| {
+            let stem = path.rsplit('/').next().unwrap_or(path).to_lowercase();
+            let stem = stem
+                .rsplit_once('.')
+                .map_or(stem.as_str(), |(stem, _)| stem);
+            let named_like_boilerplate =
+                stem.split(|c: char| !c.is_ascii_alphanumeric())
+                    .any(|word| {
+                        self.boilerplate
+                            .patterns
+                            .iter()
+                            .any(|pattern| word.starts_with(pattern.as_str()))
+                    });
+
+            let reason = if named_like_boilerplate {
+                "file name matches a boilerplate pattern"
+            } else if word_count(content) < self.boilerplate.min_words {
+                "too short to be a chapter"
+            } else {
+                return true;
+            };
+
+            info!(path = %path, reason, "Skipping EPUB boilerplate document");
+            warnings.push(format!("Skipped {}: {}", path, reason));
+            false
+        });
+    }
+
+    /// Note images that were uploaded but that no reference in `html` points at
+    fn warn_unreferenced_images(
+        &self,
+        images: &[ExtractedImage],
+        html: &str,
+        warnings: &mut Vec<String>,
+    ) {
+        let unreferenced = images
+            .iter()
+            .filter(|image| !html.contains(&self.image_src(image)))
+            .count();
+
+        if unreferenced > 0 {
+            warnings.push(format!(
+                "{} of {} images had no match in the HTML",
+                unreferenced,
+                images.len()
+            ));
+        }
+    }
+
+    /// Upload every image in the EPUB, returning them with a path -> CDN URL map
+    async fn upload_epub_images(
+        &self,
+        bytes: &[u8],
+        book_id: &str,
+        warnings: &mut Vec<String>,
+    ) -> AppResult<(Vec<ExtractedImage>, HashMap<String, String>)> {
         let mut archive = ZipArchive::new(Cursor::new(bytes))
             .map_err(|e| AppError::BadRequest(format!("Invalid EPUB file: {}", e)))?;
 
-        let mut html_parts: Vec<String> = Vec::new();
-        let mut images: Vec<ExtractedImage> = Vec::new();
         let mut image_url_map: HashMap<String, String> = HashMap::new();
 
         // First pass: collect image data synchronously (ZipFile is not Send)
-        let mut pending_images: Vec<(String, Vec<u8>, String, u64)> = Vec::new();
+        let mut pending_images: Vec<(String, Vec<u8>, String)> = Vec::new();
         for i in 0..archive.len() {
             let mut file = archive
                 .by_index(i)
@@ -102,38 +299,227 @@ impl ContentExtractor {
                     .first_or_octet_stream()
                     .to_string();
 
-                let size = buffer.len() as u64;
-                pending_images.push((name, buffer, content_type, size));
+                pending_images.push((name, buffer, content_type));
             }
         }
         // ZipFile dropped here - archive borrow released
 
         // Now upload images asynchronously (no ZipFile held across await)
-        for (name, buffer, content_type, size) in pending_images {
-            let filename = Self::sanitize_filename(&name);
+        let images = self
+            .upload_images(book_id, pending_images, warnings)
+            .await?;
 
-            let cdn_url = self
-                .storage
-                .upload_image("content-images", book_id, &filename, buffer, &content_type)
-                .await?;
+        // Map original path to CDN URL (handle both relative and absolute paths)
+        for image in &images {
+            let name = &image.original_path;
+            let original_basename = name.rsplit('/').next().unwrap_or(name);
+            let src = self.image_src(image);
+            image_url_map.insert(name.clone(), src.clone());
+            image_url_map.insert(original_basename.to_string(), src);
+        }
+
+        Ok((images, image_url_map))
+    }
+
+    /// Upload an extracted image plus a WebP preview under `thumb/`.
+    /// The content type comes from the bytes, not the file name; buffers that aren't
+    /// images are skipped. SVGs are not rasterized, and images that fail to decode get no thumbnail.
+    /// Objects are named by content hash, so an image already stored for the book is reused.
+    async fn upload_extracted_image(
+        &self,
+        book_id: &str,
+        name: String,
+        buffer: Vec<u8>,
+        claimed_type: &str,
+    ) -> AppResult<Option<ExtractedImage>> {
+        let Some(content_type) = Self::sniff_image_type(&buffer) else {
+            warn!(path = %name, claimed_type = %claimed_type, "Skipping file that is not an image");
+            return Ok(None);
+        };
+
+        if content_type != claimed_type {
+            warn!(
+                path = %name,
+                claimed_type = %claimed_type,
+                detected_type = %content_type,
+                "Image content type does not match its name, relabelling"
+            );
+        }
+
+        let content_hash = format!("{:x}", Sha256::digest(&buffer));
+        let filename = format!("{}.{}", content_hash, Self::image_extension(content_type));
+        let size = buffer.len() as u64;
 
-            // Map original path to CDN URL (handle both relative and absolute paths)
-            let original_basename = name.rsplit('/').next().unwrap_or(&name);
-            image_url_map.insert(name.clone(), cdn_url.clone());
-            image_url_map.insert(original_basename.to_string(), cdn_url.clone());
+        let key = StorageService::image_key("content-images", book_id, &filename);
+        if self.storage.object_exists(&key).await? {
+            let thumbnail_key = StorageService::image_key(
+                "content-images",
+                book_id,
+                &Self::thumbnail_name(&filename),
+            );
+            let thumbnail_url = match self.storage.object_exists(&thumbnail_key).await {
+                Ok(true) => Some(self.storage.get_public_url(&thumbnail_key)),
+                _ => None,
+            };
 
-            images.push(ExtractedImage {
+            return Ok(Some(ExtractedImage {
                 original_path: name,
-                cdn_url,
-                content_type,
+                cdn_url: self.storage.get_public_url(&key),
+                thumbnail_url,
+                content_type: content_type.to_string(),
                 size,
-            });
+                content_hash,
+                reused: true,
+            }));
+        }
+
+        let thumbnail = if content_type == "image/svg+xml" {
+            None
+        } else {
+            let bytes = buffer.clone();
+            tokio::task::spawn_blocking(move || webp_thumbnail(&bytes, THUMBNAIL_WIDTH))
+                .await
+                .ok()
+                .flatten()
+        };
+
+        let cdn_url = self
+            .storage
+            .upload_image("content-images", book_id, &filename, buffer, content_type)
+            .await?;
+
+        let thumbnail_url = match thumbnail {
+            Some(thumbnail) => self.upload_thumbnail(book_id, &filename, thumbnail).await,
+            None => None,
+        };
+
+        Ok(Some(ExtractedImage {
+            original_path: name,
+            cdn_url,
+            thumbnail_url,
+            content_type: content_type.to_string(),
+            size,
+            content_hash,
+            reused: false,
+        }))
+    }
+
+    /// Upload `(name, bytes, claimed type)` images, `upload_concurrency` at a time.
+    /// The result keeps the input order whatever order the uploads finish in. Every upload
+    /// runs to completion, so when one fails the others that succeeded are known and deleted
+    /// again, leaving nothing behind from a half-finished extraction.
+    async fn upload_images(
+        &self,
+        book_id: &str,
+        pending: Vec<(String, Vec<u8>, String)>,
+        warnings: &mut Vec<String>,
+    ) -> AppResult<Vec<ExtractedImage>> {
+        let results: Vec<(String, AppResult<Option<ExtractedImage>>)> = stream::iter(pending)
+            .map(|(name, buffer, claimed_type)| async move {
+                let result = self
+                    .upload_extracted_image(book_id, name.clone(), buffer, &claimed_type)
+                    .await;
+                (name, result)
+            })
+            .buffered(self.upload_concurrency)
+            .collect()
+            .await;
+
+        let mut images = Vec::new();
+        let mut failure = None;
+        for (name, result) in results {
+            match result {
+                Ok(Some(image)) => images.push(image),
+                Ok(None) => warnings.push(format!("Skipped {}: not a recognised image", name)),
+                Err(e) => {
+                    failure.get_or_insert(e);
+                }
+            }
         }
 
-        // Second pass: extract HTML/XHTML content
+        if let Some(e) = failure {
+            self.delete_images(&images).await;
+            return Err(e);
+        }
+
+        Ok(images)
+    }
+
+    /// Best-effort removal of extracted images and their thumbnails from storage.
+    /// Reused objects belong to earlier uploads and are kept.
+    pub async fn delete_images(&self, images: &[ExtractedImage]) {
+        let keys: Vec<String> = images
+            .iter()
+            .filter(|image| !image.reused)
+            .flat_map(|image| std::iter::once(&image.cdn_url).chain(&image.thumbnail_url))
+            .filter_map(|url| self.storage.key_from_public_url(url))
+            .collect();
+
+        if keys.is_empty() {
+            return;
+        }
+
+        match self.storage.delete_files(&keys).await {
+            Ok(deleted) => info!(deleted, keys = ?keys, "Cleaned up uploaded images"),
+            Err(e) => warn!(error = ?e, keys = ?keys, "Failed to clean up uploaded images"),
+        }
+    }
+
+    /// Upload a thumbnail next to its image; failures are logged rather than failing the upload
+    async fn upload_thumbnail(
+        &self,
+        book_id: &str,
+        filename: &str,
+        thumbnail: Vec<u8>,
+    ) -> Option<String> {
+        match self
+            .storage
+            .upload_image(
+                "content-images",
+                book_id,
+                &Self::thumbnail_name(filename),
+                thumbnail,
+                "image/webp",
+            )
+            .await
+        {
+            Ok(url) => Some(url),
+            Err(e) => {
+                warn!(error = ?e, filename = %filename, "Failed to upload thumbnail");
+                None
+            }
+        }
+    }
+
+    /// Read the EPUB's XHTML documents with their archive paths, following the OPF spine
+    /// when one can be found
+    fn read_epub_documents(
+        bytes: &[u8],
+        warnings: &mut Vec<String>,
+    ) -> AppResult<Vec<(String, String)>> {
         let mut archive = ZipArchive::new(Cursor::new(bytes))
             .map_err(|e| AppError::Internal(format!("Failed to reopen archive: {}", e)))?;
 
+        let mut documents = Vec::new();
+
+        if let Some(spine_paths) = Self::epub_spine_paths(&mut archive) {
+            for path in spine_paths {
+                let Ok(mut file) = archive.by_name(&path) else {
+                    warn!(path = %path, "EPUB spine references a missing document");
+                    warnings.push(format!("Skipped missing chapter document {}", path));
+                    continue;
+                };
+
+                let mut content = String::new();
+                file.read_to_string(&mut content)
+                    .map_err(|e| AppError::Internal(format!("Failed to read content: {}", e)))?;
+                documents.push((path, content));
+            }
+
+            return Ok(documents);
+        }
+
+        // No usable OPF: fall back to archive order
         for i in 0..archive.len() {
             let mut file = archive
                 .by_index(i)
@@ -145,31 +531,200 @@ impl ContentExtractor {
                 let mut content = String::new();
                 file.read_to_string(&mut content)
                     .map_err(|e| AppError::Internal(format!("Failed to read content: {}", e)))?;
+                documents.push((name, content));
+            }
+        }
+
+        Ok(documents)
+    }
 
-                // Replace image references with CDN URLs
-                let updated_content = Self::replace_image_urls(&content, &image_url_map);
-                html_parts.push(updated_content);
+    /// Resolve the archive paths of the spine documents via META-INF/container.xml and the OPF
+    fn epub_spine_paths(archive: &mut ZipArchive<Cursor<&[u8]>>) -> Option<Vec<String>> {
+        let (opf_path, opf) = Self::epub_package(archive)?;
+        let opf_dir = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+        // manifest id -> (href, properties)
+        let manifest: HashMap<String, (String, String)> = ITEM_RE
+            .find_iter(&opf)
+            .filter_map(|tag| {
+                let tag = tag.as_str();
+                let id = Self::xml_attr(tag, "id")?;
+                let href = Self::xml_attr(tag, "href")?;
+                let properties = Self::xml_attr(tag, "properties").unwrap_or_default();
+                Some((id, (href, properties)))
+            })
+            .collect();
+
+        let paths: Vec<String> = ITEMREF_RE
+            .find_iter(&opf)
+            .filter_map(|tag| Self::xml_attr(tag.as_str(), "idref"))
+            .filter_map(|idref| manifest.get(&idref))
+            .filter(|(_, properties)| !properties.split_whitespace().any(|p| p == "nav"))
+            .map(|(href, _)| Self::resolve_archive_path(opf_dir, href))
+            .collect();
+
+        if paths.is_empty() {
+            None
+        } else {
+            Some(paths)
+        }
+    }
+
+    /// Path and contents of the OPF package document named by META-INF/container.xml
+    fn epub_package(archive: &mut ZipArchive<Cursor<&[u8]>>) -> Option<(String, String)> {
+        let container = Self::read_archive_text(archive, "META-INF/container.xml")?;
+        let opf_path = ROOTFILE_RE
+            .find_iter(&container)
+            .find_map(|tag| Self::xml_attr(tag.as_str(), "full-path"))?;
+        let opf = Self::read_archive_text(archive, &opf_path)?;
+        Some((opf_path, opf))
+    }
+
+    /// Chapter titles keyed by archive path, read from the EPUB 3 nav document or, failing
+    /// that, the EPUB 2 NCX. Empty when the book has neither.
+    fn epub_toc_titles(bytes: &[u8]) -> HashMap<String, String> {
+        let Ok(mut archive) = ZipArchive::new(Cursor::new(bytes)) else {
+            return HashMap::new();
+        };
+
+        // Prefer what the OPF manifest declares, then look for the conventional file names
+        let mut nav_path = None;
+        let mut ncx_path = None;
+        if let Some((opf_path, opf)) = Self::epub_package(&mut archive) {
+            let opf_dir = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+            for tag in ITEM_RE.find_iter(&opf).map(|tag| tag.as_str()) {
+                let Some(href) = Self::xml_attr(tag, "href") else {
+                    continue;
+                };
+                let properties = Self::xml_attr(tag, "properties").unwrap_or_default();
+                if properties.split_whitespace().any(|p| p == "nav") {
+                    nav_path.get_or_insert_with(|| Self::resolve_archive_path(opf_dir, &href));
+                } else if Self::xml_attr(tag, "media-type").as_deref()
+                    == Some("application/x-dtbncx+xml")
+                {
+                    ncx_path.get_or_insert_with(|| Self::resolve_archive_path(opf_dir, &href));
+                }
             }
         }
+        let names: Vec<String> = archive.file_names().map(str::to_string).collect();
+        let nav_path = nav_path.or_else(|| {
+            names
+                .iter()
+                .find(|name| name.to_lowercase().ends_with("nav.xhtml"))
+                .cloned()
+        });
+        let ncx_path = ncx_path.or_else(|| {
+            names
+                .iter()
+                .find(|name| name.to_lowercase().ends_with(".ncx"))
+                .cloned()
+        });
+
+        let nav_titles = nav_path
+            .and_then(|path| {
+                let nav = Self::read_archive_text(&mut archive, &path)?;
+                Some(Self::nav_titles(&path, &nav))
+            })
+            .unwrap_or_default();
+        if !nav_titles.is_empty() {
+            return nav_titles;
+        }
 
-        let html_content = html_parts.join("\n\n<!-- Chapter Break -->\n\n");
+        ncx_path
+            .and_then(|path| {
+                let ncx = Self::read_archive_text(&mut archive, &path)?;
+                Some(Self::ncx_titles(&path, &ncx))
+            })
+            .unwrap_or_default()
+    }
 
-        Ok(ExtractedContent {
-            html_content,
-            images,
-        })
+    /// Titles from the links of an EPUB 3 nav document's `toc` list
+    fn nav_titles(nav_path: &str, nav: &str) -> HashMap<String, String> {
+        let toc = NAV_TOC_RE
+            .captures(nav)
+            .and_then(|cap| cap.get(1))
+            .map_or(nav, |toc| toc.as_str());
+
+        let entries = NAV_LINK_RE
+            .captures_iter(toc)
+            .map(|cap| (cap[1].to_string(), cap[2].to_string()));
+        Self::toc_entries_to_titles(nav_path, entries)
+    }
+
+    /// Titles from the `navPoint` labels of an EPUB 2 NCX
+    fn ncx_titles(ncx_path: &str, ncx: &str) -> HashMap<String, String> {
+        let entries = NCX_POINT_RE
+            .captures_iter(ncx)
+            .map(|cap| (cap[2].to_string(), cap[1].to_string()));
+        Self::toc_entries_to_titles(ncx_path, entries)
+    }
+
+    /// Resolve `(href, label)` pairs relative to the TOC file. A document keeps the first
+    /// title pointing into it, since later entries usually target sections within it.
+    fn toc_entries_to_titles(
+        toc_path: &str,
+        entries: impl Iterator<Item = (String, String)>,
+    ) -> HashMap<String, String> {
+        let toc_dir = toc_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+        let mut titles = HashMap::new();
+
+        for (href, label) in entries {
+            let stripped = TAG_RE.replace_all(&label, "");
+            let title = quick_xml::escape::unescape(&stripped).unwrap_or(stripped.clone());
+            let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+            if title.is_empty() || href.contains("://") {
+                continue;
+            }
+            titles
+                .entry(Self::resolve_archive_path(toc_dir, &href))
+                .or_insert(title);
+        }
+
+        titles
+    }
+
+    fn read_archive_text(archive: &mut ZipArchive<Cursor<&[u8]>>, path: &str) -> Option<String> {
+        let mut file = archive.by_name(path).ok()?;
+        let mut content = String::new();
+        file.read_to_string(&mut content).ok()?;
+        Some(content)
+    }
+
+    fn xml_attr(tag: &str, name: &str) -> Option<String> {
+        ATTR_RE
+            .captures_iter(tag)
+            .find(|cap| &cap[1] == name)
+            .map(|cap| cap[2].to_string())
+    }
+
+    /// Join an OPF-relative href onto the OPF directory, dropping fragments and resolving `..`
+    fn resolve_archive_path(base_dir: &str, href: &str) -> String {
+        let href = href.split('#').next().unwrap_or(href);
+        let mut segments: Vec<&str> = base_dir.split('/').filter(|s| !s.is_empty()).collect();
+
+        for segment in href.split('/') {
+            match segment {
+                "" | "." => {}
+                ".." => {
+                    segments.pop();
+                }
+                segment => segments.push(segment),
+            }
+        }
+
+        segments.join("/")
     }
 
     /// Extract content from DOCX file
     pub async fn extract_docx(&self, bytes: &[u8], book_id: &str) -> AppResult<ExtractedContent> {
+        let mut warnings = Vec::new();
         let mut archive = ZipArchive::new(Cursor::new(bytes))
             .map_err(|e| AppError::BadRequest(format!("Invalid DOCX file: {}", e)))?;
 
-        let mut images: Vec<ExtractedImage> = Vec::new();
         let mut image_url_map: HashMap<String, String> = HashMap::new();
 
         // First pass: collect image data synchronously (ZipFile is not Send)
-        let mut pending_images: Vec<(String, Vec<u8>, String, u64)> = Vec::new();
+        let mut pending_images: Vec<(String, Vec<u8>, String)> = Vec::new();
         for i in 0..archive.len() {
             let mut file = archive
                 .by_index(i)
@@ -186,58 +741,235 @@ impl ContentExtractor {
                     .first_or_octet_stream()
                     .to_string();
 
-                let size = buffer.len() as u64;
-                pending_images.push((name, buffer, content_type, size));
+                pending_images.push((name, buffer, content_type));
             }
         }
         // ZipFile dropped here - archive borrow released
 
         // Now upload images asynchronously (no ZipFile held across await)
-        for (name, buffer, content_type, size) in pending_images {
-            let filename = Self::sanitize_filename(&name);
-
-            let cdn_url = self
-                .storage
-                .upload_image("content-images", book_id, &filename, buffer, &content_type)
-                .await?;
-
-            let original_basename = name.rsplit('/').next().unwrap_or(&name);
-            image_url_map.insert(name.clone(), cdn_url.clone());
-            image_url_map.insert(original_basename.to_string(), cdn_url.clone());
+        let images = self
+            .upload_images(book_id, pending_images, &mut warnings)
+            .await?;
 
-            images.push(ExtractedImage {
-                original_path: name,
-                cdn_url,
-                content_type,
-                size,
-            });
+        for image in &images {
+            let name = &image.original_path;
+            let original_basename = name.rsplit('/').next().unwrap_or(name);
+            let src = self.image_src(image);
+            image_url_map.insert(name.clone(), src.clone());
+            image_url_map.insert(original_basename.to_string(), src);
         }
 
         // Extract document.xml and convert to HTML
         let mut archive: ZipArchive<Cursor<&[u8]>> = ZipArchive::new(Cursor::new(bytes))
             .map_err(|e| AppError::Internal(format!("Failed to reopen archive: {}", e)))?;
 
-        let mut document_xml = String::new();
-        if let Ok(mut file) = archive.by_name("word/document.xml") {
-            let _ = file.read_to_string(&mut document_xml);
-        }
+        let document_xml = Self::read_archive_text(&mut archive, "word/document.xml")
+            .unwrap_or_else(|| {
+                warnings.push("DOCX has no readable word/document.xml".to_string());
+                String::new()
+            });
+        let rels_xml = Self::read_archive_text(&mut archive, "word/_rels/document.xml.rels")
+            .unwrap_or_default();
+        let numbering_xml =
+            Self::read_archive_text(&mut archive, "word/numbering.xml").unwrap_or_default();
+
+        // Resolve image relationship ids to the uploaded CDN URLs
+        let image_urls: HashMap<String, String> = Self::docx_relationships(&rels_xml)
+            .into_iter()
+            .filter_map(|(rel_id, target)| {
+                let path = Self::resolve_archive_path("word", &target);
+                let basename = target.rsplit('/').next().unwrap_or(&target);
+                image_url_map
+                    .get(&path)
+                    .or_else(|| image_url_map.get(basename))
+                    .map(|url| (rel_id, url.clone()))
+            })
+            .collect();
 
         // Convert DOCX XML to simple HTML
-        let html_content = Self::docx_xml_to_html(&document_xml, &image_url_map);
+        let html_content = Self::docx_xml_to_html(
+            &document_xml,
+            &image_urls,
+            &Self::docx_list_kinds(&numbering_xml),
+            &mut warnings,
+        );
+        self.warn_unreferenced_images(&images, &html_content, &mut warnings);
+
+        Ok(ExtractedContent {
+            html_content,
+            images,
+            warnings,
+        })
+    }
+
+    /// Extract content from PDF file
+    pub async fn extract_pdf(&self, bytes: &[u8], book_id: &str) -> AppResult<ExtractedContent> {
+        let mut warnings = Vec::new();
+        // First pass: parse synchronously and copy out text and image data (Document is not kept across awaits)
+        let mut page_texts: Vec<(u32, String)> = Vec::new();
+        let mut pending_images: Vec<(u32, String, Vec<u8>, String)> = Vec::new();
+        {
+            let document = Document::load_mem(bytes)
+                .map_err(|e| AppError::BadRequest(format!("Invalid PDF file: {}", e)))?;
+
+            for (page_num, page_id) in document.get_pages() {
+                match document.extract_text_with_limit(&[page_num], MAX_PDF_PAGE_CONTENT) {
+                    Ok(text) => page_texts.push((page_num, text)),
+                    Err(e) => {
+                        warn!(page = page_num, error = %e, "Failed to extract PDF page text");
+                        warnings.push(format!("Skipped text of page {}: {}", page_num, e));
+                    }
+                }
+
+                let page_images = match document.get_page_images(page_id) {
+                    Ok(page_images) => page_images,
+                    Err(e) => {
+                        warn!(page = page_num, error = %e, "Failed to read PDF page images");
+                        warnings.push(format!("Skipped images of page {}: {}", page_num, e));
+                        continue;
+                    }
+                };
+
+                for image in page_images {
+                    // Only passthrough encodings can be uploaded as-is; raw bitmaps are skipped
+                    let Some((extension, content_type)) =
+                        Self::pdf_image_type(image.filters.as_deref())
+                    else {
+                        warn!(page = page_num, filters = ?image.filters, "Skipping undecodable PDF image");
+                        warnings.push(format!(
+                            "Skipped an image on page {} in an unsupported encoding",
+                            page_num
+                        ));
+                        continue;
+                    };
+
+                    let name = format!("page{}_img{}.{}", page_num, image.id.0, extension);
+                    pending_images.push((
+                        page_num,
+                        name,
+                        image.content.to_vec(),
+                        content_type.to_string(),
+                    ));
+                }
+            }
+        }
+
+        // Now upload images asynchronously
+        let image_pages: HashMap<String, u32> = pending_images
+            .iter()
+            .map(|(page_num, name, _, _)| (name.clone(), *page_num))
+            .collect();
+        let pending_images = pending_images
+            .into_iter()
+            .map(|(_, name, buffer, content_type)| (name, buffer, content_type))
+            .collect();
+        let images = self
+            .upload_images(book_id, pending_images, &mut warnings)
+            .await?;
+
+        let mut page_image_urls: HashMap<u32, Vec<String>> = HashMap::new();
+        for image in &images {
+            if let Some(&page_num) = image_pages.get(&image.original_path) {
+                page_image_urls
+                    .entry(page_num)
+                    .or_default()
+                    .push(self.image_src(image));
+            }
+        }
+
+        // Second pass: one paragraph per text line, page images after the page's text
+        let mut html_content = String::new();
+        for (page_num, text) in page_texts {
+            for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+                html_content.push_str(&format!("<p>{}</p>\n", Self::escape_html(line)));
+            }
+
+            for url in page_image_urls.remove(&page_num).unwrap_or_default() {
+                html_content.push_str(&format!(r#"<img src="{}" alt="image" />"#, url));
+                html_content.push('\n');
+            }
+        }
 
         Ok(ExtractedContent {
             html_content,
             images,
+            warnings,
         })
     }
 
-    /// Auto-detect format and extract content
-    pub async fn extract(&self, bytes: &[u8], book_id: &str) -> AppResult<ExtractedContent> {
+    /// Auto-detect format and extract content as one record per chapter.
+    /// EPUB documents map to chapters in spine order, titled from the book's table of contents
+    /// and otherwise from each document's own `<title>`; DOCX and PDF yield a single chapter.
+    pub async fn extract_chapters(
+        &self,
+        bytes: &[u8],
+        book_id: &str,
+        skip_boilerplate: bool,
+    ) -> AppResult<ExtractedChapters> {
+        if Self::detect_format(bytes) != ContentFormat::Epub {
+            let extracted = self.extract(bytes, book_id, skip_boilerplate).await?;
+            let title = Self::derive_title(&extracted.html_content)
+                .unwrap_or_else(|| "Chapter 1".to_string());
+
+            return Ok(ExtractedChapters {
+                chapters: vec![ExtractedChapter {
+                    title,
+                    html: extracted.html_content,
+                }],
+                images: extracted.images,
+                warnings: extracted.warnings,
+            });
+        }
+
+        let mut warnings = Vec::new();
+        let (images, image_url_map) = self
+            .upload_epub_images(bytes, book_id, &mut warnings)
+            .await?;
+
+        let mut toc_titles = Self::epub_toc_titles(bytes);
+        let mut documents = Self::read_epub_documents(bytes, &mut warnings)?;
+        if skip_boilerplate {
+            self.drop_boilerplate(&mut documents, &mut warnings);
+        }
+        let chapters: Vec<ExtractedChapter> = documents
+            .iter()
+            .enumerate()
+            .map(|(i, (path, content))| ExtractedChapter {
+                title: toc_titles
+                    .remove(path)
+                    .or_else(|| Self::derive_title(content))
+                    .unwrap_or_else(|| format!("Chapter {}", i + 1)),
+                html: Self::replace_image_urls(content, &image_url_map),
+            })
+            .collect();
+
+        let all_html: String = chapters
+            .iter()
+            .map(|chapter| chapter.html.as_str())
+            .collect();
+        self.warn_unreferenced_images(&images, &all_html, &mut warnings);
+
+        Ok(ExtractedChapters {
+            chapters,
+            images,
+            warnings,
+        })
+    }
+
+    /// Auto-detect format and extract content. `skip_boilerplate` only affects EPUBs,
+    /// the one format split into separate documents.
+    pub async fn extract(
+        &self,
+        bytes: &[u8],
+        book_id: &str,
+        skip_boilerplate: bool,
+    ) -> AppResult<ExtractedContent> {
         match Self::detect_format(bytes) {
-            ContentFormat::Epub => self.extract_epub(bytes, book_id).await,
+            ContentFormat::Epub => self.extract_epub(bytes, book_id, skip_boilerplate).await,
             ContentFormat::Docx => self.extract_docx(bytes, book_id).await,
+            ContentFormat::Pdf => self.extract_pdf(bytes, book_id).await,
             ContentFormat::Unknown => Err(AppError::BadRequest(
-                "Unsupported file format. Only EPUB and DOCX are supported.".to_string(),
+                "Unsupported file format. Only EPUB, DOCX and PDF are supported.".to_string(),
             )),
         }
     }
@@ -252,6 +984,142 @@ impl ContentExtractor {
             || lower.ends_with(".svg")
     }
 
+    /// Identify an image format from its magic bytes
+    pub fn sniff_image_type(bytes: &[u8]) -> Option<&'static str> {
+        if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+            return Some("image/jpeg");
+        }
+        if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            return Some("image/png");
+        }
+        if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+            return Some("image/gif");
+        }
+        if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+            return Some("image/webp");
+        }
+        if bytes.starts_with(&[0x00, 0x00, 0x00, 0x0C, b'j', b'P', b' ', b' '])
+            || bytes.starts_with(&[0xFF, 0x4F, 0xFF, 0x51])
+        {
+            return Some("image/jp2");
+        }
+
+        if Self::is_safe_svg(bytes) {
+            return Some("image/svg+xml");
+        }
+
+        None
+    }
+
+    /// Whether `bytes` is an SVG document: only an XML declaration, doctype, comments and
+    /// whitespace may precede the root element, which must be `<svg>`. Documents that could
+    /// run script (`<script>`, `<foreignObject>`, `on*` handlers, `javascript:` links) are
+    /// refused, since stored images are served from the CDN origin. So are doctypes with an
+    /// internal subset and custom entity references: the parser never expands them, a
+    /// browser does.
+    fn is_safe_svg(bytes: &[u8]) -> bool {
+        let Ok(text) = std::str::from_utf8(bytes) else {
+            return false;
+        };
+        let mut reader = Reader::from_str(text.trim_start_matches('\u{feff}'));
+        let mut seen_root = false;
+
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(element)) | Ok(Event::Empty(element)) => {
+                    if !seen_root && element.local_name().as_ref() != b"svg" {
+                        return false;
+                    }
+                    seen_root = true;
+                    if Self::is_scripting_element(&element) {
+                        return false;
+                    }
+                }
+                Ok(Event::Text(text)) if !seen_root => {
+                    if !text.iter().all(u8::is_ascii_whitespace) {
+                        return false;
+                    }
+                }
+                // Only the predefined and character references unescape
+                Ok(Event::Text(text)) => {
+                    if text.unescape().is_err() {
+                        return false;
+                    }
+                }
+                Ok(Event::DocType(doctype)) => {
+                    if doctype.contains(&b'[') {
+                        return false;
+                    }
+                }
+                Ok(Event::Decl(_)) | Ok(Event::Comment(_)) => {}
+                Ok(Event::Eof) => return seen_root,
+                Ok(_) if !seen_root => return false,
+                Ok(_) => {}
+                Err(_) => return false,
+            }
+        }
+    }
+
+    /// Attribute values are checked as a browser reads them: unescaped, with whitespace
+    /// and control characters dropped. Attributes that don't parse count as scripting.
+    fn is_scripting_element(element: &BytesStart) -> bool {
+        let name = element.local_name().as_ref().to_ascii_lowercase();
+        if name == b"script" || name == b"foreignobject" {
+            return true;
+        }
+
+        element.attributes().any(|attr| {
+            let Ok(attr) = attr else {
+                return true;
+            };
+            let key = attr.key.local_name().as_ref().to_ascii_lowercase();
+            let Ok(value) = attr.unescape_value() else {
+                return true;
+            };
+            let value: String = value
+                .chars()
+                .filter(|c| !c.is_whitespace() && !c.is_control())
+                .collect::<String>()
+                .to_lowercase();
+            key.starts_with(b"on") || value.contains("javascript:")
+        })
+    }
+
+    /// Chapter title from the document's `<title>`, falling back to its first `<h1>`
+    fn derive_title(html: &str) -> Option<String> {
+        [&TITLE_RE, &H1_RE].iter().find_map(|re| {
+            let inner = re.captures(html)?;
+            let text = TAG_RE.replace_all(&inner[1], "");
+            let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            if text.is_empty() {
+                None
+            } else {
+                Some(text)
+            }
+        })
+    }
+
+    /// Map a PDF image stream's filter chain to a file extension and MIME type
+    fn pdf_image_type(filters: Option<&[String]>) -> Option<(&'static str, &'static str)> {
+        // Stream content is still encoded, so only a lone image codec filter yields a usable file
+        match filters? {
+            [filter] if filter == "DCTDecode" => Some(("jpg", "image/jpeg")),
+            [filter] if filter == "JPXDecode" => Some(("jp2", "image/jp2")),
+            _ => None,
+        }
+    }
+
+    /// Escape extracted plain text; `&` goes first so generated entities aren't re-escaped
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
+
+    /// Chapter documents for the archive-order fallback; navigation files are read for
+    /// titles by `epub_toc_titles` instead
     fn is_content_file(name: &str) -> bool {
         let lower = name.to_lowercase();
         (lower.ends_with(".html") || lower.ends_with(".xhtml") || lower.ends_with(".htm"))
@@ -259,19 +1127,30 @@ impl ContentExtractor {
             && !lower.contains("nav")
     }
 
-    fn sanitize_filename(path: &str) -> String {
-        let basename = path.rsplit('/').next().unwrap_or(path);
-        // Add timestamp to prevent collisions
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        format!("{}_{}", timestamp, basename.replace(' ', "_"))
+    /// File extension for a sniffed image type
+    fn image_extension(content_type: &str) -> &'static str {
+        match content_type {
+            "image/jpeg" => "jpg",
+            "image/png" => "png",
+            "image/gif" => "gif",
+            "image/webp" => "webp",
+            "image/jp2" => "jp2",
+            "image/svg+xml" => "svg",
+            _ => "bin",
+        }
+    }
+
+    /// Name of the WebP preview stored next to `filename`
+    fn thumbnail_name(filename: &str) -> String {
+        let stem = filename.rsplit_once('.').map_or(filename, |(stem, _)| stem);
+        format!("thumb/{}.webp", stem)
     }
 
     fn replace_image_urls(content: &str, url_map: &HashMap<String, String>) -> String {
         let mut result = content.to_string();
 
         // Replace src="..." attributes
-        let src_re = Regex::new(r#"src=["']([^"']+)["']"#).unwrap();
-        for cap in src_re.captures_iter(content) {
+        for cap in SRC_RE.captures_iter(content) {
             let original = &cap[1];
             let basename = original.rsplit('/').next().unwrap_or(original);
 
@@ -281,8 +1160,7 @@ impl ContentExtractor {
         }
 
         // Replace xlink:href for SVG
-        let xlink_re = Regex::new(r#"xlink:href=["']([^"']+)["']"#).unwrap();
-        for cap in xlink_re.captures_iter(content) {
+        for cap in XLINK_RE.captures_iter(content) {
             let original = &cap[1];
             let basename = original.rsplit('/').next().unwrap_or(original);
 
@@ -294,65 +1172,654 @@ impl ContentExtractor {
         result
     }
 
-    fn docx_xml_to_html(xml: &str, image_url_map: &HashMap<String, String>) -> String {
+    fn docx_xml_to_html(
+        xml: &str,
+        image_urls: &HashMap<String, String>,
+        list_kinds: &HashMap<(String, usize), &'static str>,
+        warnings: &mut Vec<String>,
+    ) -> String {
+        let mut reader = Reader::from_str(xml);
         let mut html = String::new();
+        let mut open_lists: Vec<&'static str> = Vec::new();
+        // A stack because text boxes nest paragraphs inside runs
+        let mut paragraphs: Vec<DocxParagraph> = Vec::new();
+        let mut run = DocxRun::default();
+        let mut in_run = false;
+        let mut in_text = false;
+
+        loop {
+            let event = match reader.read_event() {
+                Ok(event) => event,
+                Err(e) => {
+                    warn!(error = %e, "Malformed DOCX document.xml, output may be truncated");
+                    warnings.push(format!(
+                        "Malformed DOCX document, content after byte {} was dropped",
+                        reader.buffer_position()
+                    ));
+                    break;
+                }
+            };
 
-        // Simple DOCX to HTML conversion
-        // Extract paragraphs <w:p> and text runs <w:t>
-        let para_re = Regex::new(r"<w:p[^>]*>(.*?)</w:p>").unwrap();
-        let text_re = Regex::new(r"<w:t[^>]*>([^<]*)</w:t>").unwrap();
-        let bold_re = Regex::new(r"<w:b/>").unwrap();
-        let italic_re = Regex::new(r"<w:i/>").unwrap();
-        let image_re = Regex::new(r#"<a:blip[^>]*r:embed="([^"]+)"[^>]*/>"#).unwrap();
-
-        for para_cap in para_re.captures_iter(xml) {
-            let para_content = &para_cap[1];
-            let mut para_html = String::new();
-
-            // Check for formatting
-            let is_bold = bold_re.is_match(para_content);
-            let is_italic = italic_re.is_match(para_content);
-
-            // Extract text
-            for text_cap in text_re.captures_iter(para_content) {
-                para_html.push_str(&text_cap[1]);
+            match &event {
+                Event::Start(e) | Event::Empty(e) => {
+                    let is_start = matches!(event, Event::Start(_));
+                    match e.local_name().as_ref() {
+                        b"p" if is_start => paragraphs.push(DocxParagraph::default()),
+                        b"p" => Self::finish_docx_paragraph(
+                            &mut html,
+                            &mut open_lists,
+                            DocxParagraph::default(),
+                            list_kinds,
+                        ),
+                        b"pStyle" => {
+                            if let Some(paragraph) = paragraphs.last_mut() {
+                                paragraph.style = Self::docx_attr(e, b"w:val");
+                            }
+                        }
+                        b"ilvl" => {
+                            if let Some(paragraph) = paragraphs.last_mut() {
+                                paragraph.level = Self::docx_attr(e, b"w:val")
+                                    .and_then(|v| v.parse().ok())
+                                    .unwrap_or(0);
+                            }
+                        }
+                        b"numId" => {
+                            if let Some(paragraph) = paragraphs.last_mut() {
+                                paragraph.num_id = Self::docx_attr(e, b"w:val");
+                            }
+                        }
+                        b"r" if is_start => {
+                            run = DocxRun::default();
+                            in_run = true;
+                        }
+                        b"b" => run.bold = Self::docx_toggle(e),
+                        b"i" => run.italic = Self::docx_toggle(e),
+                        b"u" => run.underline = Self::docx_toggle(e),
+                        b"t" => in_text = is_start,
+                        b"br" if in_run => {
+                            if let Some(paragraph) = paragraphs.last_mut() {
+                                paragraph.html.push_str("<br />");
+                            }
+                        }
+                        // w:tab also defines tab stops in paragraph properties; only runs emit text
+                        b"tab" if in_run => {
+                            if let Some(paragraph) = paragraphs.last_mut() {
+                                paragraph.html.push(' ');
+                            }
+                        }
+                        b"blip" => {
+                            let url = Self::docx_attr(e, b"r:embed")
+                                .and_then(|rel_id| image_urls.get(&rel_id));
+                            if let (Some(url), Some(paragraph)) = (url, paragraphs.last_mut()) {
+                                paragraph
+                                    .html
+                                    .push_str(&format!(r#"<img src="{}" alt="image" />"#, url));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                Event::Text(text) if in_text => {
+                    let Ok(text) = text.unescape() else {
+                        continue;
+                    };
+                    if let Some(paragraph) = paragraphs.last_mut() {
+                        paragraph
+                            .html
+                            .push_str(&run.wrap(&Self::escape_html(&text)));
+                    }
+                }
+                Event::End(e) => match e.local_name().as_ref() {
+                    b"t" => in_text = false,
+                    b"r" => in_run = false,
+                    b"p" => {
+                        if let Some(paragraph) = paragraphs.pop() {
+                            Self::finish_docx_paragraph(
+                                &mut html,
+                                &mut open_lists,
+                                paragraph,
+                                list_kinds,
+                            );
+                        }
+                    }
+                    _ => {}
+                },
+                Event::Eof => break,
+                _ => {}
             }
+        }
 
-            // Check for images
-            for img_cap in image_re.captures_iter(para_content) {
-                let rel_id = &img_cap[1];
-                // Try to find corresponding image
-                for (path, url) in image_url_map {
-                    if path.contains(rel_id)
-                        || rel_id.contains(path.rsplit('/').next().unwrap_or(""))
-                    {
-                        para_html.push_str(&format!(r#"<img src="{}" alt="image" />"#, url));
-                        break;
+        Self::close_docx_lists(&mut html, &mut open_lists, 0);
+
+        if html.is_empty() {
+            // Fallback: just strip all XML tags
+            html = TAG_RE.replace_all(xml, "").to_string();
+        }
+
+        html
+    }
+
+    /// Emit a completed paragraph as a heading, list item or plain `<p>`
+    fn finish_docx_paragraph(
+        html: &mut String,
+        open_lists: &mut Vec<&'static str>,
+        paragraph: DocxParagraph,
+        list_kinds: &HashMap<(String, usize), &'static str>,
+    ) {
+        let style = paragraph.style.as_deref().unwrap_or("");
+
+        if let Some(kind) = Self::docx_list_kind(&paragraph, list_kinds) {
+            let depth = paragraph.level + 1;
+            Self::close_docx_lists(html, open_lists, depth);
+
+            if open_lists.len() == depth && open_lists.last() != Some(&kind) {
+                Self::close_docx_lists(html, open_lists, depth - 1);
+            }
+
+            if open_lists.len() == depth {
+                html.push_str("</li>\n<li>");
+            } else {
+                while open_lists.len() < depth {
+                    html.push_str(&format!("<{}>\n<li>", kind));
+                    open_lists.push(kind);
+                }
+            }
+
+            html.push_str(&paragraph.html);
+            return;
+        }
+
+        Self::close_docx_lists(html, open_lists, 0);
+
+        match Self::docx_heading_level(style) {
+            Some(level) => html.push_str(&format!(
+                "<h{level}>{}</h{level}>\n",
+                paragraph.html,
+                level = level
+            )),
+            None => html.push_str(&format!("<p>{}</p>\n", paragraph.html)),
+        }
+    }
+
+    /// Close open lists (and their pending `<li>`) until only `depth` remain
+    fn close_docx_lists(html: &mut String, open_lists: &mut Vec<&'static str>, depth: usize) {
+        while open_lists.len() > depth {
+            if let Some(kind) = open_lists.pop() {
+                html.push_str(&format!("</li>\n</{}>\n", kind));
+            }
+        }
+    }
+
+    fn docx_list_kind(
+        paragraph: &DocxParagraph,
+        list_kinds: &HashMap<(String, usize), &'static str>,
+    ) -> Option<&'static str> {
+        let style = paragraph.style.as_deref().unwrap_or("").to_lowercase();
+        let style_kind = if style.starts_with("listnumber") {
+            Some("ol")
+        } else if style.starts_with("listbullet") {
+            Some("ul")
+        } else {
+            None
+        };
+
+        match paragraph.num_id.as_deref() {
+            // numId 0 explicitly removes numbering
+            Some("0") => None,
+            Some(num_id) => Some(
+                list_kinds
+                    .get(&(num_id.to_string(), paragraph.level))
+                    .copied()
+                    .or(style_kind)
+                    .unwrap_or("ul"),
+            ),
+            None => style_kind,
+        }
+    }
+
+    fn docx_heading_level(style: &str) -> Option<usize> {
+        let style = style.to_lowercase().replace(' ', "");
+        if style == "title" {
+            return Some(1);
+        }
+        let level: usize = style.strip_prefix("heading")?.parse().ok()?;
+        (1..=9).contains(&level).then_some(level.min(6))
+    }
+
+    /// Map `(numId, ilvl)` to `ul`/`ol` using the abstract numbering formats in numbering.xml
+    fn docx_list_kinds(numbering_xml: &str) -> HashMap<(String, usize), &'static str> {
+        let mut reader = Reader::from_str(numbering_xml);
+        let mut level_formats: HashMap<(String, usize), String> = HashMap::new();
+        let mut num_to_abstract: HashMap<String, String> = HashMap::new();
+        let mut current_abstract: Option<String> = None;
+        let mut current_level: usize = 0;
+        let mut current_num: Option<String> = None;
+
+        loop {
+            let event = match reader.read_event() {
+                Ok(Event::Eof) | Err(_) => break,
+                Ok(event) => event,
+            };
+
+            if let Event::Start(e) | Event::Empty(e) = &event {
+                match e.local_name().as_ref() {
+                    b"abstractNum" => current_abstract = Self::docx_attr(e, b"w:abstractNumId"),
+                    b"lvl" => {
+                        current_level = Self::docx_attr(e, b"w:ilvl")
+                            .and_then(|v| v.parse().ok())
+                            .unwrap_or(0)
                     }
+                    b"numFmt" => {
+                        if let (Some(abstract_id), Some(format)) =
+                            (&current_abstract, Self::docx_attr(e, b"w:val"))
+                        {
+                            level_formats.insert((abstract_id.clone(), current_level), format);
+                        }
+                    }
+                    b"num" => current_num = Self::docx_attr(e, b"w:numId"),
+                    b"abstractNumId" => {
+                        if let (Some(num_id), Some(abstract_id)) =
+                            (&current_num, Self::docx_attr(e, b"w:val"))
+                        {
+                            num_to_abstract.insert(num_id.clone(), abstract_id);
+                        }
+                    }
+                    _ => {}
                 }
             }
+        }
 
-            if !para_html.is_empty() {
-                if is_bold && is_italic {
-                    html.push_str(&format!("<p><strong><em>{}</em></strong></p>\n", para_html));
-                } else if is_bold {
-                    html.push_str(&format!("<p><strong>{}</strong></p>\n", para_html));
-                } else if is_italic {
-                    html.push_str(&format!("<p><em>{}</em></p>\n", para_html));
-                } else {
-                    html.push_str(&format!("<p>{}</p>\n", para_html));
+        let mut kinds = HashMap::new();
+        for (num_id, abstract_id) in &num_to_abstract {
+            for ((format_abstract, level), format) in &level_formats {
+                if format_abstract == abstract_id {
+                    let kind = if format == "bullet" { "ul" } else { "ol" };
+                    kinds.insert((num_id.clone(), *level), kind);
                 }
-            } else {
-                html.push_str("<p></p>\n");
             }
         }
+        kinds
+    }
 
-        if html.is_empty() {
-            // Fallback: just strip all XML tags
-            let strip_re = Regex::new(r"<[^>]+>").unwrap();
-            html = strip_re.replace_all(xml, "").to_string();
+    /// Map relationship ids (`rId7`) to their targets from document.xml.rels
+    fn docx_relationships(rels_xml: &str) -> HashMap<String, String> {
+        let mut reader = Reader::from_str(rels_xml);
+        let mut relationships = HashMap::new();
+
+        loop {
+            let event = match reader.read_event() {
+                Ok(Event::Eof) | Err(_) => break,
+                Ok(event) => event,
+            };
+
+            if let Event::Start(e) | Event::Empty(e) = &event {
+                if e.local_name().as_ref() == b"Relationship" {
+                    if let (Some(id), Some(target)) =
+                        (Self::docx_attr(e, b"Id"), Self::docx_attr(e, b"Target"))
+                    {
+                        relationships.insert(id, target);
+                    }
+                }
+            }
         }
+        relationships
+    }
+
+    fn docx_attr(element: &BytesStart, name: &[u8]) -> Option<String> {
+        element
+            .try_get_attribute(name)
+            .ok()
+            .flatten()
+            .and_then(|attr| attr.unescape_value().ok())
+            .map(|value| value.into_owned())
+    }
+
+    /// `<w:b/>` is on unless its value explicitly turns it off
+    fn docx_toggle(element: &BytesStart) -> bool {
+        !matches!(
+            Self::docx_attr(element, b"w:val").as_deref(),
+            Some("0" | "false" | "none")
+        )
+    }
+}
+
+/// Paragraph being assembled while walking document.xml
+#[derive(Debug, Default)]
+struct DocxParagraph {
+    style: Option<String>,
+    num_id: Option<String>,
+    level: usize,
+    html: String,
+}
 
+/// Character formatting of the current run
+#[derive(Debug, Default)]
+struct DocxRun {
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl DocxRun {
+    fn wrap(&self, text: &str) -> String {
+        let mut html = text.to_string();
+        if self.underline {
+            html = format!("<u>{}</u>", html);
+        }
+        if self.italic {
+            html = format!("<em>{}</em>", html);
+        }
+        if self.bold {
+            html = format!("<strong>{}</strong>", html);
+        }
         html
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn sniffs_raster_formats_from_magic_bytes() {
+        let png = [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, 0, 0];
+        assert_eq!(ContentExtractor::sniff_image_type(&png), Some("image/png"));
+        assert_eq!(
+            ContentExtractor::sniff_image_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some("image/jpeg")
+        );
+        assert_eq!(
+            ContentExtractor::sniff_image_type(b"GIF89a\x01\x00"),
+            Some("image/gif")
+        );
+        assert_eq!(
+            ContentExtractor::sniff_image_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            Some("image/webp")
+        );
+    }
+
+    #[test]
+    fn text_named_like_an_image_is_not_an_image() {
+        // e.g. "cover.png" whose bytes are plain text
+        assert_eq!(
+            ContentExtractor::sniff_image_type(b"this is not really a png\n"),
+            None
+        );
+        assert_eq!(ContentExtractor::sniff_image_type(b""), None);
+    }
+
+    #[test]
+    fn accepts_svg_root_after_prolog() {
+        let svg = br#"<?xml version="1.0" encoding="UTF-8"?>
+<!-- drawn by hand -->
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd">
+<svg xmlns="http://www.w3.org/2000/svg" width="10" height="10"><rect width="10" height="10"/></svg>"#;
+        assert_eq!(
+            ContentExtractor::sniff_image_type(svg),
+            Some("image/svg+xml")
+        );
+    }
+
+    #[test]
+    fn html_mentioning_svg_is_not_an_image() {
+        let html = b"<html><body><p>inline</p><svg><rect/></svg></body></html>";
+        assert_eq!(ContentExtractor::sniff_image_type(html), None);
+
+        let text = b"notes about the <svg> element";
+        assert_eq!(ContentExtractor::sniff_image_type(text), None);
+    }
+
+    #[test]
+    fn svg_that_can_run_script_is_refused() {
+        let script = br#"<svg xmlns="http://www.w3.org/2000/svg"><script>alert(1)</script></svg>"#;
+        let handler = br#"<svg xmlns="http://www.w3.org/2000/svg" onload="alert(1)"/>"#;
+        let link = br#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink"><a xlink:href="JavaScript:alert(1)"><rect/></a></svg>"#;
+        let foreign = br#"<svg xmlns="http://www.w3.org/2000/svg"><foreignObject><div/></foreignObject></svg>"#;
+
+        for svg in [&script[..], handler, link, foreign] {
+            assert_eq!(ContentExtractor::sniff_image_type(svg), None);
+        }
+    }
+
+    #[test]
+    fn svg_with_an_internal_doctype_subset_is_refused() {
+        let entity = br#"<!DOCTYPE svg [<!ENTITY s "<script>alert(1)</script>">]><svg xmlns="http://www.w3.org/2000/svg">&s;</svg>"#;
+        assert_eq!(ContentExtractor::sniff_image_type(entity), None);
+
+        let subset_only =
+            br#"<!DOCTYPE svg [<!ENTITY s "x">]><svg xmlns="http://www.w3.org/2000/svg"/>"#;
+        assert_eq!(ContentExtractor::sniff_image_type(subset_only), None);
+
+        // Declared elsewhere, but still never expanded by the parser
+        let undeclared = br#"<svg xmlns="http://www.w3.org/2000/svg"><text>&s;</text></svg>"#;
+        assert_eq!(ContentExtractor::sniff_image_type(undeclared), None);
+
+        let predefined =
+            br#"<svg xmlns="http://www.w3.org/2000/svg"><text>a &lt; b &#38; c</text></svg>"#;
+        assert_eq!(
+            ContentExtractor::sniff_image_type(predefined),
+            Some("image/svg+xml")
+        );
+    }
+
+    #[test]
+    fn escaped_javascript_links_are_refused() {
+        let colon = br#"<svg xmlns="http://www.w3.org/2000/svg"><a href="javascript&#58;alert(1)"><rect/></a></svg>"#;
+        let hex = br#"<svg xmlns="http://www.w3.org/2000/svg"><a href="&#x6A;avascript:alert(1)"><rect/></a></svg>"#;
+        let spaced = br#"<svg xmlns="http://www.w3.org/2000/svg"><a href=" java&#9;script:alert(1)"><rect/></a></svg>"#;
+
+        for svg in [&colon[..], hex, spaced] {
+            assert_eq!(ContentExtractor::sniff_image_type(svg), None);
+        }
+    }
+
+    /// A stored (uncompressed) zip holding `files` in the given archive order
+    fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::Write;
+        use zip::write::SimpleFileOptions;
+
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options =
+            SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+        for (name, content) in files {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[test]
+    fn epub_documents_follow_the_spine_not_the_archive() {
+        let container = r#"<?xml version="1.0"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles>
+</container>"#;
+        let opf = r#"<?xml version="1.0"?>
+<package xmlns="http://www.idpf.org/2007/opf" version="3.0">
+  <manifest>
+    <item id="nav" href="nav.xhtml" media-type="application/xhtml+xml" properties="nav"/>
+    <item id="c2" href="text/chapter2.xhtml" media-type="application/xhtml+xml"/>
+    <item id="c1" href="text/chapter1.xhtml" media-type="application/xhtml+xml"/>
+  </manifest>
+  <spine><itemref idref="nav"/><itemref idref="c1"/><itemref idref="c2"/></spine>
+</package>"#;
+        // Archive order deliberately disagrees with reading order
+        let epub = archive(&[
+            ("mimetype", "application/epub+zip"),
+            ("OEBPS/text/chapter2.xhtml", "<p>Second</p>"),
+            ("OEBPS/nav.xhtml", "<nav>Contents</nav>"),
+            ("META-INF/container.xml", container),
+            ("OEBPS/text/chapter1.xhtml", "<p>First</p>"),
+            ("OEBPS/content.opf", opf),
+        ]);
+
+        let mut warnings = Vec::new();
+        let documents = ContentExtractor::read_epub_documents(&epub, &mut warnings).unwrap();
+
+        let paths: Vec<&str> = documents.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["OEBPS/text/chapter1.xhtml", "OEBPS/text/chapter2.xhtml"]
+        );
+        assert_eq!(documents[0].1, "<p>First</p>");
+        assert!(warnings.is_empty());
+    }
+
+    fn docx_document(body: &str) -> String {
+        format!(
+            r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        )
+    }
+
+    #[test]
+    fn docx_headings_lists_and_underline_survive() {
+        let numbering = r#"<w:numbering xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:abstractNum w:abstractNumId="4"><w:lvl w:ilvl="0"><w:numFmt w:val="bullet"/></w:lvl></w:abstractNum>
+  <w:num w:numId="1"><w:abstractNumId w:val="4"/></w:num>
+</w:numbering>"#;
+        let document = docx_document(
+            r#"<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Chapter One</w:t></w:r></w:p>
+<w:p><w:pPr><w:pStyle w:val="Heading2"/></w:pPr><w:r><w:t>Morning</w:t></w:r></w:p>
+<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>Bread</w:t></w:r></w:p>
+<w:p><w:pPr><w:numPr><w:ilvl w:val="0"/><w:numId w:val="1"/></w:numPr></w:pPr><w:r><w:t>Milk</w:t></w:r></w:p>
+<w:p><w:r><w:rPr><w:u w:val="single"/></w:rPr><w:t>Underlined</w:t></w:r><w:r><w:t> plain</w:t></w:r></w:p>"#,
+        );
+
+        let list_kinds = ContentExtractor::docx_list_kinds(numbering);
+        let mut warnings = Vec::new();
+        let html = ContentExtractor::docx_xml_to_html(
+            &document,
+            &HashMap::new(),
+            &list_kinds,
+            &mut warnings,
+        );
+
+        assert_eq!(
+            html,
+            "<h1>Chapter One</h1>\n<h2>Morning</h2>\n<ul>\n<li>Bread</li>\n<li>Milk</li>\n</ul>\n<p><u>Underlined</u> plain</p>\n"
+        );
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn escapes_text_without_touching_generated_tags() {
+        assert_eq!(
+            ContentExtractor::escape_html("a < b && c > d"),
+            "a &lt; b &amp;&amp; c &gt; d"
+        );
+        assert_eq!(
+            ContentExtractor::escape_html(r#"say "hi" & 'bye'"#),
+            "say &quot;hi&quot; &amp; &#39;bye&#39;"
+        );
+
+        // Runs arrive XML-escaped; they must come out escaped exactly once inside our tags
+        let document = docx_document(
+            r#"<w:p><w:r><w:rPr><w:b/></w:rPr><w:t>a &lt; b &amp;&amp; c &gt; d</w:t></w:r></w:p>"#,
+        );
+        let html = ContentExtractor::docx_xml_to_html(
+            &document,
+            &HashMap::new(),
+            &HashMap::new(),
+            &mut Vec::new(),
+        );
+        assert_eq!(
+            html,
+            "<p><strong>a &lt; b &amp;&amp; c &gt; d</strong></p>\n"
+        );
+    }
+
+    /// In-flight request gauge for the mock storage server
+    #[derive(Default)]
+    struct Gauge {
+        current: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    /// Local S3 stand-in: HEAD says nothing is stored yet, PUT accepts anything. Each
+    /// request is held for a delay derived from its path so uploads finish out of order.
+    async fn mock_storage() -> (String, Arc<Gauge>) {
+        use axum::{extract::State, http::Method, http::StatusCode, http::Uri, Router};
+
+        let gauge = Arc::new(Gauge::default());
+        let app = Router::new()
+            .fallback(
+                |State(gauge): State<Arc<Gauge>>, method: Method, uri: Uri| async move {
+                    let now = gauge.current.fetch_add(1, Ordering::SeqCst) + 1;
+                    gauge.peak.fetch_max(now, Ordering::SeqCst);
+
+                    let spread = uri.path().bytes().map(u64::from).sum::<u64>() % 15;
+                    tokio::time::sleep(std::time::Duration::from_millis(5 + spread)).await;
+
+                    gauge.current.fetch_sub(1, Ordering::SeqCst);
+                    if method == Method::HEAD {
+                        StatusCode::NOT_FOUND
+                    } else {
+                        StatusCode::OK
+                    }
+                },
+            )
+            .with_state(gauge.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}", addr), gauge)
+    }
+
+    #[tokio::test]
+    async fn many_images_upload_concurrently_and_keep_their_order() {
+        const IMAGES: usize = 60;
+        const CONCURRENCY: usize = 4;
+
+        let (endpoint, gauge) = mock_storage().await;
+        let mut config = crate::test_support::config();
+        config.s3_endpoint = endpoint;
+        config.image_upload_concurrency = CONCURRENCY;
+        let extractor = ContentExtractor::new(StorageService::new(&config), &config);
+
+        let mut pending: Vec<(String, Vec<u8>, String)> = (0..IMAGES)
+            .map(|n| {
+                let svg = format!(r#"<svg xmlns="http://www.w3.org/2000/svg" id="i{}"/>"#, n);
+                (
+                    format!("OEBPS/images/{:02}.svg", n),
+                    svg.into_bytes(),
+                    "image/svg+xml".to_string(),
+                )
+            })
+            .collect();
+        pending.insert(
+            IMAGES / 2,
+            (
+                "OEBPS/images/fake.png".to_string(),
+                b"not an image".to_vec(),
+                "image/png".to_string(),
+            ),
+        );
+        let expected: Vec<String> = pending
+            .iter()
+            .map(|(name, _, _)| name.clone())
+            .filter(|name| !name.ends_with("fake.png"))
+            .collect();
+
+        let mut warnings = Vec::new();
+        let images = extractor
+            .upload_images("book-1", pending, &mut warnings)
+            .await
+            .unwrap();
+
+        let paths: Vec<String> = images.iter().map(|i| i.original_path.clone()).collect();
+        assert_eq!(paths, expected);
+        assert_eq!(
+            warnings,
+            ["Skipped OEBPS/images/fake.png: not a recognised image"]
+        );
+
+        let peak = gauge.peak.load(Ordering::SeqCst);
+        assert!(peak > 1, "uploads never overlapped (peak {})", peak);
+        assert!(peak <= CONCURRENCY, "{} requests in flight", peak);
+    }
+}
@@ -1,7 +1,13 @@
 use crate::database::Database;
-use crate::errors::AppResult;
-use crate::models::genre_model::{CreateGenreDto, Genre, GenreDto, UpdateGenreDto};
-use chrono::Utc;
+use crate::errors::{map_still_referenced, AppError, AppResult};
+use crate::models::book_model::{Book, BookDto};
+use crate::models::genre_model::{
+    AddBookGenresDto, CreateGenreDto, Genre, GenreDto, GenreQuery, GenreTrendDto, GenreTrendParams,
+    GenreTrendsResponse, GenreWithBookCount, UpdateGenreDto,
+};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use crate::utils::slug::{map_slug_conflict, unique_slug};
+use chrono::{Duration, Utc};
 use sqlx::QueryBuilder;
 
 pub struct GenreService {
@@ -13,28 +19,62 @@ impl GenreService {
         Self { db }
     }
 
-    pub async fn get_genres(&self) -> AppResult<Vec<GenreDto>> {
+    pub async fn get_genres(&self, params: GenreQuery) -> AppResult<PaginatedResponse<GenreDto>> {
+        let sort = params.sort()?;
+        let offset = page_offset(params.page, params.page_size)?;
         let redis = &self.db.redis;
+        let search = params.search.as_deref().map(str::trim).unwrap_or("");
+        let cache_key = format!(
+            "genre:list:page:{}:size:{}:search:{}:sort:{}",
+            params.page,
+            params.page_size,
+            search,
+            sort.as_str()
+        );
 
-        if let Ok(Some(cached_genre)) = redis.get_json::<Vec<GenreDto>>("genre:list").await {
-            return Ok(cached_genre);
+        if let Ok(Some(cached)) = redis
+            .get_json::<PaginatedResponse<GenreDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached);
         }
-        let genre = sqlx::query_as::<_, Genre>(
-            r#"
-        SELECT id, title, description, created_at, updated_at
-        FROM "Genre"
-        "#,
+
+        let search_pattern = (!search.is_empty()).then(|| format!("%{}%", search));
+
+        let total_items = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM "Genre" WHERE $1::TEXT IS NULL OR title ILIKE $1"#,
         )
-        .fetch_all(&self.db.pool)
+        .bind(&search_pattern)
+        .fetch_one(&self.db.pool)
         .await?;
 
-        let genres: Vec<GenreDto> = genre.into_iter().map(Into::into).collect();
+        let sql = format!(
+            r#"
+            SELECT g.id, g.title, g.slug, g.description, g.created_at, g.updated_at,
+                   COUNT(bg.book_id) AS book_count
+            FROM "Genre" g
+            LEFT JOIN "BookGenre" bg ON bg.genre_id = g.id
+            WHERE $1::TEXT IS NULL OR g.title ILIKE $1
+            GROUP BY g.id
+            {}
+            LIMIT $2 OFFSET $3
+            "#,
+            sort.order_by()
+        );
 
-        let _ = redis
-            .set_json(&"genre:list".to_string(), &genres, 600)
-            .await;
+        let genres = sqlx::query_as::<_, GenreWithBookCount>(&sql)
+            .bind(&search_pattern)
+            .bind(params.page_size)
+            .bind(offset)
+            .fetch_all(&self.db.pool)
+            .await?;
+
+        let data: Vec<GenreDto> = genres.into_iter().map(Into::into).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
 
-        Ok(genres)
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
     }
 
     pub async fn get_genres_by_book(&self, book_id: String) -> AppResult<Vec<GenreDto>> {
@@ -47,7 +87,7 @@ impl GenreService {
 
         let genres = sqlx::query_as::<_, Genre>(
             r#"
-            SELECT g.id, g.title, g.description, g.created_at, g.updated_at
+            SELECT g.id, g.title, g.slug, g.description, g.created_at, g.updated_at
             FROM "Genre" g
             INNER JOIN "BookGenre" bg ON g.id = bg.genre_id
             WHERE bg.book_id = $1
@@ -63,6 +103,133 @@ impl GenreService {
 
         Ok(genres_dto)
     }
+    pub async fn add_genres_to_book(
+        &self,
+        book_id: String,
+        request: AddBookGenresDto,
+    ) -> AppResult<Vec<GenreDto>> {
+        if request.genre_ids.is_empty() {
+            return Err(AppError::BadRequest(
+                "genre_ids must not be empty".to_string(),
+            ));
+        }
+
+        let book_exists: bool =
+            sqlx::query_scalar(r#"SELECT EXISTS(SELECT 1 FROM "Book" WHERE id = $1)"#)
+                .bind(&book_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        if !book_exists {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let found: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "Genre" WHERE id = ANY($1)"#)
+            .bind(&request.genre_ids)
+            .fetch_one(&self.db.pool)
+            .await?;
+
+        let mut unique_ids = request.genre_ids.clone();
+        unique_ids.sort();
+        unique_ids.dedup();
+
+        if found != unique_ids.len() as i64 {
+            return Err(AppError::NotFound("Genre not found".to_string()));
+        }
+
+        sqlx::query(
+            r#"
+            INSERT INTO "BookGenre" (book_id, genre_id)
+            SELECT $1, UNNEST($2::TEXT[])
+            ON CONFLICT (book_id, genre_id) DO NOTHING
+            "#,
+        )
+        .bind(&book_id)
+        .bind(&unique_ids)
+        .execute(&self.db.pool)
+        .await?;
+
+        self.invalidate_book_genres(&book_id).await;
+        self.get_genres_by_book(book_id).await
+    }
+
+    pub async fn remove_genre_from_book(&self, book_id: String, genre_id: String) -> AppResult<()> {
+        let result = sqlx::query(r#"DELETE FROM "BookGenre" WHERE book_id = $1 AND genre_id = $2"#)
+            .bind(&book_id)
+            .bind(&genre_id)
+            .execute(&self.db.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Genre is not assigned to this book".to_string(),
+            ));
+        }
+
+        self.invalidate_book_genres(&book_id).await;
+        Ok(())
+    }
+
+    pub async fn get_books_by_genre(
+        &self,
+        genre_id: String,
+        params: PaginationParams,
+    ) -> AppResult<PaginatedResponse<BookDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+        let cache_key = format!(
+            "genre:{}:books:page:{}:size:{}",
+            genre_id, params.page, params.page_size
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<PaginatedResponse<BookDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
+        }
+
+        let total_items =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "BookGenre" WHERE genre_id = $1"#)
+                .bind(&genre_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        let books = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT b.id, b.title, b.slug, b.author, b.author_id, b.cover, b.description, b.asset,
+                   b.series_id, b.series_position,
+                   b.status, b.language, b.release_date, b.popular, b.views, b.version,
+                   b.created_at, b.updated_at
+            FROM "Book" b
+            INNER JOIN "BookGenre" bg ON bg.book_id = b.id
+            WHERE bg.genre_id = $1
+            ORDER BY b.created_at DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&genre_id)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
+
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
+    }
+
+    async fn invalidate_book_genres(&self, book_id: &str) {
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("book:{}", book_id)).await;
+        let _ = redis.del(&format!("book:{}:genres", book_id)).await;
+        let _ = redis.del_prefix("books:list:").await;
+        let _ = redis.del_prefix("genre:").await;
+    }
+
     pub async fn get_genre(&self, id: String) -> AppResult<GenreDto> {
         let redis = &self.db.redis;
         let cache_key = format!("genre:{id}");
@@ -73,7 +240,7 @@ impl GenreService {
 
         let genre = sqlx::query_as::<_, Genre>(
             r#"
-    SELECT id, title, description, created_at, updated_at
+    SELECT id, title, slug, description, created_at, updated_at
     FROM "Genre" WHERE id = $1
     "#,
         )
@@ -86,21 +253,44 @@ impl GenreService {
 
         Ok(data)
     }
+    pub async fn get_genre_by_slug(&self, slug: &str) -> AppResult<GenreDto> {
+        let id = sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Genre" WHERE slug = $1"#)
+            .bind(slug)
+            .fetch_optional(&self.db.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Genre not found".to_string()))?;
+
+        self.get_genre(id).await
+    }
+
     pub async fn create_genre(&self, request: CreateGenreDto) -> AppResult<GenreDto> {
+        let slug = unique_slug(
+            &self.db.pool,
+            "Genre",
+            request.title.as_deref().unwrap_or_default(),
+            "genre",
+            None,
+        )
+        .await?;
+
         let genre = sqlx::query_as::<_, Genre>(
             r#"
-                    INSERT INTO "Genre" (id, title, description, created_at, updated_at)
-                VALUES ($1, $2, $3, $4, $5)
-                    RETURNING id, title, description, created_at, updated_at
+                    INSERT INTO "Genre" (id, title, slug, description, created_at, updated_at)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                    RETURNING id, title, slug, description, created_at, updated_at
                 "#,
         )
         .bind(cuid2::create_id())
         .bind(&request.title)
+        .bind(&slug)
         .bind(&request.description)
         .bind(Utc::now())
         .bind(Utc::now())
         .fetch_one(&self.db.pool)
-        .await?;
+        .await
+        .map_err(map_slug_conflict)?;
+
+        let _ = self.db.redis.del_prefix("genre:list").await;
 
         Ok(genre.into())
     }
@@ -114,12 +304,16 @@ impl GenreService {
         let mut has_updates = false;
 
         if let Some(ref title) = request.title {
-            separated.push("title").push_bind(title);
+            let slug = unique_slug(&self.db.pool, "Genre", title, "genre", Some(&id)).await?;
+            separated.push("title = ").push_bind_unseparated(title);
+            separated.push("slug = ").push_bind_unseparated(slug);
             has_updates = true;
         }
 
         if let Some(ref description) = request.description {
-            separated.push("description").push_bind(description);
+            separated
+                .push("description = ")
+                .push_bind_unseparated(description);
             has_updates = true;
         }
 
@@ -127,15 +321,19 @@ impl GenreService {
             return self.get_genre(id).await;
         }
 
-        separated.push("updated_at").push_bind(Utc::now());
+        separated
+            .push("updated_at = ")
+            .push_bind_unseparated(Utc::now());
 
         builder.push(" WHERE id = ").push_bind(id.clone());
-        builder.push(" RETURNING *");
+        builder.push(" RETURNING id, title, slug, description, created_at, updated_at");
 
         let updated_genre = builder
             .build_query_as::<Genre>()
-            .fetch_one(&self.db.pool)
-            .await?;
+            .fetch_optional(&self.db.pool)
+            .await
+            .map_err(map_slug_conflict)?
+            .ok_or_else(|| AppError::NotFound("Genre not found".to_string()))?;
 
         let _ = redis.del(&cache_key).await;
         let _ = redis.del_prefix("genre:list").await;
@@ -143,24 +341,288 @@ impl GenreService {
         Ok(updated_genre.into())
     }
 
-    pub async fn delete_genre(&self, id: String) -> AppResult<GenreDto> {
+    /// Refuses to delete a genre that books still reference unless `force` is set,
+    /// in which case the BookGenre rows are cascaded away with it
+    pub async fn delete_genre(&self, id: String, force: bool) -> AppResult<GenreDto> {
         let redis = &self.db.redis;
         let cache_key = format!("genre:{id}");
 
+        let mut tx = self.db.pool.begin().await?;
+
+        // Lock the genre so no book can be tagged between the count and the delete
+        sqlx::query_scalar::<_, String>(r#"SELECT id FROM "Genre" WHERE id = $1 FOR UPDATE"#)
+            .bind(&id)
+            .fetch_optional(&mut *tx)
+            .await?
+            .ok_or_else(|| AppError::NotFound("Genre not found".to_string()))?;
+
+        let book_count: i64 =
+            sqlx::query_scalar(r#"SELECT COUNT(*) FROM "BookGenre" WHERE genre_id = $1"#)
+                .bind(&id)
+                .fetch_one(&mut *tx)
+                .await?;
+
+        if book_count > 0 && !force {
+            return Err(AppError::GenreInUse { book_count });
+        }
+
         let deleted_genre = sqlx::query_as::<_, Genre>(
             r#"
             DELETE FROM "Genre"
             WHERE id = $1
-            RETURNING id, title, description, created_at, updated_at
+            RETURNING id, title, slug, description, created_at, updated_at
             "#,
         )
         .bind(&id)
-        .fetch_one(&self.db.pool)
-        .await?;
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(map_still_referenced)?
+        .ok_or_else(|| AppError::NotFound("Genre not found".to_string()))?;
+
+        tx.commit().await?;
 
         let _ = redis.del(&cache_key).await;
-        let _ = redis.del_prefix("genre:list").await;
+        let _ = redis.del_prefix("genre:").await;
+        // Join rows were cascaded away, so cached book genre lists are stale
+        let _ = redis.del_prefix("book:").await;
+        let _ = redis.del_prefix("books:list:").await;
 
         Ok(deleted_genre.into())
     }
+
+    pub async fn get_genre_trends(
+        &self,
+        params: GenreTrendParams,
+    ) -> AppResult<GenreTrendsResponse> {
+        if !(1..=365).contains(&params.days) {
+            return Err(AppError::BadRequest(
+                "days must be between 1 and 365".to_string(),
+            ));
+        }
+        if !(1..=100).contains(&params.limit) {
+            return Err(AppError::BadRequest(
+                "limit must be between 1 and 100".to_string(),
+            ));
+        }
+
+        let redis = &self.db.redis;
+        let cache_key = format!("genre:trends:days:{}:limit:{}", params.days, params.limit);
+
+        if let Ok(Some(cached)) = redis.get_json::<GenreTrendsResponse>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        let since = Utc::now().naive_utc() - Duration::days(params.days);
+
+        let genres = sqlx::query_as::<_, GenreTrendDto>(
+            r#"
+            WITH book_counts AS (
+                SELECT bg.genre_id, COUNT(*) AS new_books
+                FROM "BookGenre" bg
+                INNER JOIN "Book" b ON b.id = bg.book_id
+                WHERE b.created_at >= $1
+                GROUP BY bg.genre_id
+            ),
+            bookmark_counts AS (
+                SELECT bg.genre_id, COUNT(*) AS new_bookmarks
+                FROM "BookGenre" bg
+                INNER JOIN "Bookmark" bm ON bm.book_id = bg.book_id
+                WHERE bm.created_at >= $1
+                GROUP BY bg.genre_id
+            )
+            SELECT g.id AS genre_id,
+                   g.title,
+                   COALESCE(bc.new_books, 0) AS new_books,
+                   COALESCE(bmc.new_bookmarks, 0) AS new_bookmarks
+            FROM "Genre" g
+            LEFT JOIN book_counts bc ON bc.genre_id = g.id
+            LEFT JOIN bookmark_counts bmc ON bmc.genre_id = g.id
+            ORDER BY new_books DESC, new_bookmarks DESC, g.title ASC
+            LIMIT $2
+            "#,
+        )
+        .bind(since)
+        .bind(params.limit)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let response = GenreTrendsResponse {
+            days: params.days,
+            genres,
+        };
+        // Trends are coarse-grained, a short TTL is plenty
+        let _ = redis.set_json(&cache_key, &response, 300).await;
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use sqlx::PgPool;
+
+    fn service(pool: &PgPool) -> GenreService {
+        GenreService::new(test_support::database(pool.clone()))
+    }
+
+    async fn genre(service: &GenreService, title: &str) -> String {
+        service
+            .create_genre(CreateGenreDto {
+                title: Some(title.to_string()),
+                description: Some(format!("{} stories", title)),
+            })
+            .await
+            .unwrap()
+            .id
+    }
+
+    async fn tag(pool: &PgPool, book_id: &str, genre_id: &str) {
+        sqlx::query(r#"INSERT INTO "BookGenre" (book_id, genre_id) VALUES ($1, $2)"#)
+            .bind(book_id)
+            .bind(genre_id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    async fn bookmark(pool: &PgPool, user_id: &str, book_id: &str, age_days: i64) {
+        let created_at = (Utc::now() - Duration::days(age_days)).naive_utc();
+        sqlx::query(
+            r#"
+            INSERT INTO "Bookmark" (id, user_id, book_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $4)
+            "#,
+        )
+        .bind(cuid2::create_id())
+        .bind(user_id)
+        .bind(book_id)
+        .bind(created_at)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn trends_count_only_activity_inside_the_window(pool: PgPool) {
+        let service = service(&pool);
+        let fantasy = genre(&service, "Trend Fantasy").await;
+        let horror = genre(&service, "Trend Horror").await;
+
+        let fresh_a = test_support::seed_book(&pool, "Fresh A", 2).await;
+        let fresh_b = test_support::seed_book(&pool, "Fresh B", 5).await;
+        let old = test_support::seed_book(&pool, "Old", 60).await;
+        tag(&pool, &fresh_a, &fantasy).await;
+        tag(&pool, &fresh_b, &fantasy).await;
+        tag(&pool, &old, &horror).await;
+
+        let reader = test_support::seed_user(&pool, "trend_reader").await;
+        bookmark(&pool, &reader, &old, 1).await;
+        bookmark(&pool, &reader, &fresh_a, 3).await;
+        bookmark(&pool, &reader, &old, 45).await;
+
+        let trends = service
+            .get_genre_trends(GenreTrendParams {
+                days: 30,
+                limit: 100,
+            })
+            .await
+            .unwrap();
+        assert_eq!(trends.days, 30);
+
+        let counts = |id: &str| {
+            let trend = trends
+                .genres
+                .iter()
+                .find(|g| g.genre_id == id)
+                .expect("genre should be listed");
+            (trend.new_books, trend.new_bookmarks)
+        };
+        assert_eq!(counts(&fantasy), (2, 1));
+        assert_eq!(counts(&horror), (0, 1));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn trends_reject_out_of_range_windows(pool: PgPool) {
+        let result = service(&pool)
+            .get_genre_trends(GenreTrendParams { days: 0, limit: 10 })
+            .await;
+        assert!(matches!(result, Err(AppError::BadRequest(_))));
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn update_changes_only_the_given_field(pool: PgPool) {
+        let service = service(&pool);
+        let id = genre(&service, "Partial").await;
+        let before = service.get_genre(id.clone()).await.unwrap();
+
+        let updated = service
+            .update_genre(
+                id.clone(),
+                UpdateGenreDto {
+                    title: None,
+                    description: Some("Only this changed".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(updated.id, id);
+        assert_eq!(updated.description, "Only this changed");
+        assert_eq!(updated.title, before.title);
+        assert_eq!(updated.slug, before.slug);
+        assert_eq!(
+            service.get_genre(id).await.unwrap().description,
+            "Only this changed"
+        );
+    }
+
+    async fn genre_links(pool: &PgPool, genre_id: &str) -> i64 {
+        sqlx::query_scalar(r#"SELECT COUNT(*) FROM "BookGenre" WHERE genre_id = $1"#)
+            .bind(genre_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn delete_is_blocked_while_books_use_the_genre(pool: PgPool) {
+        let service = service(&pool);
+        let id = genre(&service, "In Use").await;
+        for title in ["Tagged One", "Tagged Two"] {
+            let book = test_support::seed_book(&pool, title, 0).await;
+            tag(&pool, &book, &id).await;
+        }
+
+        let err = service.delete_genre(id.clone(), false).await.unwrap_err();
+
+        assert!(matches!(err, AppError::GenreInUse { book_count: 2 }));
+        assert_eq!(genre_links(&pool, &id).await, 2);
+        assert!(service.get_genre(id).await.is_ok());
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn forced_delete_removes_the_genre_and_its_links(pool: PgPool) {
+        let service = service(&pool);
+        let id = genre(&service, "Forced").await;
+        let book = test_support::seed_book(&pool, "Tagged", 0).await;
+        tag(&pool, &book, &id).await;
+
+        let deleted = service.delete_genre(id.clone(), true).await.unwrap();
+
+        assert_eq!(deleted.id, id);
+        assert_eq!(genre_links(&pool, &id).await, 0);
+        let remaining: i64 = sqlx::query_scalar(r#"SELECT COUNT(*) FROM "Genre" WHERE id = $1"#)
+            .bind(&id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 0);
+    }
 }
@@ -1,7 +1,14 @@
 pub mod auth_service;
+pub mod author_service;
 pub mod book_service;
 pub mod chapter_service;
 pub mod content_extractor;
 pub mod genre_service;
 pub mod notification_service;
+pub mod reading_progress_service;
+pub mod review_service;
+pub mod series_service;
+pub mod stats_service;
 pub mod storage_service;
+pub mod tag_service;
+pub mod user_service;
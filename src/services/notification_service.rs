@@ -1,12 +1,15 @@
-use crate::config::Config;
+use crate::config::{Config, FcmDelivery};
 use crate::database::Database;
-use crate::errors::AppResult;
+use crate::errors::{AppError, AppResult};
+use crate::models::notification_model::{NotificationDto, NotificationPreferences};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use chrono::Utc;
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response};
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::sync::Arc;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
@@ -35,6 +38,41 @@ struct GoogleTokenResponse {
     expires_in: u64,
 }
 
+/// Error body returned by the Google OAuth token endpoint
+#[derive(Debug, Deserialize)]
+struct GoogleTokenError {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Error body returned by the FCM V1 API
+#[derive(Debug, Deserialize)]
+struct FcmErrorResponse {
+    error: FcmErrorBody,
+}
+
+#[derive(Debug, Deserialize)]
+struct FcmErrorBody {
+    status: Option<String>,
+    #[serde(default)]
+    details: Vec<FcmErrorDetail>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FcmErrorDetail {
+    #[serde(rename = "errorCode")]
+    error_code: Option<String>,
+}
+
+/// FCM error codes meaning the device token will never work again
+const PRUNABLE_FCM_ERRORS: [&str; 3] = ["UNREGISTERED", "INVALID_ARGUMENT", "NOT_FOUND"];
+
+/// Origin of the FCM HTTP v1 send endpoint
+const FCM_API_BASE: &str = "https://fcm.googleapis.com";
+
+/// Delay before the first retry; doubled on every further attempt
+const FCM_RETRY_BASE_DELAY: Duration = Duration::from_millis(250);
+
 /// Cached access token
 struct CachedToken {
     token: String,
@@ -46,6 +84,9 @@ pub struct NotificationService {
     http_client: Client,
     project_id: Option<String>,
     credentials: Option<ServiceAccountCredentials>,
+    max_attempts: u32,
+    delivery: FcmDelivery,
+    api_base: String,
     cached_token: Arc<RwLock<Option<CachedToken>>>,
 }
 
@@ -53,13 +94,9 @@ impl NotificationService {
     pub fn new(db: Database, config: &Config) -> Self {
         let (project_id, credentials) = Self::load_credentials(config);
 
-        if project_id.is_none() {
-            info!("FCM not configured - push notifications disabled");
-        } else {
-            info!(
-                "FCM V1 API configured for project: {}",
-                project_id.as_ref().unwrap()
-            );
+        match &project_id {
+            Some(id) => info!("FCM V1 API configured for project: {}", id),
+            None => info!("FCM not configured - push notifications disabled"),
         }
 
         Self {
@@ -67,6 +104,9 @@ impl NotificationService {
             http_client: Client::new(),
             project_id,
             credentials,
+            max_attempts: config.fcm_max_attempts.max(1),
+            delivery: config.fcm_delivery,
+            api_base: FCM_API_BASE.to_string(),
             cached_token: Arc::new(RwLock::new(None)),
         }
     }
@@ -153,13 +193,14 @@ impl NotificationService {
 
         // Exchange JWT for access token
         let response = self
-            .http_client
-            .post("https://oauth2.googleapis.com/token")
-            .form(&[
-                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
-                ("assertion", &jwt),
-            ])
-            .send()
+            .send_with_retry(|| {
+                self.http_client
+                    .post("https://oauth2.googleapis.com/token")
+                    .form(&[
+                        ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                        ("assertion", &jwt),
+                    ])
+            })
             .await;
 
         let response = match response {
@@ -170,6 +211,26 @@ impl NotificationService {
             }
         };
 
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            match serde_json::from_str::<GoogleTokenError>(&error_text) {
+                Ok(body) => error!(
+                    status = %status,
+                    error = %body.error,
+                    description = body.error_description.as_deref().unwrap_or(""),
+                    "FCM token exchange rejected; check the service account key and that its \
+                     client_email still exists and is enabled"
+                ),
+                Err(_) => error!(
+                    status = %status,
+                    body = %error_text,
+                    "FCM token exchange failed with an unexpected response"
+                ),
+            }
+            return None;
+        }
+
         let token_response: GoogleTokenResponse = match response.json().await {
             Ok(t) => t,
             Err(e) => {
@@ -190,7 +251,7 @@ impl NotificationService {
         Some(token_response.access_token)
     }
 
-    /// Send push notification to all users who bookmarked a novel
+    /// Record an inbox entry and send a push notification to all users who bookmarked a novel
     pub async fn notify_new_chapter(
         &self,
         novel_id: &str,
@@ -199,6 +260,26 @@ impl NotificationService {
         chapter_title: &str,
         chapter_id: &str,
     ) -> AppResult<()> {
+        let notification_title = format!("📖 {}", novel_title);
+        let notification_body = format!(
+            "Chapter {} - {} is now available!",
+            chapter_num, chapter_title
+        );
+
+        // The inbox is written regardless of whether FCM is configured
+        let recorded = self
+            .record_inbox_notifications(
+                novel_id,
+                chapter_id,
+                &notification_title,
+                &notification_body,
+            )
+            .await?;
+        info!(
+            recorded,
+            "Recorded inbox notifications for novel {}", novel_id
+        );
+
         let project_id = match &self.project_id {
             Some(id) => id,
             None => {
@@ -215,6 +296,34 @@ impl NotificationService {
             }
         };
 
+        if self.delivery == FcmDelivery::Topic {
+            match self
+                .send_fcm_topic_notification(
+                    project_id,
+                    &access_token,
+                    novel_id,
+                    &notification_title,
+                    &notification_body,
+                    chapter_id,
+                )
+                .await
+            {
+                Ok(true) => {
+                    info!("Sent topic notification for novel {}", novel_id);
+                    return Ok(());
+                }
+                Ok(false) => warn!(
+                    "Topic notification for novel {} was rejected, falling back to per-token delivery",
+                    novel_id
+                ),
+                Err(e) => warn!(
+                    error = ?e,
+                    "Topic notification for novel {} failed, falling back to per-token delivery",
+                    novel_id
+                ),
+            }
+        }
+
         // Get all FCM tokens for users who bookmarked this novel
         let tokens = self.get_bookmark_user_tokens(novel_id).await?;
 
@@ -232,20 +341,40 @@ impl NotificationService {
             novel_id
         );
 
-        let notification_title = format!("📖 {}", novel_title);
-        let notification_body = format!(
-            "Chapter {} - {} is now available!",
-            chapter_num, chapter_title
-        );
+        self.send_to_tokens(
+            project_id,
+            &access_token,
+            tokens,
+            &notification_title,
+            &notification_body,
+            novel_id,
+            chapter_id,
+        )
+        .await;
 
+        Ok(())
+    }
+
+    /// Send the chapter message to each device token, pruning tokens FCM reports as dead
+    #[allow(clippy::too_many_arguments)]
+    async fn send_to_tokens(
+        &self,
+        project_id: &str,
+        access_token: &str,
+        tokens: Vec<String>,
+        title: &str,
+        body: &str,
+        novel_id: &str,
+        chapter_id: &str,
+    ) {
         for token in tokens {
             match self
                 .send_fcm_v1_notification(
                     project_id,
-                    &access_token,
+                    access_token,
                     &token,
-                    &notification_title,
-                    &notification_body,
+                    title,
+                    body,
                     novel_id,
                     chapter_id,
                 )
@@ -267,8 +396,47 @@ impl NotificationService {
                 }
             }
         }
+    }
 
-        Ok(())
+    /// Insert one inbox row per user who bookmarked the novel, returning how many were written
+    async fn record_inbox_notifications(
+        &self,
+        novel_id: &str,
+        chapter_id: &str,
+        title: &str,
+        body: &str,
+    ) -> AppResult<u64> {
+        let user_ids = sqlx::query_scalar::<_, String>(
+            r#"SELECT DISTINCT user_id FROM "Bookmark" WHERE book_id = $1"#,
+        )
+        .bind(novel_id)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        if user_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let ids: Vec<String> = user_ids.iter().map(|_| cuid2::create_id()).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO "Notification" (id, user_id, title, body, novel_id, chapter_id, created_at)
+            SELECT id, user_id, $3, $4, $5, $6, $7
+            FROM UNNEST($1::TEXT[], $2::TEXT[]) AS t(id, user_id)
+            "#,
+        )
+        .bind(&ids)
+        .bind(&user_ids)
+        .bind(title)
+        .bind(body)
+        .bind(novel_id)
+        .bind(chapter_id)
+        .bind(Utc::now())
+        .execute(&self.db.pool)
+        .await?;
+
+        Ok(result.rows_affected())
     }
 
     /// Get FCM tokens for all users who bookmarked a specific novel
@@ -281,6 +449,7 @@ impl NotificationService {
             WHERE b.book_id = $1 
             AND u.fcm_token IS NOT NULL 
             AND u.fcm_token != ''
+            AND u.notify_new_chapters
             "#,
         )
         .bind(novel_id)
@@ -290,6 +459,7 @@ impl NotificationService {
         Ok(tokens)
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn send_fcm_v1_notification(
         &self,
         project_id: &str,
@@ -300,60 +470,463 @@ impl NotificationService {
         novel_id: &str,
         chapter_id: &str,
     ) -> Result<bool, crate::errors::AppError> {
-        let payload = serde_json::json!({
-            "message": {
-                "token": device_token,
-                "notification": {
-                    "title": title,
-                    "body": body
-                },
-                "data": {
-                    "novel_id": novel_id,
-                    "chapter_id": chapter_id,
-                    "click_action": "FLUTTER_NOTIFICATION_CLICK"
-                },
-                "android": {
-                    "priority": "high",
-                    "notification": {
-                        "icon": "ic_notification",
-                        "color": "#6366F1",
-                        "sound": "default"
-                    }
-                }
-            }
-        });
+        let payload =
+            Self::chapter_message(("token", device_token), title, body, novel_id, chapter_id);
 
-        let url = format!(
-            "https://fcm.googleapis.com/v1/projects/{}/messages:send",
-            project_id
-        );
+        let url = format!("{}/v1/projects/{}/messages:send", self.api_base, project_id);
 
         let response = self
-            .http_client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", access_token))
-            .header("Content-Type", "application/json")
-            .json(&payload)
-            .send()
+            .send_with_retry(|| {
+                self.http_client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", access_token))
+                    .header("Content-Type", "application/json")
+                    .json(&payload)
+            })
             .await?;
 
         if response.status().is_success() {
             info!("FCM V1 notification sent successfully");
             Ok(true)
         } else {
+            let status = response.status();
             let error_text = response.text().await.unwrap_or_default();
 
-            // Check if token is unregistered (invalid)
-            if error_text.contains("UNREGISTERED") || error_text.contains("NOT_FOUND") {
-                warn!("FCM token is invalid/unregistered, will be removed");
+            if Self::is_prunable_fcm_error(&error_text) {
+                warn!(
+                    status = %status,
+                    "FCM token is invalid/unregistered, will be removed"
+                );
                 Ok(false) // Signal to remove this token
             } else {
-                error!("FCM V1 notification failed: {}", error_text);
+                error!("FCM V1 notification failed ({}): {}", status, error_text);
                 Ok(true) // Don't remove token for other errors
             }
         }
     }
 
+    /// Send one message to the novel's topic, returning whether FCM accepted it
+    async fn send_fcm_topic_notification(
+        &self,
+        project_id: &str,
+        access_token: &str,
+        novel_id: &str,
+        title: &str,
+        body: &str,
+        chapter_id: &str,
+    ) -> AppResult<bool> {
+        let topic = Self::novel_topic(novel_id);
+        let payload = Self::chapter_message(("topic", &topic), title, body, novel_id, chapter_id);
+
+        let url = format!("{}/v1/projects/{}/messages:send", self.api_base, project_id);
+
+        let response = self
+            .send_with_retry(|| {
+                self.http_client
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", access_token))
+                    .header("Content-Type", "application/json")
+                    .json(&payload)
+            })
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(true);
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        error!("FCM topic notification failed ({}): {}", status, error_text);
+        Ok(false)
+    }
+
+    /// FCM message for a new chapter, addressed by `("token", ..)` or `("topic", ..)`
+    fn chapter_message(
+        target: (&str, &str),
+        title: &str,
+        body: &str,
+        novel_id: &str,
+        chapter_id: &str,
+    ) -> serde_json::Value {
+        let (target_key, target_value) = target;
+        let mut message = serde_json::json!({
+            "notification": {
+                "title": title,
+                "body": body
+            },
+            "data": {
+                "novel_id": novel_id,
+                "chapter_id": chapter_id,
+                "click_action": "FLUTTER_NOTIFICATION_CLICK"
+            },
+            "android": {
+                "priority": "high",
+                "notification": {
+                    "icon": "ic_notification",
+                    "color": "#6366F1",
+                    "sound": "default"
+                }
+            }
+        });
+        message[target_key] = serde_json::Value::from(target_value);
+
+        serde_json::json!({ "message": message })
+    }
+
+    fn novel_topic(novel_id: &str) -> String {
+        format!("novel_{}", novel_id)
+    }
+
+    /// Whether bookmark and preference changes need to be mirrored into FCM topics
+    fn uses_topics(&self) -> bool {
+        self.delivery == FcmDelivery::Topic && self.project_id.is_some()
+    }
+
+    /// Subscribe or unsubscribe the user's device from one novel's topic in the background.
+    /// Subscriptions are skipped for users without a token or who opted out of new-chapter pushes.
+    pub fn spawn_novel_topic_sync(
+        self: &Arc<Self>,
+        user_id: &str,
+        novel_id: &str,
+        subscribe: bool,
+    ) {
+        if !self.uses_topics() {
+            return;
+        }
+
+        let service = Arc::clone(self);
+        let user_id = user_id.to_string();
+        let novel_id = novel_id.to_string();
+
+        tokio::spawn(async move {
+            let token = sqlx::query_scalar::<_, String>(
+                r#"
+                SELECT fcm_token FROM "User"
+                WHERE id = $1 AND fcm_token IS NOT NULL AND fcm_token != ''
+                AND (NOT $2 OR notify_new_chapters)
+                "#,
+            )
+            .bind(&user_id)
+            .bind(subscribe)
+            .fetch_optional(&service.db.pool)
+            .await;
+
+            match token {
+                Ok(Some(token)) => {
+                    service
+                        .update_topic_membership(&token, &[novel_id], subscribe)
+                        .await
+                }
+                Ok(None) => {}
+                Err(e) => error!("Failed to load FCM token for topic sync: {:?}", e),
+            }
+        });
+    }
+
+    /// Re-apply the user's preference to the topics of every bookmarked novel in the background
+    pub fn spawn_user_topic_sync(self: &Arc<Self>, user_id: &str) {
+        if !self.uses_topics() {
+            return;
+        }
+
+        let service = Arc::clone(self);
+        let user_id = user_id.to_string();
+
+        tokio::spawn(async move {
+            let user = sqlx::query_as::<_, (Option<String>, bool)>(
+                r#"SELECT fcm_token, notify_new_chapters FROM "User" WHERE id = $1"#,
+            )
+            .bind(&user_id)
+            .fetch_optional(&service.db.pool)
+            .await;
+
+            let (token, subscribe) = match user {
+                Ok(Some((Some(token), subscribe))) if !token.is_empty() => (token, subscribe),
+                Ok(_) => return,
+                Err(e) => {
+                    error!("Failed to load user for topic sync: {:?}", e);
+                    return;
+                }
+            };
+
+            match service.bookmarked_novel_ids(&user_id).await {
+                Ok(novel_ids) => {
+                    service
+                        .update_topic_membership(&token, &novel_ids, subscribe)
+                        .await
+                }
+                Err(e) => error!("Failed to load bookmarks for topic sync: {:?}", e),
+            }
+        });
+    }
+
+    /// Unsubscribe the user's current device from every bookmarked novel's topic.
+    /// Call before the stored token is replaced or cleared; `keep_token` skips the
+    /// release when the device is simply re-registering the same token.
+    pub async fn release_user_topics(self: &Arc<Self>, user_id: &str, keep_token: Option<&str>) {
+        if !self.uses_topics() {
+            return;
+        }
+
+        let token = sqlx::query_scalar::<_, Option<String>>(
+            r#"SELECT fcm_token FROM "User" WHERE id = $1"#,
+        )
+        .bind(user_id)
+        .fetch_optional(&self.db.pool)
+        .await;
+
+        let token = match token {
+            Ok(Some(Some(token))) if !token.is_empty() && Some(token.as_str()) != keep_token => {
+                token
+            }
+            Ok(_) => return,
+            Err(e) => {
+                error!("Failed to load FCM token for topic release: {:?}", e);
+                return;
+            }
+        };
+
+        let novel_ids = match self.bookmarked_novel_ids(user_id).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                error!("Failed to load bookmarks for topic release: {:?}", e);
+                return;
+            }
+        };
+
+        let service = Arc::clone(self);
+        tokio::spawn(async move {
+            service
+                .update_topic_membership(&token, &novel_ids, false)
+                .await;
+        });
+    }
+
+    async fn bookmarked_novel_ids(&self, user_id: &str) -> AppResult<Vec<String>> {
+        let ids =
+            sqlx::query_scalar::<_, String>(r#"SELECT book_id FROM "Bookmark" WHERE user_id = $1"#)
+                .bind(user_id)
+                .fetch_all(&self.db.pool)
+                .await?;
+
+        Ok(ids)
+    }
+
+    /// Add or remove a device token from each novel's topic via the Instance ID API
+    async fn update_topic_membership(
+        &self,
+        device_token: &str,
+        novel_ids: &[String],
+        subscribe: bool,
+    ) {
+        if novel_ids.is_empty() {
+            return;
+        }
+
+        let access_token = match self.get_access_token().await {
+            Some(token) => token,
+            None => {
+                error!("Failed to get FCM access token for topic sync");
+                return;
+            }
+        };
+
+        let url = if subscribe {
+            "https://iid.googleapis.com/iid/v1:batchAdd"
+        } else {
+            "https://iid.googleapis.com/iid/v1:batchRemove"
+        };
+
+        for novel_id in novel_ids {
+            let payload = serde_json::json!({
+                "to": format!("/topics/{}", Self::novel_topic(novel_id)),
+                "registration_tokens": [device_token],
+            });
+
+            let response = self
+                .send_with_retry(|| {
+                    self.http_client
+                        .post(url)
+                        .header("Authorization", format!("Bearer {}", access_token))
+                        .header("access_token_auth", "true")
+                        .json(&payload)
+                })
+                .await;
+
+            match response {
+                Ok(r) if r.status().is_success() => {}
+                Ok(r) => {
+                    let status = r.status();
+                    let error_text = r.text().await.unwrap_or_default();
+                    warn!(
+                        status = %status,
+                        subscribe,
+                        "FCM topic update for novel {} failed: {}", novel_id, error_text
+                    );
+                }
+                Err(e) => warn!(
+                    error = %e,
+                    subscribe,
+                    "FCM topic update for novel {} failed", novel_id
+                ),
+            }
+        }
+    }
+
+    /// Send a request, retrying network errors and 5xx responses with exponential backoff.
+    /// 4xx responses are returned immediately since repeating them cannot help.
+    async fn send_with_retry<F>(&self, build: F) -> Result<Response, reqwest::Error>
+    where
+        F: Fn() -> RequestBuilder,
+    {
+        let mut attempt = 1;
+        loop {
+            let result = build().send().await;
+
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => !e.is_builder(),
+            };
+            if !retryable || attempt >= self.max_attempts {
+                return result;
+            }
+
+            let delay = FCM_RETRY_BASE_DELAY * 2u32.pow(attempt - 1);
+            match &result {
+                Ok(response) => warn!(
+                    attempt,
+                    status = %response.status(),
+                    "FCM request failed, retrying in {:?}", delay
+                ),
+                Err(e) => warn!(attempt, error = %e, "FCM request failed, retrying in {:?}", delay),
+            }
+
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Whether an FCM error body says the device token should be dropped
+    fn is_prunable_fcm_error(error_text: &str) -> bool {
+        let parsed: FcmErrorResponse = match serde_json::from_str(error_text) {
+            Ok(parsed) => parsed,
+            // Fall back to a plain text scan if the body isn't the documented shape
+            Err(_) => {
+                return PRUNABLE_FCM_ERRORS
+                    .iter()
+                    .any(|code| error_text.contains(code))
+            }
+        };
+
+        let detail_codes = parsed
+            .error
+            .details
+            .iter()
+            .filter_map(|detail| detail.error_code.as_deref());
+
+        parsed
+            .error
+            .status
+            .as_deref()
+            .into_iter()
+            .chain(detail_codes)
+            .any(|code| PRUNABLE_FCM_ERRORS.contains(&code))
+    }
+
+    /// List a user's inbox, newest first
+    pub async fn get_notifications(
+        &self,
+        user_id: &str,
+        params: PaginationParams,
+    ) -> AppResult<PaginatedResponse<NotificationDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+
+        let total_items = sqlx::query_scalar::<_, i64>(
+            r#"SELECT COUNT(*) FROM "Notification" WHERE user_id = $1"#,
+        )
+        .bind(user_id)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        let notifications = sqlx::query_as::<_, NotificationDto>(
+            r#"
+            SELECT id, title, body, novel_id, chapter_id, read_at IS NOT NULL AS read, created_at
+            FROM "Notification"
+            WHERE user_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(user_id)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            notifications,
+            params.page,
+            params.page_size,
+            total_items,
+        ))
+    }
+
+    /// Mark a single inbox notification as read; notifications of other users are reported as missing
+    pub async fn mark_read(&self, user_id: &str, id: &str) -> AppResult<NotificationDto> {
+        let notification = sqlx::query_as::<_, NotificationDto>(
+            r#"
+            UPDATE "Notification"
+            SET read_at = COALESCE(read_at, $3)
+            WHERE id = $1 AND user_id = $2
+            RETURNING id, title, body, novel_id, chapter_id, read_at IS NOT NULL AS read, created_at
+            "#,
+        )
+        .bind(id)
+        .bind(user_id)
+        .bind(Utc::now())
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Notification not found".to_string()))?;
+
+        Ok(notification)
+    }
+
+    /// Mark every unread inbox notification of a user as read, returning how many changed
+    pub async fn mark_all_read(&self, user_id: &str) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"UPDATE "Notification" SET read_at = $1 WHERE user_id = $2 AND read_at IS NULL"#,
+        )
+        .bind(Utc::now())
+        .bind(user_id)
+        .execute(&self.db.pool)
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    pub async fn update_preferences(
+        &self,
+        user_id: &str,
+        preferences: &NotificationPreferences,
+    ) -> AppResult<NotificationPreferences> {
+        let updated = sqlx::query_as::<_, NotificationPreferences>(
+            r#"
+            UPDATE "User"
+            SET notify_new_chapters = $2, updated_at = $3
+            WHERE id = $1
+            RETURNING notify_new_chapters
+            "#,
+        )
+        .bind(user_id)
+        .bind(preferences.notify_new_chapters)
+        .bind(Utc::now())
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        Ok(updated)
+    }
+
     /// Remove invalid FCM token from user
     async fn remove_invalid_token(&self, token: &str) {
         let result = sqlx::query(r#"UPDATE "User" SET fcm_token = NULL WHERE fcm_token = $1"#)
@@ -373,3 +946,189 @@ impl NotificationService {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use sqlx::PgPool;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    async fn seed_notifications(pool: &PgPool, user_id: &str, count: usize, read: bool) {
+        for n in 0..count {
+            sqlx::query(
+                r#"
+                INSERT INTO "Notification" (id, user_id, title, body, read_at)
+                VALUES ($1, $2, $3, 'body', CASE WHEN $4 THEN NOW() END)
+                "#,
+            )
+            .bind(cuid2::create_id())
+            .bind(user_id)
+            .bind(format!("Notification {}", n))
+            .bind(read)
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn unread_count(pool: &PgPool, user_id: &str) -> i64 {
+        sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM "Notification" WHERE user_id = $1 AND read_at IS NULL"#,
+        )
+        .bind(user_id)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn mark_all_read_marks_only_the_users_unread_notifications(pool: PgPool) {
+        let reader = test_support::seed_user(&pool, "reader").await;
+        let other = test_support::seed_user(&pool, "other").await;
+        seed_notifications(&pool, &reader, 3, false).await;
+        seed_notifications(&pool, &reader, 2, true).await;
+        seed_notifications(&pool, &other, 1, false).await;
+
+        let service = NotificationService::new(
+            test_support::database(pool.clone()),
+            &test_support::config(),
+        );
+
+        assert_eq!(service.mark_all_read(&reader).await.unwrap(), 3);
+        assert_eq!(unread_count(&pool, &reader).await, 0);
+        assert_eq!(unread_count(&pool, &other).await, 1);
+
+        // Nothing left to mark the second time round
+        assert_eq!(service.mark_all_read(&reader).await.unwrap(), 0);
+    }
+
+    /// Local stand-in for FCM answering each request with the next status in `statuses`
+    /// (repeating the last one) and `body`, returning its base URL and a request counter
+    async fn mock_fcm(statuses: Vec<u16>, body: &'static str) -> (String, Arc<AtomicUsize>) {
+        use axum::{extract::State, http::StatusCode, Router};
+
+        let hits = Arc::new(AtomicUsize::new(0));
+        let app = Router::new()
+            .fallback(
+                move |State((hits, statuses)): State<(Arc<AtomicUsize>, Arc<Vec<u16>>)>| async move {
+                    let n = hits.fetch_add(1, Ordering::SeqCst);
+                    let status = statuses[n.min(statuses.len() - 1)];
+                    (StatusCode::from_u16(status).unwrap(), body)
+                },
+            )
+            .with_state((hits.clone(), Arc::new(statuses)));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        (format!("http://{}", addr), hits)
+    }
+
+    fn service_with_attempts(max_attempts: u32) -> NotificationService {
+        let mut config = test_support::config();
+        config.fcm_max_attempts = max_attempts;
+        NotificationService::new(test_support::lazy_database(&config), &config)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_success() {
+        let (base, hits) = mock_fcm(vec![503, 503, 200], "").await;
+        let service = service_with_attempts(3);
+
+        let response = service
+            .send_with_retry(|| service.http_client.post(format!("{}/send", base)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (base, hits) = mock_fcm(vec![503], "").await;
+        let service = service_with_attempts(2);
+
+        let response = service
+            .send_with_retry(|| service.http_client.post(format!("{}/send", base)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 503);
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn client_errors_are_not_retried() {
+        let (base, hits) = mock_fcm(vec![400, 200], "").await;
+        let service = service_with_attempts(3);
+
+        let response = service
+            .send_with_retry(|| service.http_client.post(format!("{}/send", base)))
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 400);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[sqlx::test]
+    #[ignore = "needs a Postgres DATABASE_URL"]
+    async fn unregistered_token_is_cleared_from_the_user(pool: PgPool) {
+        let stale_user = test_support::seed_user(&pool, "stale_device").await;
+        let other_user = test_support::seed_user(&pool, "other_device").await;
+        for (user_id, token) in [(&stale_user, "stale-token"), (&other_user, "live-token")] {
+            sqlx::query(r#"UPDATE "User" SET fcm_token = $1 WHERE id = $2"#)
+                .bind(token)
+                .bind(user_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let (base, hits) = mock_fcm(
+            vec![404],
+            r#"{"error": {"code": 404, "status": "NOT_FOUND", "details": [
+                {"@type": "type.googleapis.com/google.firebase.fcm.v1.FcmError",
+                 "errorCode": "UNREGISTERED"}]}}"#,
+        )
+        .await;
+        let mut service = NotificationService::new(
+            test_support::database(pool.clone()),
+            &test_support::config(),
+        );
+        service.api_base = base;
+
+        service
+            .send_to_tokens(
+                "project",
+                "access-token",
+                vec!["stale-token".to_string()],
+                "title",
+                "body",
+                "novel",
+                "chapter",
+            )
+            .await;
+
+        // 4xx is final, so exactly one send
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+        let token_of = |user_id: String| {
+            let pool = pool.clone();
+            async move {
+                sqlx::query_scalar::<_, Option<String>>(
+                    r#"SELECT fcm_token FROM "User" WHERE id = $1"#,
+                )
+                .bind(user_id)
+                .fetch_one(&pool)
+                .await
+                .unwrap()
+            }
+        };
+        assert_eq!(token_of(stale_user).await, None);
+        assert_eq!(token_of(other_user).await.as_deref(), Some("live-token"));
+    }
+}
@@ -0,0 +1,84 @@
+use crate::database::Database;
+use crate::errors::{AppError, AppResult};
+use crate::models::reading_progress_model::{
+    ReadingProgress, ReadingProgressDto, UpdateReadingProgressDto,
+};
+use chrono::Utc;
+
+pub struct ReadingProgressService {
+    db: Database,
+}
+
+impl ReadingProgressService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn upsert_progress(
+        &self,
+        user_id: &str,
+        book_id: String,
+        request: UpdateReadingProgressDto,
+    ) -> AppResult<ReadingProgressDto> {
+        if !request.percent.is_finite() || !(0.0..=100.0).contains(&request.percent) {
+            return Err(AppError::Validation(
+                "percent must be between 0 and 100".to_string(),
+            ));
+        }
+
+        let chapter_num: Option<i32> = sqlx::query_scalar(
+            r#"SELECT chapter_num FROM "Chapter" WHERE id = $1 AND book_id = $2"#,
+        )
+        .bind(&request.chapter_id)
+        .bind(&book_id)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        let chapter_num = chapter_num
+            .ok_or_else(|| AppError::NotFound("Chapter not found in this book".to_string()))?;
+
+        let progress = sqlx::query_as::<_, ReadingProgress>(
+            r#"
+            INSERT INTO "ReadingProgress" (user_id, book_id, chapter_id, chapter_num, percent, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (user_id, book_id) DO UPDATE
+            SET chapter_id = EXCLUDED.chapter_id,
+                chapter_num = EXCLUDED.chapter_num,
+                percent = EXCLUDED.percent,
+                updated_at = EXCLUDED.updated_at
+            RETURNING user_id, book_id, chapter_id, chapter_num, percent, updated_at
+            "#,
+        )
+        .bind(user_id)
+        .bind(&book_id)
+        .bind(&request.chapter_id)
+        .bind(chapter_num)
+        .bind(request.percent)
+        .bind(Utc::now())
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        Ok(progress.into())
+    }
+
+    pub async fn get_progress(
+        &self,
+        user_id: &str,
+        book_id: String,
+    ) -> AppResult<ReadingProgressDto> {
+        let progress = sqlx::query_as::<_, ReadingProgress>(
+            r#"
+            SELECT user_id, book_id, chapter_id, chapter_num, percent, updated_at
+            FROM "ReadingProgress"
+            WHERE user_id = $1 AND book_id = $2
+            "#,
+        )
+        .bind(user_id)
+        .bind(&book_id)
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No reading progress for this book".to_string()))?;
+
+        Ok(progress.into())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::database::Database;
+use crate::errors::{AppError, AppResult};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use crate::models::review_model::{
+    CreateReviewDto, RatingSummary, Review, ReviewDto, UpdateReviewDto, MAX_RATING, MIN_RATING,
+};
+use chrono::Utc;
+use cuid2;
+
+pub struct ReviewService {
+    db: Database,
+}
+
+impl ReviewService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    fn validate_rating(rating: i16) -> AppResult<()> {
+        if !(MIN_RATING..=MAX_RATING).contains(&rating) {
+            return Err(AppError::Unprocessable(format!(
+                "rating must be between {} and {}",
+                MIN_RATING, MAX_RATING
+            )));
+        }
+        Ok(())
+    }
+
+    pub async fn create_review(
+        &self,
+        user_id: &str,
+        book_id: String,
+        request: CreateReviewDto,
+    ) -> AppResult<ReviewDto> {
+        Self::validate_rating(request.rating)?;
+
+        let book_exists: bool =
+            sqlx::query_scalar(r#"SELECT EXISTS(SELECT 1 FROM "Book" WHERE id = $1)"#)
+                .bind(&book_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        if !book_exists {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let now = Utc::now();
+        let review = sqlx::query_as::<_, Review>(
+            r#"
+            INSERT INTO "Review" (id, user_id, book_id, rating, body, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            RETURNING id, user_id, book_id, rating, body, created_at, updated_at
+            "#,
+        )
+        .bind(cuid2::create_id())
+        .bind(user_id)
+        .bind(&book_id)
+        .bind(request.rating)
+        .bind(&request.body)
+        .bind(now)
+        .bind(now)
+        .fetch_one(&self.db.pool)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+                AppError::Conflict("You have already reviewed this book".to_string())
+            }
+            e => e.into(),
+        })?;
+
+        self.invalidate(&book_id).await;
+        Ok(review.into())
+    }
+
+    pub async fn update_review(
+        &self,
+        user_id: &str,
+        id: String,
+        request: UpdateReviewDto,
+    ) -> AppResult<ReviewDto> {
+        if let Some(rating) = request.rating {
+            Self::validate_rating(rating)?;
+        }
+
+        let review = sqlx::query_as::<_, Review>(
+            r#"
+            UPDATE "Review"
+            SET rating = COALESCE($1, rating),
+                body = COALESCE($2, body),
+                updated_at = $3
+            WHERE id = $4 AND user_id = $5
+            RETURNING id, user_id, book_id, rating, body, created_at, updated_at
+            "#,
+        )
+        .bind(request.rating)
+        .bind(&request.body)
+        .bind(Utc::now())
+        .bind(&id)
+        .bind(user_id)
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+        self.invalidate(&review.book_id).await;
+        Ok(review.into())
+    }
+
+    /// Deletes a review owned by `user_id`; `moderate` lifts the ownership check.
+    pub async fn delete_review(&self, user_id: &str, id: String, moderate: bool) -> AppResult<()> {
+        let book_id: Option<String> = sqlx::query_scalar(
+            r#"DELETE FROM "Review" WHERE id = $1 AND ($3 OR user_id = $2) RETURNING book_id"#,
+        )
+        .bind(&id)
+        .bind(user_id)
+        .bind(moderate)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        let book_id = book_id.ok_or_else(|| AppError::NotFound("Review not found".to_string()))?;
+
+        self.invalidate(&book_id).await;
+        Ok(())
+    }
+
+    pub async fn get_reviews_by_book(
+        &self,
+        book_id: String,
+        params: PaginationParams,
+    ) -> AppResult<PaginatedResponse<ReviewDto>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let redis = &self.db.redis;
+        let cache_key = format!(
+            "reviews:book:{}:page:{}:size:{}",
+            book_id, params.page, params.page_size
+        );
+
+        if let Ok(Some(cached_response)) = redis
+            .get_json::<PaginatedResponse<ReviewDto>>(&cache_key)
+            .await
+        {
+            return Ok(cached_response);
+        }
+
+        let total_items =
+            sqlx::query_scalar::<_, i64>(r#"SELECT COUNT(*) FROM "Review" WHERE book_id = $1"#)
+                .bind(&book_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        let reviews = sqlx::query_as::<_, Review>(
+            r#"
+            SELECT id, user_id, book_id, rating, body, created_at, updated_at
+            FROM "Review"
+            WHERE book_id = $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&book_id)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<ReviewDto> = reviews.into_iter().map(ReviewDto::from).collect();
+        let response = PaginatedResponse::new(data, params.page, params.page_size, total_items);
+
+        let _ = redis.set_json(&cache_key, &response, 600).await;
+
+        Ok(response)
+    }
+
+    pub async fn get_rating_summary(&self, book_id: &str) -> AppResult<RatingSummary> {
+        let summary = sqlx::query_as::<_, RatingSummary>(
+            r#"
+            SELECT AVG(rating)::DOUBLE PRECISION AS average, COUNT(*) AS count
+            FROM "Review"
+            WHERE book_id = $1
+            "#,
+        )
+        .bind(book_id)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        Ok(summary)
+    }
+
+    /// Drop the cached book (its rating summary) and review pages after reviews change
+    pub async fn invalidate(&self, book_id: &str) {
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("book:{}", book_id)).await;
+        let _ = redis
+            .del_prefix(&format!("reviews:book:{}:", book_id))
+            .await;
+    }
+}
@@ -0,0 +1,123 @@
+use crate::database::Database;
+use crate::errors::{AppError, AppResult};
+use crate::models::book_model::{Book, BookDto};
+use crate::models::series_model::{CreateSeriesDto, SeriesDto, SeriesInfo, SeriesWithBookCount};
+use crate::utils::slug::{map_slug_conflict, unique_slug};
+use chrono::Utc;
+
+pub struct SeriesService {
+    db: Database,
+}
+
+impl SeriesService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn create_series(&self, request: CreateSeriesDto) -> AppResult<SeriesDto> {
+        let slug = unique_slug(&self.db.pool, "Series", &request.title, "series", None).await?;
+
+        let series = sqlx::query_as::<_, SeriesWithBookCount>(
+            r#"
+            INSERT INTO "Series" (id, title, slug, description, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $5)
+            RETURNING id, title, slug, description, created_at, updated_at, 0::BIGINT AS book_count
+            "#,
+        )
+        .bind(cuid2::create_id())
+        .bind(request.title.trim())
+        .bind(&slug)
+        .bind(&request.description)
+        .bind(Utc::now())
+        .fetch_one(&self.db.pool)
+        .await
+        .map_err(map_slug_conflict)?;
+
+        Ok(series.into())
+    }
+
+    pub async fn get_series(&self, id: String) -> AppResult<SeriesDto> {
+        let redis = &self.db.redis;
+        let cache_key = format!("series:{id}");
+
+        if let Ok(Some(cached)) = redis.get_json::<SeriesDto>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        let series = sqlx::query_as::<_, SeriesWithBookCount>(
+            r#"
+            SELECT s.id, s.title, s.slug, s.description, s.created_at, s.updated_at,
+                   (SELECT COUNT(*) FROM "Book" b WHERE b.series_id = s.id) AS book_count
+            FROM "Series" s
+            WHERE s.id = $1
+            "#,
+        )
+        .bind(&id)
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Series not found".to_string()))?;
+
+        let data: SeriesDto = series.into();
+        let _ = redis.set_json(&cache_key, &data, 600).await;
+
+        Ok(data)
+    }
+
+    /// Every book in the series, in reading order
+    pub async fn get_books_in_series(&self, id: String) -> AppResult<Vec<BookDto>> {
+        let redis = &self.db.redis;
+        let cache_key = format!("series:{id}:books");
+
+        if let Ok(Some(cached)) = redis.get_json::<Vec<BookDto>>(&cache_key).await {
+            return Ok(cached);
+        }
+
+        // Distinguish an unknown series from an empty one
+        self.get_series(id.clone()).await?;
+
+        let books = sqlx::query_as::<_, Book>(
+            r#"
+            SELECT id, title, slug, author, author_id, cover, description, asset,
+                   series_id, series_position,
+                   status, language, release_date, popular, views, version,
+                   created_at, updated_at
+            FROM "Book"
+            WHERE series_id = $1
+            ORDER BY series_position ASC
+            "#,
+        )
+        .bind(&id)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let data: Vec<BookDto> = books.into_iter().map(BookDto::from).collect();
+        let _ = redis.set_json(&cache_key, &data, 600).await;
+
+        Ok(data)
+    }
+
+    /// Series placement of `book_id`, or `None` when it stands alone
+    pub async fn get_series_info(&self, book_id: &str) -> AppResult<Option<SeriesInfo>> {
+        let info = sqlx::query_as::<_, SeriesInfo>(
+            r#"
+            SELECT s.id, s.title, s.slug, b.series_position AS position,
+                   (SELECT COUNT(*) FROM "Book" sb WHERE sb.series_id = s.id) AS book_count
+            FROM "Book" b
+            INNER JOIN "Series" s ON s.id = b.series_id
+            WHERE b.id = $1
+            "#,
+        )
+        .bind(book_id)
+        .fetch_optional(&self.db.pool)
+        .await?;
+
+        Ok(info)
+    }
+
+    /// Drop cached views of a series after its membership changed
+    pub async fn invalidate(&self, series_id: &str) {
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("series:{series_id}")).await;
+        let _ = redis.del(&format!("series:{series_id}:books")).await;
+    }
+}
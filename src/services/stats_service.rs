@@ -0,0 +1,48 @@
+use crate::database::Database;
+use crate::errors::AppResult;
+use crate::models::stats_model::SiteStatsDto;
+use chrono::{Duration, Utc};
+
+const STATS_CACHE_KEY: &str = "stats:site";
+const STATS_CACHE_TTL_SECS: i64 = 60;
+
+pub struct StatsService {
+    db: Database,
+}
+
+impl StatsService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_site_stats(&self) -> AppResult<SiteStatsDto> {
+        let redis = &self.db.redis;
+
+        if let Ok(Some(cached)) = redis.get_json::<SiteStatsDto>(STATS_CACHE_KEY).await {
+            return Ok(cached);
+        }
+
+        let since = Utc::now().naive_utc() - Duration::days(7);
+
+        // One round trip for every total
+        let stats = sqlx::query_as::<_, SiteStatsDto>(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM "Book") AS total_books,
+                (SELECT COUNT(*) FROM "Chapter") AS total_chapters,
+                (SELECT COUNT(*) FROM "User") AS total_users,
+                (SELECT COUNT(*) FROM "Bookmark") AS total_bookmarks,
+                (SELECT COUNT(*) FROM "Book" WHERE created_at >= $1) AS books_last_7_days
+            "#,
+        )
+        .bind(since)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        let _ = redis
+            .set_json(STATS_CACHE_KEY, &stats, STATS_CACHE_TTL_SECS)
+            .await;
+
+        Ok(stats)
+    }
+}
@@ -1,10 +1,17 @@
 use crate::config::Config;
-use crate::errors::AppResult;
+use crate::errors::{AppError, AppResult};
 use aws_config::Region;
 use aws_credential_types::Credentials;
 use aws_sdk_s3::config::Builder as S3ConfigBuilder;
+use aws_sdk_s3::presigning::PresigningConfig;
 use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::types::{Delete, ObjectIdentifier};
 use aws_sdk_s3::Client;
+use std::time::Duration;
+use tracing::warn;
+
+/// S3 `DeleteObjects` accepts at most 1000 keys per request
+const DELETE_BATCH_SIZE: usize = 1000;
 
 #[derive(Clone)]
 pub struct StorageService {
@@ -70,15 +77,110 @@ impl StorageService {
         bytes: Vec<u8>,
         content_type: &str,
     ) -> AppResult<String> {
-        let key = format!("{}/{}/{}", folder, book_id, filename);
+        let key = Self::image_key(folder, book_id, filename);
         self.upload_bytes(&key, bytes, content_type).await
     }
 
+    /// Object key used by `upload_image`
+    pub fn image_key(folder: &str, book_id: &str, filename: &str) -> String {
+        format!("{}/{}/{}", folder, book_id, filename)
+    }
+
+    /// Whether an object is already stored under `key`
+    pub async fn object_exists(&self, key: &str) -> AppResult<bool> {
+        match self
+            .client
+            .head_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e)
+                if e.as_service_error().is_some_and(|err| err.is_not_found())
+                    || e.raw_response()
+                        .is_some_and(|response| response.status().as_u16() == 404) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(AppError::Internal(format!("R2 head failed: {}", e))),
+        }
+    }
+
+    /// Presigned PUT URL so clients can upload straight to R2.
+    /// The client must send the same Content-Type header that was signed.
+    pub async fn presign_put(
+        &self,
+        key: &str,
+        content_type: &str,
+        expires: Duration,
+    ) -> AppResult<String> {
+        let presigning_config = PresigningConfig::expires_in(expires)
+            .map_err(|e| AppError::Internal(format!("Invalid presign expiry: {}", e)))?;
+
+        let request = self
+            .client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .content_type(content_type)
+            .presigned(presigning_config)
+            .await
+            .map_err(|e| AppError::Internal(format!("R2 presign failed: {}", e)))?;
+
+        Ok(request.uri().to_string())
+    }
+
+    /// Stream an object back from R2
+    pub async fn get_object(&self, key: &str) -> AppResult<ByteStream> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(key)
+            .send()
+            .await
+            .map_err(|e| {
+                let is_missing = e.as_service_error().is_some_and(|err| err.is_no_such_key())
+                    || e.raw_response()
+                        .is_some_and(|response| response.status().as_u16() == 404);
+
+                if is_missing {
+                    AppError::NotFound(format!("Object not found: {}", key))
+                } else {
+                    AppError::Internal(format!("R2 download failed: {}", e))
+                }
+            })?;
+
+        Ok(output.body)
+    }
+
+    /// Read a whole object into memory
+    pub async fn get_bytes(&self, key: &str) -> AppResult<Vec<u8>> {
+        let data = self
+            .get_object(key)
+            .await?
+            .collect()
+            .await
+            .map_err(|e| AppError::Internal(format!("R2 download failed: {}", e)))?;
+
+        Ok(data.into_bytes().to_vec())
+    }
+
     /// Generate CDN URL for a key
     pub fn get_public_url(&self, key: &str) -> String {
         format!("{}/{}", self.cdn_url.trim_end_matches('/'), key)
     }
 
+    /// Recover the object key from a CDN URL issued by this service
+    pub fn key_from_public_url(&self, url: &str) -> Option<String> {
+        let prefix = format!("{}/", self.cdn_url.trim_end_matches('/'));
+        url.strip_prefix(&prefix)
+            .filter(|key| !key.is_empty())
+            .map(str::to_string)
+    }
+
     /// Delete a file from R2
     pub async fn delete_file(&self, key: &str) -> AppResult<()> {
         self.client
@@ -91,4 +193,82 @@ impl StorageService {
 
         Ok(())
     }
+
+    /// Delete many keys using batched `DeleteObjects` calls, returning how many were removed
+    pub async fn delete_files(&self, keys: &[String]) -> AppResult<usize> {
+        let mut deleted = 0;
+
+        for batch in keys.chunks(DELETE_BATCH_SIZE) {
+            let objects = batch
+                .iter()
+                .map(|key| ObjectIdentifier::builder().key(key).build())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::Internal(format!("Invalid R2 key: {}", e)))?;
+
+            let delete = Delete::builder()
+                .set_objects(Some(objects))
+                .quiet(true)
+                .build()
+                .map_err(|e| AppError::Internal(format!("Invalid R2 delete request: {}", e)))?;
+
+            let output = self
+                .client
+                .delete_objects()
+                .bucket(&self.bucket)
+                .delete(delete)
+                .send()
+                .await
+                .map_err(|e| AppError::Internal(format!("R2 batch delete failed: {}", e)))?;
+
+            // Quiet mode only reports failures
+            let failed = output.errors().len();
+            for error in output.errors() {
+                warn!(key = ?error.key(), code = ?error.code(), "Failed to delete object from R2");
+            }
+            deleted += batch.len() - failed;
+        }
+
+        Ok(deleted)
+    }
+
+    /// Delete every object under a prefix such as `content-images/{book_id}/`
+    pub async fn delete_prefix(&self, prefix: &str) -> AppResult<usize> {
+        // An empty prefix would wipe the whole bucket
+        if prefix.trim_matches('/').is_empty() {
+            return Err(AppError::BadRequest(
+                "Refusing to delete an empty prefix".to_string(),
+            ));
+        }
+
+        let mut keys: Vec<String> = Vec::new();
+        let mut continuation_token: Option<String> = None;
+
+        loop {
+            let output = self
+                .client
+                .list_objects_v2()
+                .bucket(&self.bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token.take())
+                .send()
+                .await
+                .map_err(|e| AppError::Internal(format!("R2 list failed: {}", e)))?;
+
+            keys.extend(
+                output
+                    .contents()
+                    .iter()
+                    .filter_map(|object| object.key().map(str::to_string)),
+            );
+
+            match output.next_continuation_token() {
+                Some(token) if output.is_truncated().unwrap_or(false) => {
+                    continuation_token = Some(token.to_string());
+                }
+                _ => break,
+            }
+        }
+
+        self.delete_files(&keys).await
+    }
 }
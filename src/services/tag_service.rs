@@ -0,0 +1,123 @@
+use crate::database::Database;
+use crate::errors::{AppError, AppResult};
+use crate::models::tag_model::{normalize_tag, AddBookTagsDto, Tag, TagDto};
+
+pub struct TagService {
+    db: Database,
+}
+
+impl TagService {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    pub async fn get_tags_by_book(&self, book_id: String) -> AppResult<Vec<TagDto>> {
+        let redis = &self.db.redis;
+        let cache_key = format!("book:{}:tags", book_id);
+
+        if let Ok(Some(cached_tags)) = redis.get_json::<Vec<TagDto>>(&cache_key).await {
+            return Ok(cached_tags);
+        }
+
+        let tags = sqlx::query_as::<_, Tag>(
+            r#"
+            SELECT t.id, t.name, t.created_at
+            FROM "Tag" t
+            INNER JOIN "BookTag" bt ON t.id = bt.tag_id
+            WHERE bt.book_id = $1
+            ORDER BY t.name ASC
+            "#,
+        )
+        .bind(&book_id)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        let tags_dto: Vec<TagDto> = tags.into_iter().map(Into::into).collect();
+        let _ = redis.set_json(&cache_key, &tags_dto, 600).await;
+
+        Ok(tags_dto)
+    }
+
+    /// Attach tags by name, creating any that do not exist yet
+    pub async fn add_tags_to_book(
+        &self,
+        book_id: String,
+        request: AddBookTagsDto,
+    ) -> AppResult<Vec<TagDto>> {
+        let names = request.normalized()?;
+
+        let book_exists: bool =
+            sqlx::query_scalar(r#"SELECT EXISTS(SELECT 1 FROM "Book" WHERE id = $1)"#)
+                .bind(&book_id)
+                .fetch_one(&self.db.pool)
+                .await?;
+
+        if !book_exists {
+            return Err(AppError::NotFound("Book not found".to_string()));
+        }
+
+        let ids: Vec<String> = names.iter().map(|_| cuid2::create_id()).collect();
+        let mut tx = self.db.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO "Tag" (id, name)
+            SELECT * FROM UNNEST($1::TEXT[], $2::TEXT[])
+            ON CONFLICT (name) DO NOTHING
+            "#,
+        )
+        .bind(&ids)
+        .bind(&names)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO "BookTag" (book_id, tag_id)
+            SELECT $1, id FROM "Tag" WHERE name = ANY($2)
+            ON CONFLICT (book_id, tag_id) DO NOTHING
+            "#,
+        )
+        .bind(&book_id)
+        .bind(&names)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+
+        self.invalidate_book_tags(&book_id).await;
+        self.get_tags_by_book(book_id).await
+    }
+
+    pub async fn remove_tag_from_book(&self, book_id: String, tag: String) -> AppResult<()> {
+        let name = normalize_tag(&tag)?;
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM "BookTag" bt
+            USING "Tag" t
+            WHERE bt.tag_id = t.id AND bt.book_id = $1 AND t.name = $2
+            "#,
+        )
+        .bind(&book_id)
+        .bind(&name)
+        .execute(&self.db.pool)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound(
+                "Tag is not assigned to this book".to_string(),
+            ));
+        }
+
+        self.invalidate_book_tags(&book_id).await;
+        Ok(())
+    }
+
+    async fn invalidate_book_tags(&self, book_id: &str) {
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("book:{}", book_id)).await;
+        let _ = redis.del(&format!("book:{}:tags", book_id)).await;
+        let _ = redis.del_prefix("books:list:").await;
+    }
+}
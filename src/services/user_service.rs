@@ -0,0 +1,177 @@
+use crate::database::Database;
+use crate::errors::{map_still_referenced, AppError, AppResult};
+use crate::models::paging_model::{page_offset, PaginatedResponse, PaginationParams};
+use crate::models::user_model::{Role, SafeUser};
+use crate::services::book_service::BookService;
+use crate::services::review_service::ReviewService;
+use crate::services::storage_service::StorageService;
+use crate::utils::password::PasswordService;
+use chrono::Utc;
+use tracing::warn;
+
+pub struct UserService {
+    db: Database,
+    storage: StorageService,
+}
+
+impl UserService {
+    pub fn new(db: Database, storage: StorageService) -> Self {
+        Self { db, storage }
+    }
+
+    pub async fn list_users(
+        &self,
+        params: PaginationParams,
+    ) -> AppResult<PaginatedResponse<SafeUser>> {
+        let offset = page_offset(params.page, params.page_size)?;
+        let search = params
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| format!("%{}%", s));
+
+        let total_items = sqlx::query_scalar::<_, i64>(
+            r#"
+            SELECT COUNT(*) FROM "User"
+            WHERE $1::TEXT IS NULL OR username ILIKE $1 OR email ILIKE $1
+            "#,
+        )
+        .bind(&search)
+        .fetch_one(&self.db.pool)
+        .await?;
+
+        let users = sqlx::query_as::<_, SafeUser>(
+            r#"
+            SELECT id, username, email, bio, profile_pic, role, created_at, updated_at
+            FROM "User"
+            WHERE $1::TEXT IS NULL OR username ILIKE $1 OR email ILIKE $1
+            ORDER BY created_at DESC, id DESC
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+        .bind(&search)
+        .bind(params.page_size)
+        .bind(offset)
+        .fetch_all(&self.db.pool)
+        .await?;
+
+        Ok(PaginatedResponse::new(
+            users,
+            params.page,
+            params.page_size,
+            total_items,
+        ))
+    }
+
+    pub async fn update_role(&self, actor_id: &str, id: &str, role: Role) -> AppResult<SafeUser> {
+        if actor_id == id {
+            return Err(AppError::BadRequest(
+                "You cannot change your own role".to_string(),
+            ));
+        }
+
+        let user = sqlx::query_as::<_, SafeUser>(
+            r#"
+            UPDATE "User"
+            SET role = $2, updated_at = $3
+            WHERE id = $1
+            RETURNING id, username, email, bio, profile_pic, role, created_at, updated_at
+            "#,
+        )
+        .bind(id)
+        .bind(role)
+        .bind(Utc::now())
+        .fetch_optional(&self.db.pool)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        let _ = self.db.redis.del(&format!("user:{id}")).await;
+        let _ = self.db.redis.del(&format!("user:role:{id}")).await;
+
+        Ok(user)
+    }
+
+    pub async fn delete_user(&self, actor_id: &str, id: &str) -> AppResult<()> {
+        if actor_id == id {
+            return Err(AppError::BadRequest(
+                "You cannot delete your own account".to_string(),
+            ));
+        }
+
+        self.remove_user(id).await
+    }
+
+    /// Delete the caller's own account after re-checking their password
+    pub async fn delete_own_account(&self, id: &str, password: &str) -> AppResult<()> {
+        let hash = sqlx::query_scalar::<_, String>(r#"SELECT password FROM "User" WHERE id = $1"#)
+            .bind(id)
+            .fetch_optional(&self.db.pool)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        if !PasswordService::verify_password(password, &hash).unwrap_or(false) {
+            return Err(AppError::BadRequest("Invalid password".to_string()));
+        }
+
+        self.remove_user(id).await
+    }
+
+    /// Delete a user with everything keyed to them in one transaction, then clear
+    /// caches and stored avatars. Reading progress and notifications cascade.
+    async fn remove_user(&self, id: &str) -> AppResult<()> {
+        let mut tx = self.db.pool.begin().await?;
+
+        // Bookmark still references User with ON DELETE RESTRICT
+        let bookmarked: Vec<String> =
+            sqlx::query_scalar(r#"DELETE FROM "Bookmark" WHERE user_id = $1 RETURNING book_id"#)
+                .bind(id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        // Reviews would cascade, but their books' cached ratings need refreshing
+        let reviewed: Vec<String> =
+            sqlx::query_scalar(r#"DELETE FROM "Review" WHERE user_id = $1 RETURNING book_id"#)
+                .bind(id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        // Stored replays hold response bodies and have no foreign key to cascade from
+        sqlx::query(r#"DELETE FROM "IdempotencyKey" WHERE user_id = $1"#)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        let username: Option<String> =
+            sqlx::query_scalar(r#"DELETE FROM "User" WHERE id = $1 RETURNING username"#)
+                .bind(id)
+                .fetch_optional(&mut *tx)
+                .await
+                .map_err(map_still_referenced)?;
+
+        let username = username.ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+        tx.commit().await?;
+
+        let redis = &self.db.redis;
+        let _ = redis.del(&format!("user:{id}")).await;
+        let _ = redis.del(&format!("user:{username}")).await;
+        let _ = redis.del(&format!("user:role:{id}")).await;
+
+        let books = BookService::new(self.db.clone(), self.storage.clone());
+        for book_id in &bookmarked {
+            books.invalidate_bookmark_count(book_id).await;
+        }
+
+        let reviews = ReviewService::new(self.db.clone());
+        for book_id in &reviewed {
+            reviews.invalidate(book_id).await;
+        }
+
+        if let Err(e) = self.storage.delete_prefix(&format!("avatars/{id}/")).await {
+            warn!(error = ?e, user_id = %id, "Failed to delete avatars for removed user");
+        }
+
+        Ok(())
+    }
+}
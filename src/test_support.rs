@@ -0,0 +1,133 @@
+//! Fixtures shared by unit tests that need configuration or a seeded database.
+//! Database tests run under `#[sqlx::test]`, which creates a fresh database from
+//! `migrations/` for each test; they are ignored unless `DATABASE_URL` points at a
+//! Postgres server (`make test-db`).
+
+use crate::config::Config;
+use crate::database::Database;
+use crate::redis::RedisClient;
+use crate::services::notification_service::NotificationService;
+use crate::services::storage_service::StorageService;
+use crate::{AppState, AppStateInner};
+use chrono::{Duration, Utc};
+use sqlx::postgres::PgPoolOptions;
+use sqlx::PgPool;
+use std::sync::{Arc, Once};
+use std::time::Instant;
+
+/// Placeholders for every variable `Config::from_env` requires
+const REQUIRED_ENV: &[(&str, &str)] = &[
+    ("DATABASE_URL", "postgres://localhost/unused"),
+    (
+        "JWT_SECRET_KEY",
+        "test-secret-key-that-is-long-enough-for-hs256",
+    ),
+    ("JWT_ACCESS_EXPIRES_IN", "3600"),
+    ("JWT_REFRESH_EXPIRES_IN", "604800"),
+    ("API_KEY", "web:test-api-key"),
+    ("EMAIL", "test@example.com"),
+    ("PASSWORD", "password"),
+    ("AWS_ACCESS_KEY_ID", "test-access-key"),
+    ("AWS_SECRET_ACCESS_KEY", "test-secret"),
+    ("AWS_ENDPOINT", "https://storage.example.com"),
+    ("AWS_BUCKET", "test-bucket"),
+    ("AWS_URL", "https://cdn.example.com"),
+    ("PORT", "4000"),
+];
+
+/// `Config::from_env` with placeholders filled in for anything the environment lacks
+pub fn config() -> Config {
+    static ENV: Once = Once::new();
+    ENV.call_once(|| {
+        for (key, value) in REQUIRED_ENV {
+            if std::env::var_os(key).is_none() {
+                std::env::set_var(key, value);
+            }
+        }
+    });
+
+    Config::from_env().expect("test configuration should load")
+}
+
+/// A `Database` over `pool` with caching disabled, so every read hits Postgres
+pub fn database(pool: PgPool) -> Database {
+    Database {
+        pool,
+        redis: RedisClient::disabled(),
+    }
+}
+
+/// A `Database` whose pool only connects on first use, for code under test that
+/// never queries. Must be built inside a Tokio runtime.
+pub fn lazy_database(config: &Config) -> Database {
+    let pool = PgPoolOptions::new()
+        .connect_lazy(&config.database_url)
+        .expect("lazy pool");
+    database(pool)
+}
+
+/// Application state for handlers and middleware that never reach the database
+pub fn app_state(config: Config) -> AppState {
+    let db = lazy_database(&config);
+    app_state_with(config, db)
+}
+
+pub fn app_state_with(config: Config, db: Database) -> AppState {
+    Arc::new(AppStateInner {
+        storage: StorageService::new(&config),
+        notification: Arc::new(NotificationService::new(db.clone(), &config)),
+        db,
+        config,
+        started_at: Instant::now(),
+    })
+}
+
+pub async fn seed_user(pool: &PgPool, username: &str) -> String {
+    let id = cuid2::create_id();
+    sqlx::query(
+        r#"
+        INSERT INTO "User" (id, username, email, password, updated_at)
+        VALUES ($1, $2, $3, 'not-a-real-hash', NOW())
+        "#,
+    )
+    .bind(&id)
+    .bind(username)
+    .bind(format!("{}@example.com", username))
+    .execute(pool)
+    .await
+    .expect("seed user");
+    id
+}
+
+/// A book by a fresh author, created `age_days` ago
+pub async fn seed_book(pool: &PgPool, title: &str, age_days: i64) -> String {
+    let author_id = cuid2::create_id();
+    sqlx::query(r#"INSERT INTO "Author" (id, name) VALUES ($1, $2)"#)
+        .bind(&author_id)
+        .bind(format!("Author of {}", title))
+        .execute(pool)
+        .await
+        .expect("seed author");
+
+    let id = cuid2::create_id();
+    let created_at = (Utc::now() - Duration::days(age_days)).naive_utc();
+    sqlx::query(
+        r#"
+        INSERT INTO "Book" (
+            id, title, slug, author, author_id, cover, description,
+            created_at, updated_at
+        )
+        VALUES ($1, $2, $3, $4, $5, '', '', $6, $6)
+        "#,
+    )
+    .bind(&id)
+    .bind(title)
+    .bind(format!("{}-{}", title.to_lowercase().replace(' ', "-"), id))
+    .bind(format!("Author of {}", title))
+    .bind(&author_id)
+    .bind(created_at)
+    .execute(pool)
+    .await
+    .expect("seed book");
+    id
+}
@@ -0,0 +1,299 @@
+use crate::errors::{AppError, AppResult};
+use crate::models::auth_model::{RegisterDto, UpdateProfileDto};
+use crate::models::author_model::CreateAuthorDto;
+use crate::models::book_model::{CreateBookDto, UpdateBookDto};
+use crate::models::chapter_model::{CreateChapterDto, UpdateChapterDto};
+use crate::models::genre_model::{CreateGenreDto, UpdateGenreDto};
+use crate::models::review_model::{CreateReviewDto, UpdateReviewDto};
+use crate::models::series_model::CreateSeriesDto;
+use crate::models::tag_model::AddBookTagsDto;
+use crate::models::upload_model::CreateChapterFromUploadDto;
+use serde::{Deserialize, Serialize};
+
+/// Maximum lengths (in characters) for user-submitted text fields
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldLimits {
+    pub title: usize,
+    pub author: usize,
+    pub description: usize,
+    pub genre_name: usize,
+    pub tag_name: usize,
+    pub username: usize,
+    pub bio: usize,
+}
+
+impl Default for FieldLimits {
+    fn default() -> Self {
+        Self {
+            title: 255,
+            author: 255,
+            description: 5000,
+            genre_name: 64,
+            tag_name: 32,
+            username: 32,
+            bio: 500,
+        }
+    }
+}
+
+impl FieldLimits {
+    fn check(field: &str, value: &str, max: usize) -> AppResult<()> {
+        if value.chars().count() > max {
+            return Err(AppError::FieldTooLong {
+                field: field.to_string(),
+                max,
+            });
+        }
+        Ok(())
+    }
+
+    fn check_optional(field: &str, value: Option<&str>, max: usize) -> AppResult<()> {
+        match value {
+            Some(value) => Self::check(field, value, max),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Implemented by request DTOs whose text fields are bounded by `FieldLimits`
+pub trait LengthLimited {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()>;
+}
+
+impl LengthLimited for CreateBookDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("title", &self.title, limits.title)?;
+        FieldLimits::check("author", &self.author, limits.author)?;
+        FieldLimits::check("description", &self.description, limits.description)
+    }
+}
+
+impl LengthLimited for UpdateBookDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("title", self.title.as_deref(), limits.title)?;
+        FieldLimits::check_optional("author", self.author.as_deref(), limits.author)?;
+        FieldLimits::check_optional(
+            "description",
+            self.description.as_deref(),
+            limits.description,
+        )
+    }
+}
+
+impl LengthLimited for CreateAuthorDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("name", &self.name, limits.author)
+    }
+}
+
+impl LengthLimited for CreateChapterDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("title", &self.title, limits.title)?;
+        FieldLimits::check("description", &self.description, limits.description)
+    }
+}
+
+impl LengthLimited for UpdateChapterDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("title", self.title.as_deref(), limits.title)?;
+        FieldLimits::check_optional(
+            "description",
+            self.description.as_deref(),
+            limits.description,
+        )
+    }
+}
+
+impl LengthLimited for CreateChapterFromUploadDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("title", &self.title, limits.title)?;
+        FieldLimits::check("description", &self.description, limits.description)
+    }
+}
+
+impl LengthLimited for CreateGenreDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("title", self.title.as_deref(), limits.genre_name)?;
+        FieldLimits::check_optional(
+            "description",
+            self.description.as_deref(),
+            limits.description,
+        )
+    }
+}
+
+impl LengthLimited for UpdateGenreDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("title", self.title.as_deref(), limits.genre_name)?;
+        FieldLimits::check_optional(
+            "description",
+            self.description.as_deref(),
+            limits.description,
+        )
+    }
+}
+
+impl LengthLimited for CreateSeriesDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("title", &self.title, limits.title)?;
+        FieldLimits::check("description", &self.description, limits.description)
+    }
+}
+
+impl LengthLimited for AddBookTagsDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        for tag in &self.tags {
+            FieldLimits::check("tags", tag.trim(), limits.tag_name)?;
+        }
+        Ok(())
+    }
+}
+
+impl LengthLimited for RegisterDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check("username", &self.username, limits.username)
+    }
+}
+
+impl LengthLimited for UpdateProfileDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("username", self.username.as_deref(), limits.username)?;
+        FieldLimits::check_optional("bio", self.bio.as_deref(), limits.bio)
+    }
+}
+
+impl LengthLimited for CreateReviewDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("body", self.body.as_deref(), limits.description)
+    }
+}
+
+impl LengthLimited for UpdateReviewDto {
+    fn check_lengths(&self, limits: &FieldLimits) -> AppResult<()> {
+        FieldLimits::check_optional("body", self.body.as_deref(), limits.description)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::de::DeserializeOwned;
+    use serde_json::json;
+
+    fn dto<T: DeserializeOwned>(value: serde_json::Value) -> T {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn too_long(result: AppResult<()>) -> (String, usize) {
+        match result {
+            Err(AppError::FieldTooLong { field, max }) => (field, max),
+            other => panic!("expected FieldTooLong, got {:?}", other),
+        }
+    }
+
+    fn book(title: &str, author: &str, description: &str) -> CreateBookDto {
+        dto(json!({
+            "title": title,
+            "author": author,
+            "cover": "https://cdn.example.com/cover.jpg",
+            "description": description,
+        }))
+    }
+
+    #[test]
+    fn limits_count_characters_not_bytes() {
+        let limits = FieldLimits::default();
+        let title = "é".repeat(limits.title);
+        assert!(book(&title, "Author", "").check_lengths(&limits).is_ok());
+    }
+
+    #[test]
+    fn rejects_long_book_title() {
+        let limits = FieldLimits::default();
+        let title = "a".repeat(limits.title + 1);
+        let result = book(&title, "Author", "").check_lengths(&limits);
+        assert_eq!(too_long(result), ("title".to_string(), limits.title));
+    }
+
+    #[test]
+    fn rejects_long_book_author() {
+        let limits = FieldLimits::default();
+        let author = "a".repeat(limits.author + 1);
+        let result = book("Title", &author, "").check_lengths(&limits);
+        assert_eq!(too_long(result), ("author".to_string(), limits.author));
+    }
+
+    #[test]
+    fn rejects_long_description() {
+        let limits = FieldLimits::default();
+        let description = "a".repeat(limits.description + 1);
+        let result = book("Title", "Author", &description).check_lengths(&limits);
+        assert_eq!(
+            too_long(result),
+            ("description".to_string(), limits.description)
+        );
+
+        let patch: UpdateBookDto = dto(json!({ "version": 1, "description": description }));
+        assert_eq!(
+            too_long(patch.check_lengths(&limits)).0,
+            "description".to_string()
+        );
+    }
+
+    #[test]
+    fn rejects_long_genre_name() {
+        let limits = FieldLimits::default();
+        let genre: CreateGenreDto = dto(json!({ "title": "g".repeat(limits.genre_name + 1) }));
+        assert_eq!(
+            too_long(genre.check_lengths(&limits)),
+            ("title".to_string(), limits.genre_name)
+        );
+    }
+
+    #[test]
+    fn rejects_long_username() {
+        let limits = FieldLimits::default();
+        let username = "u".repeat(limits.username + 1);
+        let register: RegisterDto = dto(json!({
+            "username": username,
+            "email": "reader@example.com",
+            "password": "correct horse",
+        }));
+        assert_eq!(
+            too_long(register.check_lengths(&limits)),
+            ("username".to_string(), limits.username)
+        );
+
+        let profile: UpdateProfileDto = dto(json!({ "username": username }));
+        assert_eq!(too_long(profile.check_lengths(&limits)).0, "username");
+    }
+
+    #[test]
+    fn rejects_long_bio() {
+        let limits = FieldLimits::default();
+        let profile: UpdateProfileDto = dto(json!({ "bio": "b".repeat(limits.bio + 1) }));
+        assert_eq!(
+            too_long(profile.check_lengths(&limits)),
+            ("bio".to_string(), limits.bio)
+        );
+    }
+
+    #[test]
+    fn omitted_optional_fields_pass() {
+        let profile: UpdateProfileDto = dto(json!({}));
+        assert!(profile.check_lengths(&FieldLimits::default()).is_ok());
+    }
+
+    #[test]
+    fn over_length_is_a_422_naming_the_field() {
+        let err = AppError::FieldTooLong {
+            field: "title".to_string(),
+            max: 255,
+        };
+        let response = axum::response::IntoResponse::into_response(err);
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY
+        );
+    }
+}
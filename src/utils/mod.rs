@@ -1,2 +1,8 @@
+pub mod field_limits;
 pub mod password;
-pub mod jwt;
\ No newline at end of file
+pub mod jwt;
+pub mod thumbnail;
+pub mod slug;
+pub mod channel_reader;
+pub mod etag;
+pub mod reading_time;
@@ -0,0 +1,33 @@
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Script and style bodies are markup, not prose
+static NON_TEXT_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?is)<script[^>]*>.*?</script>|<style[^>]*>.*?</style>").unwrap()
+});
+static TAG_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"<[^>]*>").unwrap());
+static ENTITY_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"&#?[a-zA-Z0-9]+;").unwrap());
+
+/// Words in a chapter's text once HTML tags and entities are stripped. Tags become
+/// spaces so `a<br>b` counts as two words; tokens without letters or digits are skipped.
+pub fn word_count(content: &str) -> i32 {
+    let text = NON_TEXT_RE.replace_all(content, " ");
+    let text = TAG_RE.replace_all(&text, " ");
+    let text = ENTITY_RE.replace_all(&text, " ");
+
+    let words = text
+        .split_whitespace()
+        .filter(|word| word.chars().any(char::is_alphanumeric))
+        .count();
+    i32::try_from(words).unwrap_or(i32::MAX)
+}
+
+/// Whole minutes to read `word_count` words at `words_per_minute`, rounded up so any
+/// text takes at least a minute
+pub fn reading_time_minutes(word_count: i32, words_per_minute: u32) -> i32 {
+    if word_count <= 0 || words_per_minute == 0 {
+        return 0;
+    }
+    let minutes = (word_count as u32).div_ceil(words_per_minute);
+    minutes as i32
+}
@@ -0,0 +1,71 @@
+use crate::errors::{AppError, AppResult};
+use sqlx::PgExecutor;
+use std::collections::HashSet;
+
+/// Longest base slug we generate, leaving room for a numeric suffix
+pub const MAX_SLUG_LENGTH: usize = 80;
+
+/// Lowercase ASCII words joined by hyphens. Non-Latin text is transliterated
+/// ("Café Ōkami" becomes "cafe-okami"); anything left over is dropped.
+pub fn slugify(text: &str) -> String {
+    let ascii = deunicode::deunicode(text).to_lowercase();
+    let mut slug = String::with_capacity(ascii.len());
+
+    for c in ascii.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    slug.truncate(MAX_SLUG_LENGTH);
+    slug.trim_end_matches('-').to_string()
+}
+
+/// Slug for `text` that no other row of `table` uses, appending `-2`, `-3`, ...
+/// on collision. `exclude_id` lets a row keep its own slug when re-titled.
+/// The unique index on `slug` remains the final guard against races.
+pub async fn unique_slug<'e>(
+    executor: impl PgExecutor<'e>,
+    table: &'static str,
+    text: &str,
+    fallback: &str,
+    exclude_id: Option<&str>,
+) -> AppResult<String> {
+    let base = match slugify(text) {
+        slug if slug.is_empty() => fallback.to_string(),
+        slug => slug,
+    };
+
+    let sql = format!(
+        r#"SELECT slug FROM "{}" WHERE (slug = $1 OR slug LIKE $1 || '-%') AND ($2::TEXT IS NULL OR id <> $2)"#,
+        table
+    );
+    let taken: HashSet<String> = sqlx::query_scalar::<_, String>(&sql)
+        .bind(&base)
+        .bind(exclude_id)
+        .fetch_all(executor)
+        .await?
+        .into_iter()
+        .collect();
+
+    if !taken.contains(&base) {
+        return Ok(base);
+    }
+
+    let suffix = (2..)
+        .find(|n| !taken.contains(&format!("{}-{}", base, n)))
+        .unwrap_or(2);
+    Ok(format!("{}-{}", base, suffix))
+}
+
+/// A concurrent writer claimed the same slug between lookup and insert
+pub fn map_slug_conflict(e: sqlx::Error) -> AppError {
+    match e {
+        sqlx::Error::Database(ref db_err) if db_err.is_unique_violation() => {
+            AppError::Conflict("Slug is already in use, please retry".to_string())
+        }
+        e => e.into(),
+    }
+}
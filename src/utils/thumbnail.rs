@@ -0,0 +1,23 @@
+use image::codecs::webp::WebPEncoder;
+use image::DynamicImage;
+
+/// Width of generated preview images, in pixels
+pub const THUMBNAIL_WIDTH: u32 = 300;
+
+/// Decode an image and re-encode it as WebP no wider than `max_width`, keeping the aspect ratio.
+/// Returns `None` when the bytes can't be decoded or encoded.
+pub fn webp_thumbnail(bytes: &[u8], max_width: u32) -> Option<Vec<u8>> {
+    let image = image::load_from_memory(bytes).ok()?;
+    let image = if image.width() > max_width {
+        image.thumbnail(max_width, u32::MAX)
+    } else {
+        image
+    };
+
+    // The WebP encoder only accepts 8-bit RGB(A)
+    let rgba = DynamicImage::ImageRgba8(image.to_rgba8());
+    let mut buffer = Vec::new();
+    rgba.write_with_encoder(WebPEncoder::new_lossless(&mut buffer))
+        .ok()?;
+    Some(buffer)
+}